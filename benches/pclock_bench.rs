@@ -0,0 +1,99 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use threshold::*;
+
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+// Keeps `VERSION_COUNT` versions of a clock, each one with one more event than
+// the previous one.
+fn clock_versions(c: &mut Criterion) {
+    let clock = gen::clock();
+    memory::report("clock_versions", || gen::clock_versions(clock.clone()));
+    c.bench_function("clock_versions", move |b| {
+        b.iter(|| gen::clock_versions(clock.clone()))
+    });
+}
+
+fn pclock_versions(c: &mut Criterion) {
+    let pclock = PClock::from(gen::clock());
+    memory::report("pclock_versions", || gen::pclock_versions(pclock.clone()));
+    c.bench_function("pclock_versions", move |b| {
+        b.iter(|| gen::pclock_versions(pclock.clone()))
+    });
+}
+
+criterion_group!(
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = clock_versions, pclock_versions
+);
+criterion_main!(benches);
+
+mod gen {
+    use threshold::*;
+
+    const VERSION_COUNT: u64 = 1_000;
+    const ACTOR_COUNT: u64 = 10_000;
+    const SEQ: u64 = 100;
+
+    pub fn clock() -> VClock<u64> {
+        VClock::with_seq(0..ACTOR_COUNT, SEQ)
+    }
+
+    pub fn clock_versions(mut clock: VClock<u64>) -> Vec<VClock<u64>> {
+        (0..VERSION_COUNT)
+            .map(|version| {
+                clock.next(&actor(version));
+                clock.clone()
+            })
+            .collect()
+    }
+
+    pub fn pclock_versions(
+        mut pclock: PClock<u64, MaxSet>,
+    ) -> Vec<PClock<u64, MaxSet>> {
+        (0..VERSION_COUNT)
+            .map(|version| {
+                pclock.next(&actor(version));
+                pclock.clone()
+            })
+            .collect()
+    }
+
+    // Actor that generates an event in some version.
+    fn actor(version: u64) -> u64 {
+        (version * 7919) % ACTOR_COUNT
+    }
+}
+
+mod memory {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Number of bytes currently allocated.
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Allocator that keeps track of the number of bytes allocated.
+    pub struct CountingAllocator;
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+            System.alloc(layout)
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            System.dealloc(ptr, layout)
+        }
+    }
+
+    /// Reports the number of bytes retained by the value returned by `f`.
+    pub fn report<T, F: FnOnce() -> T>(name: &str, f: F) {
+        let before = ALLOCATED.load(Ordering::Relaxed);
+        let value = f();
+        let retained = ALLOCATED.load(Ordering::Relaxed).saturating_sub(before);
+        drop(value);
+        println!("{}: {} KiB retained", name, retained / 1024);
+    }
+}
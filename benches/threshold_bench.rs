@@ -1,4 +1,6 @@
 use criterion::{criterion_group, criterion_main, Criterion};
+use threshold::clock::Op;
+use threshold::*;
 
 fn multiset_threshold(c: &mut Criterion) {
     let (multiset, threshold) = gen::multiset();
@@ -7,18 +9,199 @@ fn multiset_threshold(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, multiset_threshold);
+fn clock_from(c: &mut Criterion) {
+    let entries = gen::entries();
+    c.bench_function("clock_from", move |b| {
+        b.iter_with_setup(|| entries.clone(), VClock::from)
+    });
+}
+
+fn clock_from_vec_sized(c: &mut Criterion) {
+    let entries = gen::entries();
+    c.bench_function("clock_from_vec_sized", move |b| {
+        b.iter_with_setup(|| entries.clone(), VClock::from_vec_sized)
+    });
+}
+
+fn clock_join_large(c: &mut Criterion) {
+    let (clock, peer) = gen::peer_clocks();
+    c.bench_function("clock_join_large", move |b| {
+        b.iter_with_setup(|| clock.clone(), |mut clock| clock.join(&peer))
+    });
+}
+
+fn clock_subtracted(c: &mut Criterion) {
+    let (clock, other) = gen::clocks();
+    c.bench_function("subtracted", move |b| {
+        b.iter(|| clock.subtracted(&other))
+    });
+}
+
+fn clock_subtracted_new_actors(c: &mut Criterion) {
+    // all actors are missing in the other clock, so all events are collected
+    let (clock, _) = gen::clocks();
+    let other = AEClock::new();
+    c.bench_function("subtracted_new_actors", move |b| {
+        b.iter(|| clock.subtracted(&other))
+    });
+}
+
+fn clock_missing_count(c: &mut Criterion) {
+    let (clock, other) = gen::clocks();
+    c.bench_function("missing_count", move |b| {
+        b.iter(|| clock.missing_count(&other))
+    });
+}
+
+// The ops of each batch are delivered out of order within windows of 100 ops
+// (i.e. about one op per actor, and so mostly in order per actor) and of
+// 10_000 ops (i.e. the whole batch).
+const OP_WINDOWS: [usize; 2] = [100, 10_000];
+
+fn clock_apply_ops(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "apply_ops",
+        |b, window| {
+            let ops = gen::ops(*window);
+            b.iter_with_setup(
+                || ops.clone(),
+                |ops| {
+                    let mut clock = AEClock::new();
+                    clock.apply_ops(ops)
+                },
+            )
+        },
+        OP_WINDOWS.to_vec(),
+    );
+}
+
+fn clock_apply_ops_individually(c: &mut Criterion) {
+    c.bench_function_over_inputs(
+        "apply_ops_individually",
+        |b, window| {
+            let ops = gen::ops(*window);
+            b.iter_with_setup(
+                || ops.clone(),
+                |ops| {
+                    let mut clock = AEClock::new();
+                    for op in ops {
+                        match op {
+                            Op::AddDot(actor, seq) => {
+                                clock.add(&actor, seq);
+                            }
+                            Op::AddRange(actor, start, end) => {
+                                clock.add_range(&actor, start, end);
+                            }
+                            Op::JoinEntry(actor, eset) => {
+                                clock.join(&Clock::from(vec![(actor, eset)]));
+                            }
+                        }
+                    }
+                    clock
+                },
+            )
+        },
+        OP_WINDOWS.to_vec(),
+    );
+}
+
+fn above_range_set_join(c: &mut Criterion) {
+    // the time should not depend on the size of the ranges
+    c.bench_function_over_inputs(
+        "above_range_set_join",
+        |b, range_size| {
+            let (above_range_set, other) = gen::range_sets(*range_size);
+            b.iter_with_setup(
+                || above_range_set.clone(),
+                |mut above_range_set| {
+                    above_range_set.join(&other);
+                    above_range_set
+                },
+            )
+        },
+        vec![100, 1_000_000],
+    );
+}
+
+fn above_range_set_is_event(c: &mut Criterion) {
+    let above_range_set = gen::disjoint_range_set();
+    let max_event = above_range_set.max_event();
+    c.bench_function("above_range_set_is_event", move |b| {
+        // check 1000 events spread over all ranges
+        b.iter(|| {
+            (1..=1000)
+                .filter(|i| above_range_set.is_event(i * max_event / 1000))
+                .count()
+        })
+    });
+}
+
+// only the first events are iterated, so that the cost of cloning the set
+// (before iterating it) is visible
+fn below_exset_event_iter(c: &mut Criterion) {
+    let below_exset = gen::below_exset();
+    c.bench_function("below_exset_event_iter", move |b| {
+        b.iter(|| below_exset.clone().event_iter().take(100).count())
+    });
+}
+
+fn below_exset_iter(c: &mut Criterion) {
+    let below_exset = gen::below_exset();
+    c.bench_function("below_exset_iter", move |b| {
+        b.iter(|| below_exset.iter().take(100).count())
+    });
+}
+
+fn above_exset_subtracted(c: &mut Criterion) {
+    // the time should not depend on the highest contiguous event
+    c.bench_function_over_inputs(
+        "above_exset_subtracted",
+        |b, max| {
+            let (above_exset, other) = gen::above_exsets(*max);
+            b.iter(|| above_exset.subtracted(&other))
+        },
+        vec![1_000, 1_000_000],
+    );
+}
+
+criterion_group!(
+    benches,
+    multiset_threshold,
+    clock_from,
+    clock_from_vec_sized,
+    clock_join_large,
+    clock_subtracted,
+    clock_subtracted_new_actors,
+    clock_missing_count,
+    clock_apply_ops,
+    clock_apply_ops_individually,
+    above_range_set_join,
+    above_range_set_is_event,
+    below_exset_event_iter,
+    below_exset_iter,
+    above_exset_subtracted
+);
 criterion_main!(benches);
 
 mod gen {
     use rand::prelude::*;
+    use threshold::clock::Op;
     use threshold::multiset::MultiSet;
+    use threshold::*;
 
     const SEED: u64 = 1002191092;
     const THRESHOLD: u64 = 5;
     const ADD_COUNT: u32 = 10;
     const ELEM_COUNT: u32 = 100;
     const ELEM_SIZE: u32 = 2000;
+    const ACTOR_COUNT: u64 = 10;
+    const PREFIX_SIZE: u64 = 100_000;
+    const OP_COUNT: u64 = 10_000;
+    const OP_ACTOR_COUNT: u64 = 100;
+    const EXTRA_COUNT: u64 = 100;
+    const RANGE_COUNT: u64 = 10_000;
+    const ENTRY_COUNT: u64 = 10_000;
+    const PEER_ACTOR_COUNT: u64 = 1000;
 
     pub fn multiset() -> (MultiSet<String, u64>, u64) {
         let mut rng = StdRng::seed_from_u64(SEED);
@@ -38,6 +221,124 @@ mod gen {
         (multiset, THRESHOLD)
     }
 
+    pub fn clocks() -> (AEClock<u64>, AEClock<u64>) {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let mut clock = AEClock::new();
+        let mut other = AEClock::new();
+
+        for actor in 0..ACTOR_COUNT {
+            // both clocks share a large prefix, and `clock` is ahead of `other`
+            let other_seq = rng.gen_range(1, PREFIX_SIZE);
+            let seq = rng.gen_range(other_seq, PREFIX_SIZE + 1);
+            clock.add_range(&actor, 1, seq);
+            other.add_range(&actor, 1, other_seq);
+        }
+
+        (clock, other)
+    }
+
+    /// Returns two sets with highest contiguous event close to `max`, and
+    /// `EXTRA_COUNT` events (some of them extras) in their difference.
+    pub fn above_exsets(max: u64) -> (AboveExSet, AboveExSet) {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let extras = (max + 2..max + 2 * EXTRA_COUNT).step_by(2);
+        let above_exset = AboveExSet::from(max, extras.clone());
+        let other_extras: Vec<_> = extras.filter(|_| rng.gen()).collect();
+        let other = AboveExSet::from(max - EXTRA_COUNT / 2, other_extras);
+        (above_exset, other)
+    }
+
+    /// Returns `ENTRY_COUNT` entries, one per actor.
+    pub fn entries() -> Vec<(u64, MaxSet)> {
+        (0..ENTRY_COUNT)
+            .map(|actor| (actor, MaxSet::from_event(actor + 1)))
+            .collect()
+    }
+
+    /// Returns a clock with a single actor, and a (peer) clock with
+    /// `PEER_ACTOR_COUNT` actors.
+    pub fn peer_clocks() -> (VClock<u64>, VClock<u64>) {
+        let clock = VClock::from(vec![(0, MaxSet::from_event(1))]);
+        let peer = VClock::from(
+            (0..PEER_ACTOR_COUNT)
+                .map(|actor| (actor, MaxSet::from_event(actor + 1))),
+        );
+        (clock, peer)
+    }
+
+    /// Returns a set with `PREFIX_SIZE` events, half of them exceptions.
+    pub fn below_exset() -> BelowExSet {
+        let exs = (1..PREFIX_SIZE).step_by(2);
+        BelowExSet::from(PREFIX_SIZE, exs)
+    }
+
+    /// Returns two sets with `RANGE_COUNT` ranges (of up to `range_size`
+    /// events) in total.
+    pub fn range_sets(range_size: u64) -> (AboveRangeSet, AboveRangeSet) {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let mut above_range_set = AboveRangeSet::new();
+        let mut other = AboveRangeSet::new();
+
+        // event 1 is missing in both sets, so that all events are kept in
+        // (large) ranges; each range goes to one of the sets, and consecutive
+        // ranges are either adjacent or separated by a single event
+        let mut start = 2;
+        for _ in 0..RANGE_COUNT {
+            let end = start + rng.gen_range(1, range_size);
+            if rng.gen() {
+                above_range_set.add_event_range(start, end);
+            } else {
+                other.add_event_range(start, end);
+            }
+            start = end + rng.gen_range(1, 3);
+        }
+
+        (above_range_set, other)
+    }
+
+    /// Returns a set with `RANGE_COUNT` ranges, all separated by a gap (and
+    /// so none of them is merged).
+    pub fn disjoint_range_set() -> AboveRangeSet {
+        let mut above_range_set = AboveRangeSet::new();
+        for i in 1..=RANGE_COUNT {
+            above_range_set.add_event_range(i * 10, i * 10 + 4);
+        }
+        above_range_set
+    }
+
+    /// Returns `OP_COUNT` ops, delivered out of order within windows of
+    /// `window` ops.
+    pub fn ops(window: usize) -> Vec<Op<u64, AboveExSet>> {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let mut next = vec![1; OP_ACTOR_COUNT as usize];
+
+        // each actor's events are generated in order, and delivered out of
+        // order
+        let mut ops: Vec<_> = (0..OP_COUNT)
+            .map(|_| {
+                let actor = rng.gen_range(0, OP_ACTOR_COUNT);
+                let seq = next[actor as usize];
+                match rng.gen_range(0, 10) {
+                    0 => {
+                        next[actor as usize] += 5;
+                        Op::AddRange(actor, seq, seq + 4)
+                    }
+                    1 => {
+                        next[actor as usize] += 1;
+                        Op::JoinEntry(actor, AboveExSet::from_event(seq))
+                    }
+                    _ => {
+                        next[actor as usize] += 1;
+                        Op::AddDot(actor, seq)
+                    }
+                }
+            })
+            .collect();
+        ops.chunks_mut(window)
+            .for_each(|chunk| chunk.shuffle(&mut rng));
+        ops
+    }
+
     fn elements(rng: &mut StdRng) -> Vec<String> {
         (0..ELEM_COUNT)
             .map(|_| format!("{:?}", element(rng)))
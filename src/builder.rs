@@ -0,0 +1,182 @@
+//! This module contains builders for event sets and clocks.
+//!
+//! Builders allow describing an event set with a mix of single events and
+//! ranges of events. All events and ranges are validated and then added in
+//! ascending order, so that the event set is constructed in a single pass.
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! // A: 1..=100 plus 150 plus 200..=203
+//! // B: 1, 2, 3
+//! let mut builder = Clock::builder();
+//! builder.actor("A").range(1, 100).event(150).range(200, 203);
+//! builder.actor("B").events(vec![1, 2, 3]);
+//! let clock: AEClock<_> = builder.build().expect("valid clock");
+//!
+//! assert_eq!(
+//!     clock.snapshot(),
+//!     vec![("A", 100, vec![150, 200, 201, 202, 203]), ("B", 3, vec![])]
+//! );
+//! ```
+
+use crate::*;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Error returned when building an invalid event set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    /// Event 0 is not a valid event.
+    ZeroEvent,
+    /// The start of the range is higher than its end.
+    InvalidRange { start: u64, end: u64 },
+    /// Some event was added more than once.
+    Overlap { event: u64 },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::ZeroEvent => write!(f, "event 0 is not a valid event"),
+            BuildError::InvalidRange { start, end } => {
+                write!(f, "invalid range: {} is higher than {}", start, end)
+            }
+            BuildError::Overlap { event } => {
+                write!(f, "event {} was added more than once", event)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct EventSetBuilder<E: EventSet> {
+    /// Ranges of events (both ends included) in the order they were added
+    ranges: Vec<(u64, u64)>,
+    phantom: PhantomData<E>,
+}
+
+impl<E: EventSet> EventSetBuilder<E> {
+    /// Returns a new `EventSetBuilder` instance.
+    pub fn new() -> Self {
+        EventSetBuilder {
+            ranges: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Adds an event to the builder.
+    pub fn event(&mut self, event: u64) -> &mut Self {
+        self.ranges.push((event, event));
+        self
+    }
+
+    /// Adds a range of events (both ends included) to the builder.
+    pub fn range(&mut self, start: u64, end: u64) -> &mut Self {
+        self.ranges.push((start, end));
+        self
+    }
+
+    /// Adds several events to the builder.
+    pub fn events<I: IntoIterator<Item = u64>>(
+        &mut self,
+        iter: I,
+    ) -> &mut Self {
+        self.ranges
+            .extend(iter.into_iter().map(|event| (event, event)));
+        self
+    }
+
+    /// Builds the event set.
+    /// Fails if some event is 0, some range is invalid, or some event was
+    /// added more than once.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let eset: BelowExSet = EventSetBuilder::new()
+    ///     .range(1, 3)
+    ///     .event(5)
+    ///     .build()
+    ///     .expect("valid event set");
+    /// assert_eq!(eset, BelowExSet::from_events(vec![1, 2, 3, 5]));
+    ///
+    /// let result = EventSetBuilder::<BelowExSet>::new()
+    ///     .range(1, 3)
+    ///     .event(2)
+    ///     .build();
+    /// assert_eq!(result, Err(BuildError::Overlap { event: 2 }));
+    /// ```
+    pub fn build(&self) -> Result<E, BuildError> {
+        let mut eset = E::new();
+        for (start, end) in Self::normalize(self.ranges.clone())? {
+            eset.add_event_range(start, end);
+        }
+        Ok(eset)
+    }
+
+    /// Validates all ranges, returning them sorted (ASC) with adjacent ranges
+    /// merged.
+    fn normalize(
+        mut ranges: Vec<(u64, u64)>,
+    ) -> Result<Vec<(u64, u64)>, BuildError> {
+        ranges.sort_unstable();
+
+        let mut result: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            if start == 0 {
+                return Err(BuildError::ZeroEvent);
+            }
+            if start > end {
+                return Err(BuildError::InvalidRange { start, end });
+            }
+            match result.last_mut() {
+                Some((_, last_end)) if start <= *last_end => {
+                    return Err(BuildError::Overlap { event: start });
+                }
+                Some((_, last_end)) if start == *last_end + 1 => {
+                    // merge adjacent ranges
+                    *last_end = end;
+                }
+                _ => result.push((start, end)),
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClockBuilder<A: Actor, E: EventSet> {
+    /// Mapping from actor identifier to an event set builder
+    builders: BTreeMap<A, EventSetBuilder<E>>,
+}
+
+impl<A: Actor, E: EventSet> ClockBuilder<A, E> {
+    /// Returns a new `ClockBuilder` instance.
+    pub fn new() -> Self {
+        ClockBuilder {
+            builders: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the event set builder associated with some `actor`.
+    pub fn actor(&mut self, actor: A) -> &mut EventSetBuilder<E> {
+        self.builders.entry(actor).or_default()
+    }
+
+    /// Builds the clock.
+    /// Fails if any of the event sets fails to build.
+    pub fn build(&self) -> Result<Clock<A, E>, BuildError> {
+        let entries = self
+            .builders
+            .iter()
+            .map(|(actor, builder)| Ok((actor.clone(), builder.build()?)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Clock::from(entries))
+    }
+}
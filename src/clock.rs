@@ -17,11 +17,17 @@
 //! assert!(clock_b.contains(&actor_a, event));
 //! ```
 
+use crate::hash::HashMap;
 use crate::*;
 use serde::{Deserialize, Serialize};
-use std::collections::hash_map::{self, HashMap};
+use std::cmp;
+use std::collections::hash_map::{self, DefaultHasher};
+use std::convert::TryFrom;
 use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
 use std::iter::FromIterator;
+use std::ops::Sub;
+use std::str::FromStr;
 
 // A Vector Clock is `Clock` with `MaxSet` as `EventSet`.
 pub type VClock<A> = Clock<A, MaxSet>;
@@ -31,11 +37,19 @@ pub type AEClock<A> = Clock<A, AboveExSet>;
 pub type ARClock<A> = Clock<A, AboveRangeSet>;
 // A Below Exception Clock is `Clock` with `BelowExSet` as `EventSet`.
 pub type BEClock<A> = Clock<A, BelowExSet>;
+// A Below Range Clock is `Clock` with `BelowRangeSet` as `EventSet`.
+pub type BRClock<A> = Clock<A, BelowRangeSet>;
+// An Explicit Clock is `Clock` with `ExplicitSet` as `EventSet`.
+pub type XClock<A> = Clock<A, ExplicitSet>;
 
-#[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct Clock<A: Actor, E: EventSet> {
     /// Mapping from actor identifier to an event set
     clock: HashMap<A, E>,
+    /// Number of mutations that changed the clock (not part of equality, and
+    /// not serialized)
+    #[serde(skip)]
+    version: u64,
 }
 
 impl<A: Actor, E: EventSet> Clock<A, E> {
@@ -43,7 +57,8 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         Clock {
-            clock: HashMap::new(),
+            clock: HashMap::default(),
+            version: 0,
         }
     }
 
@@ -65,9 +80,82 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     pub fn with<I: IntoIterator<Item = A>>(iter: I) -> Self {
         Clock {
             clock: iter.into_iter().map(|actor| (actor, E::new())).collect(),
+            version: 0,
         }
     }
 
+    /// Returns a new `Clock` mapping each actor to an entry containing all
+    /// events from 1 to `seq`.
+    /// `Clock::with` is the same as `Clock::with_seq` with `seq` 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let actors = vec!["A", "B"];
+    /// let vclock = VClock::with_seq(actors, 5);
+    /// assert_eq!(
+    ///     vclock.frontier(),
+    ///     VClock::from(vec![("A", MaxSet::from(5)), ("B", MaxSet::from(5))])
+    /// );
+    /// ```
+    pub fn with_seq<I: IntoIterator<Item = A>>(iter: I, seq: u64) -> Self {
+        Clock {
+            clock: iter
+                .into_iter()
+                .map(|actor| {
+                    let eset = if seq == 0 {
+                        E::new()
+                    } else {
+                        E::from_event_range(1, seq)
+                    };
+                    (actor, eset)
+                })
+                .collect(),
+            version: 0,
+        }
+    }
+
+    /// Returns a new `Clock` mapping each actor in `other` to a bottom entry,
+    /// regardless of the event set used by `other`. This is useful to seed an
+    /// accumulator (to which clocks are then added) with the actors of some
+    /// other clock, e.g. a frontier.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let frontier = VClock::from(vec![("A", MaxSet::from(5)), ("B", MaxSet::from(3))]);
+    /// let mut acc = AEClock::seeded_from(&frontier);
+    /// assert_eq!(acc.len(), 2);
+    /// assert!(acc.is_bottom());
+    ///
+    /// acc.add(&"A", 2);
+    /// assert!(acc.contains(&"A", 2));
+    /// assert_eq!(acc.frontier_threshold(2), Some(0));
+    /// ```
+    pub fn seeded_from<F: EventSet>(other: &Clock<A, F>) -> Self {
+        Clock::with(other.clock.keys().cloned())
+    }
+
+    /// Returns a new `ClockBuilder` instance.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut builder = Clock::builder();
+    /// builder.actor("A").range(1, 10).event(15);
+    /// let clock: AEClock<_> = builder.build().expect("valid clock");
+    ///
+    /// assert!(clock.contains(&"A", 10));
+    /// assert!(!clock.contains(&"A", 11));
+    /// assert!(clock.contains(&"A", 15));
+    /// ```
+    pub fn builder() -> ClockBuilder<A, E> {
+        ClockBuilder::new()
+    }
+
     /// Creates a `Clock` from an iterator of tuples (actor identifier and event
     /// set).
     ///
@@ -85,9 +173,44 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     pub fn from<I: IntoIterator<Item = (A, E)>>(iter: I) -> Self {
         Clock {
             clock: HashMap::from_iter(iter),
+            version: 0,
         }
     }
 
+    /// Creates a `Clock` from a vector of tuples (actor identifier and event
+    /// set), allocating space for all actors upfront. As opposed to
+    /// `Clock::from`, where the last event set of a repeated actor wins, the
+    /// event sets of a repeated actor are joined.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a1 = ("A", AboveExSet::from_events(vec![1, 2]));
+    /// let a2 = ("A", AboveExSet::from_events(vec![4]));
+    /// let b = ("B", AboveExSet::from_events(vec![1]));
+    /// let clock = Clock::from_vec_sized(vec![a1, b, a2]);
+    ///
+    /// assert_eq!(clock.len(), 2);
+    /// assert!(clock.contains(&"A", 2));
+    /// assert!(clock.contains(&"A", 4));
+    /// ```
+    pub fn from_vec_sized(vec: Vec<(A, E)>) -> Self {
+        let mut clock =
+            HashMap::with_capacity_and_hasher(vec.len(), Default::default());
+        for (actor, eset) in vec {
+            match clock.entry(actor) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(eset);
+                }
+                hash_map::Entry::Occupied(mut entry) => {
+                    entry.get_mut().join(&eset);
+                }
+            }
+        }
+        Clock { clock, version: 0 }
+    }
+
     /// Returns the number of actors in the clock.
     ///
     /// # Examples
@@ -123,6 +246,33 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
         self.clock.is_empty()
     }
 
+    /// Checks that a clock has no events, i.e. that it has no entries or that
+    /// all of its entries are empty (see `EventSet::is_empty`).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = AEClock::with(vec!["A", "B"]);
+    /// assert!(!clock.is_empty());
+    /// assert!(clock.is_bottom());
+    ///
+    /// // an entry with only extras is not empty
+    /// clock.add(&"A", 3);
+    /// assert_eq!(clock.frontier().get(&"A"), Some(&MaxSet::from(0)));
+    /// assert!(!clock.is_bottom());
+    ///
+    /// assert!(AEClock::<&str>::new().is_bottom());
+    ///
+    /// let mut vclock = VClock::with(vec!["A", "B"]);
+    /// assert!(vclock.is_bottom());
+    /// vclock.next(&"B");
+    /// assert!(!vclock.is_bottom());
+    /// ```
+    pub fn is_bottom(&self) -> bool {
+        self.clock.values().all(|eset| eset.is_empty())
+    }
+
     /// Returns the next event for the `actor` while updating its entry in the
     /// clock.
     ///
@@ -144,9 +294,15 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// assert_eq!(next, 3);
     /// ```
     pub fn next(&mut self, actor: &A) -> u64 {
+        self.bump();
         self.upsert(actor, |eset| eset.next_event(), || (E::from_event(1), 1))
     }
 
+    /// Records that the clock has changed.
+    fn bump(&mut self) {
+        self.version += 1;
+    }
+
     /// If the actor is in already the clock, its entry is updated using
     /// function `map`. Otherwise, the output of `default` is inserted.
     fn upsert<F, D, R>(&mut self, actor: &A, mut map: F, default: D) -> R
@@ -178,7 +334,7 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// clock.add(&actor_a, 1);
     /// clock.add(&actor_a, 2);
     /// let max_set = clock.get(&actor_a).expect("there should be an event set");
-    /// let mut iter = max_set.clone().event_iter();
+    /// let mut iter = max_set.iter();
     ///
     /// assert_eq!(iter.next(), Some(1));
     /// assert_eq!(iter.next(), Some(2));
@@ -191,6 +347,10 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// Retrieves (a mutable reference to) the event set associated with some
     /// `actor`.
     ///
+    /// The clock can't tell whether the event set is changed through the
+    /// returned reference, and so, on purpose, its version (see
+    /// `Clock::version`) is incremented whenever the actor is in the clock.
+    ///
     /// # Examples
     /// ```
     /// use threshold::*;
@@ -206,7 +366,7 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     ///     .get_mut(&actor_a)
     ///     .expect("there should be an event set");
     /// max_set.add_event(3);
-    /// let mut iter = max_set.clone().event_iter();
+    /// let mut iter = max_set.iter();
     ///
     /// assert_eq!(iter.next(), Some(1));
     /// assert_eq!(iter.next(), Some(2));
@@ -214,13 +374,64 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// assert_eq!(iter.next(), None);
     /// ```
     pub fn get_mut(&mut self, actor: &A) -> Option<&mut E> {
-        self.clock.get_mut(actor)
+        // the event set may be changed through the returned reference, so the
+        // clock is conservatively considered changed
+        let eset = self.clock.get_mut(actor);
+        if eset.is_some() {
+            self.version += 1;
+        }
+        eset
+    }
+
+    /// Removes an actor from the clock, returning its event set if the actor
+    /// was in the clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::from(vec![("A", MaxSet::from(2)), ("B", MaxSet::from(3))]);
+    /// assert_eq!(clock.remove_actor(&"A"), Some(MaxSet::from(2)));
+    /// assert_eq!(clock.remove_actor(&"A"), None);
+    /// assert_eq!(clock.len(), 1);
+    /// assert!(!clock.contains(&"A", 1));
+    /// ```
+    pub fn remove_actor(&mut self, actor: &A) -> Option<E> {
+        let eset = self.clock.remove(actor);
+        if eset.is_some() {
+            self.bump();
+        }
+        eset
+    }
+
+    /// Retains only the actors for which `f` returns `true`, removing the
+    /// others from the clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::with_seq(vec!["A", "B", "C"], 5);
+    /// clock.retain_actors(|actor| *actor != "B");
+    /// assert_eq!(clock.len(), 2);
+    /// assert_eq!(clock.frontier(), VClock::with_seq(vec!["A", "C"], 5));
+    /// ```
+    pub fn retain_actors<F: FnMut(&A) -> bool>(&mut self, mut f: F) {
+        let len = self.clock.len();
+        self.clock.retain(|actor, _| f(actor));
+        if self.clock.len() != len {
+            self.bump();
+        }
     }
 
     /// Adds an event to the clock.
     /// If the clock did not have this event present, `true` is returned.
     /// If the clock did have this event present, `false` is returned.
     ///
+    /// Events that the event set can't store (see `EventSet::MAX_SEQ`) are
+    /// not added either, and so `false` is returned; `Clock::try_add` reports
+    /// them as errors instead.
+    ///
     /// # Examples
     /// ```
     /// use threshold::*;
@@ -238,15 +449,54 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// clock.add(&actor_b, 1);
     /// assert!(clock.contains(&actor_b, 1));
     /// ```
-    pub fn add(&mut self, actor: &A, seq: u64) -> bool {
-        self.upsert(
+    pub fn add(&mut self, actor: &A, seq: impl IntoSeq) -> bool {
+        let seq = seq.into_seq();
+        if seq > E::MAX_SEQ {
+            // don't insert an entry for the actor if it's not in the clock
+            return false;
+        }
+        let added = self.upsert(
             actor,
             |eset| eset.add_event(seq),
             || (E::from_event(seq), true),
-        )
+        );
+        if added {
+            self.bump();
+        }
+        added
+    }
+
+    /// Adds an event to the clock, as `Clock::add` does, returning an error
+    /// if the event set can't store it (see `EventSet::MAX_SEQ`). In that
+    /// case, the clock is left unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = Clock::<_, MaxSetOf<u32>>::new();
+    /// assert_eq!(clock.try_add(&"A", 10), Ok(true));
+    /// assert_eq!(clock.try_add(&"A", 10), Ok(false));
+    ///
+    /// let error = SeqOutOfRange { seq: 1 << 32, max: u32::MAX.into() };
+    /// assert_eq!(clock.try_add(&"B", 1 << 32), Err(error));
+    /// assert_eq!(clock.get(&"B"), None);
+    /// ```
+    pub fn try_add(
+        &mut self,
+        actor: &A,
+        seq: impl IntoSeq,
+    ) -> Result<bool, SeqOutOfRange> {
+        let seq = seq.into_seq();
+        check_seq::<E>(seq)?;
+        Ok(self.add(actor, seq))
     }
 
     /// Adds a range of events to the clock.
+    /// Returns `true` if a new event was added. As with `Clock::add`, events
+    /// that the event set can't store are not added (see
+    /// `Clock::try_add_range`).
+    ///
     /// # Examples
     /// ```
     /// use threshold::*;
@@ -260,12 +510,55 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// assert!(clock_a.contains(&actor_a, 11));
     /// assert!(!clock_a.contains(&actor_a, 21));
     /// ```
-    pub fn add_range(&mut self, actor: &A, start: u64, end: u64) -> bool {
-        self.upsert(
+    pub fn add_range(
+        &mut self,
+        actor: &A,
+        start: impl IntoSeq,
+        end: impl IntoSeq,
+    ) -> bool {
+        let (start, end) = (start.into_seq(), end.into_seq());
+        if start > end || end > E::MAX_SEQ {
+            // don't insert an entry for the actor if it's not in the clock
+            return false;
+        }
+        let added = self.upsert(
             actor,
             |eset| eset.add_event_range(start, end),
             || (E::from_event_range(start, end), true),
-        )
+        );
+        if added {
+            self.bump();
+        }
+        added
+    }
+
+    /// Adds a range of events to the clock, as `Clock::add_range` does,
+    /// returning an error if the event set can't store the end of the range
+    /// (see `EventSet::MAX_SEQ`). In that case, the clock is left unchanged.
+    /// An empty range is never an error.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = Clock::<_, MaxSetOf<u32>>::new();
+    /// assert_eq!(clock.try_add_range(&"A", 1, 10), Ok(true));
+    ///
+    /// let error = SeqOutOfRange { seq: 1 << 32, max: u32::MAX.into() };
+    /// assert_eq!(clock.try_add_range(&"A", 1, 1 << 32), Err(error));
+    /// assert_eq!(clock.get(&"A").unwrap().frontier(), 10);
+    /// ```
+    pub fn try_add_range(
+        &mut self,
+        actor: &A,
+        start: impl IntoSeq,
+        end: impl IntoSeq,
+    ) -> Result<bool, SeqOutOfRange> {
+        let (start, end) = (start.into_seq(), end.into_seq());
+        if start <= end {
+            check_seq::<E>(end)?;
+        }
+        Ok(self.add_range(actor, start, end))
     }
 
     /// Checks if an event is part of the clock.
@@ -287,13 +580,64 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// assert!(clock.contains(&actor_a, 2));
     /// assert!(clock.contains(&actor_a, 3));
     /// ```
-    pub fn contains(&self, actor: &A, seq: u64) -> bool {
-        self.clock
-            .get(actor)
-            .map_or(false, |eset| eset.is_event(seq))
+    pub fn contains(&self, actor: &A, seq: impl IntoSeq) -> bool {
+        let seq = seq.into_seq();
+        self.clock.get(actor).is_some_and(|eset| eset.is_event(seq))
+    }
+
+    /// Checks if all events within `[start, end]` (both ends included) from
+    /// some `actor` are part of the clock. See `EventSet::contains_all_range`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = AEClock::new();
+    /// clock.add_range(&"A", 1, 10);
+    /// clock.add_range(&"A", 20, 30);
+    ///
+    /// assert!(clock.contains_range(&"A", 5, 10));
+    /// assert!(clock.contains_range(&"A", 20, 30));
+    /// assert!(!clock.contains_range(&"A", 5, 20));
+    /// assert!(!clock.contains_range(&"B", 1, 1));
+    /// assert!(clock.contains_range(&"B", 2, 1));
+    /// ```
+    pub fn contains_range(
+        &self,
+        actor: &A,
+        start: impl IntoSeq,
+        end: impl IntoSeq,
+    ) -> bool {
+        let start = start.into_seq();
+        let end = end.into_seq();
+        // an empty range is always contained, even if the actor is not
+        start > end
+            || self
+                .clock
+                .get(actor)
+                .is_some_and(|eset| eset.contains_all_range(start, end))
+    }
+
+    /// Returns the highest event from some `actor` in the clock, or 0 if the
+    /// actor is not in the clock. See `EventSet::max_event`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = AEClock::new();
+    /// clock.add_range(&"A", 1, 10);
+    /// clock.add(&"A", 20);
+    ///
+    /// assert_eq!(clock.max_event(&"A"), 20);
+    /// assert_eq!(clock.max_event(&"B"), 0);
+    /// ```
+    pub fn max_event(&self, actor: &A) -> u64 {
+        self.clock.get(actor).map_or(0, |eset| eset.max_event())
     }
 
     /// Returns the clock frontier.
+    /// The frontier of an empty clock is an empty `VClock`.
     ///
     /// # Examples
     /// ```
@@ -319,6 +663,8 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
 
     /// By looking at this `Clock`'s frontier, it computes the event that's been
     /// generated in at least `threshold` actors.
+    /// Returns `None` if the clock has fewer than `threshold` actors; in
+    /// particular, it's always `None` for an empty clock.
     ///
     /// # Examples
     /// ```
@@ -352,17 +698,64 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// ```
     pub fn frontier_threshold(&self, threshold: usize) -> Option<u64> {
         debug_assert!(threshold > 0);
-        let clock_size = self.clock.len();
-        if threshold <= clock_size {
-            // get frontiers and sort them
-            let mut frontiers: Vec<_> =
-                self.clock.iter().map(|(_, eset)| eset.frontier()).collect();
-            frontiers.sort_unstable();
-
-            // get the frontier at the correct threshold
-            frontiers.into_iter().nth(clock_size - threshold)
-        } else {
+        let frontiers =
+            self.clock.values().map(|eset| eset.frontier()).collect();
+        frontier_at_threshold(frontiers, threshold)
+    }
+
+    /// Same as `Clock::frontier_threshold`, but computed only over the actors
+    /// not in `exclude` (e.g. actors known to be faulty), without building a
+    /// projection of the clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashSet;
+    /// use threshold::{clock, *};
+    ///
+    /// // actors are 0, 1 and 2
+    /// let clock = clock::vclock_from_seqs(vec![2, 1, 3]);
+    /// assert_eq!(clock.frontier_threshold(2), Some(2));
+    ///
+    /// let exclude: HashSet<_> = vec![0].into_iter().collect();
+    /// assert_eq!(clock.frontier_threshold_excluding(&exclude, 2), Some(1));
+    /// assert_eq!(clock.frontier_threshold_excluding(&exclude, 3), None);
+    /// ```
+    pub fn frontier_threshold_excluding<S: BuildHasher>(
+        &self,
+        exclude: &std::collections::HashSet<A, S>,
+        threshold: usize,
+    ) -> Option<u64> {
+        debug_assert!(threshold > 0);
+        let frontiers = self
+            .clock
+            .iter()
+            .filter(|(actor, _)| !exclude.contains(actor))
+            .map(|(_, eset)| eset.frontier())
+            .collect();
+        frontier_at_threshold(frontiers, threshold)
+    }
+
+    /// Same as `Clock::frontier_threshold`, but `None` is returned if the clock
+    /// has fewer than `min_actors` actors. This prevents thresholds from being
+    /// computed on under-populated clocks (e.g. during membership changes).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let clock = clock::vclock_from_seqs(vec![2, 1, 3]);
+    /// assert_eq!(clock.frontier_threshold_checked(2, 3), Some(2));
+    /// assert_eq!(clock.frontier_threshold_checked(2, 4), None);
+    /// ```
+    pub fn frontier_threshold_checked(
+        &self,
+        threshold: usize,
+        min_actors: usize,
+    ) -> Option<u64> {
+        if self.len() < min_actors {
             None
+        } else {
+            self.frontier_threshold(threshold)
         }
     }
 
@@ -384,13 +777,281 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// assert!(clock_b.contains(&actor_a, event));
     /// ```
     pub fn join(&mut self, other: &Self) {
+        self.join_delta(other);
+    }
+
+    /// Merges clock `other` into `self`, returning `true` if some event in
+    /// `other` was not in `self` (i.e. if `self` changed).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::new();
+    /// clock.add_range(&"A", 1, 10);
+    ///
+    /// let mut other = VClock::new();
+    /// other.add_range(&"A", 1, 5);
+    /// assert!(!clock.join_delta(&other));
+    ///
+    /// other.add(&"B", 1);
+    /// assert!(clock.join_delta(&other));
+    /// assert!(clock.contains(&"B", 1));
+    /// ```
+    pub fn join_delta(&mut self, other: &Self) -> bool {
+        // make room for as many actors as `other` has (assuming most of its
+        // actors are already in `self`, as clocks usually share their
+        // actors), so that merging a larger clock rehashes at most once
+        if other.len() > self.len() {
+            self.clock.reserve(other.len() - self.len());
+        }
+        let mut changed = false;
+        let mut grew = false;
         for (actor, eset) in other.clock.iter() {
-            self.upsert(
+            let upserted = self.upsert(
                 actor,
-                |current_eset| current_eset.join(eset),
-                || (eset.clone(), ()),
+                |current_eset| current_eset.join_mut(eset),
+                || (eset.clone(), true),
             );
+            changed |= upserted;
+            // an (empty) entry may be added without adding events
+            grew |= upserted && !eset.is_empty();
+        }
+        if changed {
+            self.bump();
+        }
+        grew
+    }
+
+    /// Removes the entries of the given `actors` from the clock, returning
+    /// them as a new clock. Entries are moved, not cloned, and actors that are
+    /// not in the clock are ignored. The returned clock can later be merged
+    /// back with `Clock::absorb`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = clock::vclock_from_seqs(vec![10, 20, 30]);
+    /// let evicted = clock.evict(vec![0, 2, 3]);
+    ///
+    /// assert_eq!(clock, VClock::from(vec![(1, MaxSet::from(20))]));
+    /// assert!(!clock.contains(&0, 10));
+    /// assert!(evicted.contains(&0, 10));
+    /// assert!(evicted.contains(&2, 30));
+    /// assert_eq!(evicted.len(), 2);
+    /// ```
+    pub fn evict<I: IntoIterator<Item = A>>(&mut self, actors: I) -> Self {
+        let evicted = Clock::from(
+            actors
+                .into_iter()
+                .filter_map(|actor| self.clock.remove_entry(&actor)),
+        );
+        if !evicted.is_empty() {
+            self.bump();
+        }
+        evicted
+    }
+
+    /// Merges clock `evicted` into `self`, consuming it. This is a join
+    /// optimized for clocks with disjoint actors (e.g. a clock returned by
+    /// `Clock::evict`): entries of actors not in `self` are moved, not cloned;
+    /// entries of actors in `self` are joined.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = clock::vclock_from_seqs(vec![10, 20, 30]);
+    /// let expected = clock.clone();
+    ///
+    /// let evicted = clock.evict(vec![0, 2]);
+    /// clock.absorb(evicted);
+    /// assert_eq!(clock, expected);
+    /// ```
+    pub fn absorb(&mut self, evicted: Self) {
+        let mut changed = false;
+        for (actor, eset) in evicted.clock {
+            match self.clock.entry(actor) {
+                hash_map::Entry::Vacant(entry) => {
+                    entry.insert(eset);
+                    changed = true;
+                }
+                hash_map::Entry::Occupied(mut entry) => {
+                    let current_eset = entry.get_mut();
+                    if !eset.leq(current_eset) {
+                        current_eset.join(&eset);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        if changed {
+            self.bump();
+        }
+    }
+
+    /// Merges clock `other` passed as argument into `self`, while bounding the
+    /// number of extras (or exceptions) of each event set to `max_extras`.
+    ///
+    /// If, after merging, the event set of some actor in `other` stores more
+    /// than `max_extras` extras or exceptions (see `EventSet::stored_extras`:
+    /// for range sets, ranges of them), it's collapsed to its frontier: all
+    /// events above the frontier are dropped.
+    ///
+    /// This trades exactness for bounded memory: after a collapse the clock
+    /// no longer contains some events that it has seen (neither the ones in
+    /// `other` nor the ones previously in `self`), and these have to be
+    /// delivered again (e.g. in a later join) to be part of the clock. Events
+    /// are never added that were not seen, and so `contains` never has false
+    /// positives.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = AEClock::new();
+    /// clock.add_range(&"A", 1, 2);
+    ///
+    /// let other = Clock::from(vec![("A", AboveExSet::from_events(vec![4, 6]))]);
+    ///
+    /// // with 2 extras allowed, the join is exact
+    /// let mut joined = clock.clone();
+    /// joined.join_capped(&other, 2);
+    /// assert_eq!(joined.get(&"A").unwrap().events(), (2, vec![4, 6]));
+    ///
+    /// // with a single extra allowed, the extras are dropped
+    /// clock.join_capped(&other, 1);
+    /// assert_eq!(clock.get(&"A").unwrap().events(), (2, vec![]));
+    /// ```
+    pub fn join_capped(&mut self, other: &Self, max_extras: usize) {
+        let version = self.version;
+        self.join(other);
+
+        let mut collapsed = false;
+        for actor in other.clock.keys() {
+            if let Some(eset) = self.clock.get_mut(actor) {
+                if eset.stored_extras() > max_extras {
+                    // keep only the events up to the frontier
+                    let frontier = eset.frontier();
+                    *eset = if frontier == 0 {
+                        E::new()
+                    } else {
+                        E::from_event_range(1, frontier)
+                    };
+                    collapsed = true;
+                }
+            }
+        }
+        // the join may have bumped the version already
+        if collapsed && !self.dirty_since(version) {
+            self.bump();
+        }
+    }
+
+    /// Applies a batch of operations to the clock. The result is the same as
+    /// applying each operation individually (in order), but operations are
+    /// first grouped by actor: dots and ranges of the same actor are sorted
+    /// and merged into the fewest possible ranges, and so each event set is
+    /// updated (and compressed) once per merged range, instead of once per
+    /// operation. This pays off when the operations of an actor are out of
+    /// order, as each of them would otherwise be stored as an extra (or an
+    /// exception) and later compressed away; for operations that are already
+    /// in order, grouping them costs about as much as it saves. Unlike
+    /// `Clock::add`, a new actor is only added to the clock if some event is
+    /// added to it.
+    ///
+    /// Returns a summary of the changes.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = AEClock::new();
+    /// let summary = clock.apply_ops(vec![
+    ///     clock::Op::AddDot("A", 2),
+    ///     clock::Op::AddRange("B", 1, 10),
+    ///     clock::Op::AddDot("A", 1),
+    ///     clock::Op::JoinEntry("A", AboveExSet::from_events(vec![1, 5])),
+    /// ]);
+    ///
+    /// assert_eq!(
+    ///     summary,
+    ///     clock::ApplySummary {
+    ///         events_added: 13,
+    ///         frontiers_advanced: 2,
+    ///         actors_touched: 2,
+    ///     }
+    /// );
+    /// assert_eq!(
+    ///     clock.snapshot(),
+    ///     vec![("A", 2, vec![5]), ("B", 10, vec![])]
+    /// );
+    /// ```
+    pub fn apply_ops<I: IntoIterator<Item = Op<A, E>>>(
+        &mut self,
+        ops: I,
+    ) -> ApplySummary {
+        // group operations by actor: ranges (dots are ranges with a single
+        // event) and event sets to join
+        type Grouped<E> = (Vec<(u64, u64)>, Vec<E>);
+        let mut grouped: HashMap<A, Grouped<E>> = HashMap::default();
+        for op in ops {
+            match op {
+                Op::AddDot(actor, seq) => {
+                    grouped.entry(actor).or_default().0.push((seq, seq))
+                }
+                Op::AddRange(actor, start, end) => {
+                    grouped.entry(actor).or_default().0.push((start, end))
+                }
+                Op::JoinEntry(actor, eset) => {
+                    grouped.entry(actor).or_default().1.push(eset)
+                }
+            }
+        }
+
+        let mut summary = ApplySummary::default();
+        for (actor, (ranges, esets)) in grouped {
+            let ranges = merge_ranges(ranges);
+            // returns the number of events added to `eset`, and whether its
+            // frontier advanced; event sets are joined before adding ranges,
+            // as these are more likely to fill the gaps between the events
+            // being joined
+            let apply = |eset: &mut E| {
+                let count_before = eset.event_count();
+                let frontier_before = eset.frontier();
+                esets.iter().for_each(|other| eset.join(other));
+                for &(start, end) in &ranges {
+                    eset.add_event_range(start, end);
+                }
+                let added = eset.event_count() - count_before;
+                (added, eset.frontier() > frontier_before)
+            };
+
+            let (added, advanced) = match self.clock.entry(actor) {
+                hash_map::Entry::Occupied(mut entry) => apply(entry.get_mut()),
+                hash_map::Entry::Vacant(entry) => {
+                    let mut eset = E::new();
+                    let result = apply(&mut eset);
+                    if result.0 > 0 {
+                        entry.insert(eset);
+                    }
+                    result
+                }
+            };
+            if added > 0 {
+                summary.events_added += added;
+                summary.actors_touched += 1;
+            }
+            if advanced {
+                summary.frontiers_advanced += 1;
+            }
+        }
+
+        if summary.events_added > 0 {
+            self.bump();
         }
+        summary
     }
 
     /// Intersects clock `other` passed as argument with `self`.
@@ -414,10 +1075,15 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
     /// assert!(clock_b.contains(&actor_a, event));
     /// ```
     pub fn meet(&mut self, other: &Self) {
+        let mut changed = false;
         let mut to_remove = Vec::new();
         for (actor, eset) in self.clock.iter_mut() {
             if let Some(other_eset) = other.get(actor) {
-                eset.meet(other_eset);
+                // only meet if some event in `eset` is not in `other_eset`
+                if !eset.leq(other_eset) {
+                    eset.meet(other_eset);
+                    changed = true;
+                }
             } else {
                 to_remove.push(actor.clone());
             }
@@ -426,71 +1092,990 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
         // at this point, `to_remove` contains the set of actors are present in
         // the local clock but not in the remote clock
         // - these actors shouldn't be in the final clock, so let's remove them
+        changed |= !to_remove.is_empty();
         for actor in to_remove {
             self.clock.remove(&actor);
         }
+        if changed {
+            self.bump();
+        }
     }
 
-    /// Returns a `Clock` iterator.
+    /// Checks if all events in `self` are also in `other`, i.e. if `self`
+    /// happened before (or is equal to) `other`. Actors missing from a clock
+    /// are considered to have no events.
+    ///
+    /// This is the order used by the `PartialOrd` implementation: `a <= b` iff
+    /// `a.leq(&b)`, and clocks where neither is included in the other are
+    /// concurrent (incomparable). So that it agrees with `==`, clocks with the
+    /// same events (see `Clock::same_events`) are only `Ordering::Equal` if
+    /// they're `==`, and incomparable otherwise.
     ///
     /// # Examples
     /// ```
+    /// use std::cmp::Ordering;
     /// use threshold::*;
     ///
-    /// let mut clock = VClock::new();
-    /// clock.next(&"A");
-    /// clock.next(&"A");
-    /// clock.next(&"B");
-    ///
-    /// for (&actor, eset) in clock.iter() {
-    ///     match actor {
-    ///         "A" => assert_eq!(eset, &MaxSet::from_event(2)),
-    ///         "B" => assert_eq!(eset, &MaxSet::from_event(1)),
-    ///         _ => panic!("unexpected actor name"),
-    ///     }
-    /// }
+    /// let a = clock::vclock_from_seqs(vec![1, 2]);
+    /// let b = clock::vclock_from_seqs(vec![1, 3]);
+    /// let c = clock::vclock_from_seqs(vec![2, 1]);
+    /// assert!(a.leq(&b));
+    /// assert!(a < b);
+    /// assert_eq!(a.partial_cmp(&a.clone()), Some(Ordering::Equal));
+    ///
+    /// // `b` and `c` are concurrent
+    /// assert!(!b.leq(&c) && !c.leq(&b));
+    /// assert_eq!(b.partial_cmp(&c), None);
+    ///
+    /// // clocks with disjoint actors are concurrent, unless one is empty
+    /// let d = VClock::from(vec![("A", MaxSet::from(1))]);
+    /// let e = VClock::from(vec![("B", MaxSet::from(1))]);
+    /// assert_eq!(d.partial_cmp(&e), None);
+    /// assert!(VClock::new() < d);
     /// ```
-    pub fn iter<'a>(&self) -> Iter<'_, A, E> {
-        Iter(self.clock.iter())
+    pub fn leq(&self, other: &Self) -> bool {
+        self.clock
+            .iter()
+            .all(|(actor, eset)| match other.get(actor) {
+                Some(other_eset) => eset.leq(other_eset),
+                None => eset.leq(&E::new()),
+            })
     }
 
-    /// Returns a `Clock` mutable iterator.
+    /// Checks if `self` and `other` have the same events, i.e. if each one
+    /// includes the other (see `Clock::leq`). Unlike `==`, actors with no
+    /// events are considered missing, and event sets are compared by their
+    /// events (and not by how they were built).
     ///
     /// # Examples
     /// ```
     /// use threshold::*;
     ///
-    /// let mut clock = VClock::new();
-    /// clock.next(&"A");
-    /// clock.next(&"A");
-    /// clock.next(&"B");
-    ///
-    /// for (&actor, eset) in clock.iter_mut() {
-    ///     if actor == "A" {
-    ///         eset.add_event(3);
-    ///     }
-    /// }
+    /// let clock = VClock::with(vec!["A"]);
+    /// assert!(clock.same_events(&VClock::new()));
+    /// assert_ne!(clock, VClock::new());
     ///
-    /// let max_set = clock.get(&"A").expect("there should be an event set");
-    /// assert_eq!(max_set, &MaxSet::from_event(3));
+    /// let a = ARClock::from(vec![("A", AboveRangeSet::from(0, vec![1, 2]))]);
+    /// let b = ARClock::from(vec![("A", AboveRangeSet::from_event_range(1, 2))]);
+    /// assert!(a.same_events(&b));
     /// ```
-    pub fn iter_mut<'a>(&mut self) -> IterMut<'_, A, E> {
-        IterMut(self.clock.iter_mut())
+    pub fn same_events(&self, other: &Self) -> bool {
+        self.leq(other) && other.leq(self)
     }
 
-    pub fn subtracted(&self, other: &Self) -> HashMap<A, Vec<u64>> {
-        self.clock
-            .iter()
+    /// Checks if `self` dominates `other`, i.e. if all events in `other` are
+    /// also in `self` (`other.leq(self)`). Actors missing from a clock are
+    /// considered to have no events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// // a replica only ships a delta to a peer whose clock doesn't dominate
+    /// // its own
+    /// let mut local = VClock::new();
+    /// local.next(&"A");
+    /// local.next(&"A");
+    ///
+    /// let a = ("A", MaxSet::from(2));
+    /// let b = ("B", MaxSet::from(1));
+    /// let peer = VClock::from(vec![a, b]);
+    /// assert!(peer.dominates(&local));
+    ///
+    /// local.next(&"A");
+    /// assert!(!peer.dominates(&local));
+    /// assert!(local.dominates(&VClock::new()));
+    /// ```
+    pub fn dominates(&self, other: &Self) -> bool {
+        other.leq(self)
+    }
+
+    /// Checks if `self` and `other` are concurrent, i.e. if neither dominates
+    /// the other.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = VClock::from(vec![("A", MaxSet::from(1))]);
+    /// let b = VClock::from(vec![("B", MaxSet::from(1))]);
+    /// assert!(a.concurrent(&b));
+    ///
+    /// let mut c = a.clone();
+    /// c.join(&b);
+    /// assert!(!a.concurrent(&c));
+    /// assert!(!c.concurrent(&c));
+    /// ```
+    pub fn concurrent(&self, other: &Self) -> bool {
+        !self.dominates(other) && !other.dominates(self)
+    }
+
+    /// Checks if `self` is a contiguous prefix of `other`, i.e. if, for each
+    /// actor, the events in `self` are all events from 1 up to its frontier,
+    /// and these are also in `other`. This is stronger than `self.leq(other)`,
+    /// which allows `self` to have gaps (as long as `other` has the events
+    /// `self` has). Actors missing from a clock are considered to have no
+    /// events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let prefix = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 2]))]);
+    /// let log = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 2, 3, 5]))]);
+    /// assert!(prefix.is_prefix_of(&log));
+    ///
+    /// // `log` dominates `gapped`, but `gapped` is missing event 2
+    /// let gapped = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 3]))]);
+    /// assert!(gapped.leq(&log));
+    /// assert!(!gapped.is_prefix_of(&log));
+    ///
+    /// // `log` doesn't have event 4
+    /// let ahead = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 2, 3, 4]))]);
+    /// assert!(!ahead.is_prefix_of(&log));
+    /// ```
+    pub fn is_prefix_of(&self, other: &Self) -> bool {
+        self.clock.iter().all(|(actor, eset)| {
+            let frontier = eset.frontier();
+            let other_frontier =
+                other.get(actor).map_or(0, |eset| eset.frontier());
+            // all events are contiguous, and `other` has them
+            eset.event_count() == frontier && frontier <= other_frontier
+        })
+    }
+
+    /// Returns, for each actor, the events in `other` that are not in `self`,
+    /// i.e. the events that `self` lacks to dominate `other`. Only actors with
+    /// such events are included, and so the result is empty iff
+    /// `self.dominates(other)`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 3]));
+    /// let b = ("B", AboveExSet::from_events(vec![1]));
+    /// let clock = Clock::from(vec![a, b]);
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 4]));
+    /// let b = ("B", AboveExSet::from_events(vec![1]));
+    /// let other = Clock::from(vec![a, b]);
+    ///
+    /// let missing = clock.missing_to_dominate(&other);
+    /// assert_eq!(missing.len(), 1);
+    /// assert_eq!(missing[&"A"], vec![4]);
+    /// assert!(!clock.dominates(&other));
+    ///
+    /// assert!(other.missing_to_dominate(&other).is_empty());
+    /// ```
+    pub fn missing_to_dominate(
+        &self,
+        other: &Self,
+    ) -> std::collections::HashMap<A, Vec<u64>> {
+        let mut missing = other.subtracted(self);
+        missing.retain(|_, events| !events.is_empty());
+        missing
+    }
+
+    /// Truncates the clock to `frontier`: events above the frontier of each
+    /// actor are dropped, as well as actors that are not in `frontier`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 3, 5]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2]));
+    /// let mut clock_a = Clock::from(vec![a, b]);
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2, 4]));
+    /// let c = ("C", AboveExSet::from_events(vec![1]));
+    /// let mut clock_b = Clock::from(vec![a, b, c]);
+    ///
+    /// let frontier = clock::common_frontier(vec![&clock_a, &clock_b]);
+    /// clock_a.truncate_to(&frontier);
+    /// clock_b.truncate_to(&frontier);
+    /// assert_eq!(clock_a, clock_b);
+    /// assert_eq!(clock_a.snapshot(), vec![("A", 2, vec![]), ("B", 2, vec![])]);
+    ///
+    /// // entries are truncated with `EventSet::truncate`, and so any event set
+    /// // can be used
+    /// let mut clock = BEClock::from(vec![("A", BelowExSet::from(10, vec![4, 8]))]);
+    /// clock.truncate_to(&VClock::from(vec![("A", MaxSet::from(8))]));
+    /// assert_eq!(clock.snapshot(), vec![("A", 7, vec![4])]);
+    /// ```
+    pub fn truncate_to(&mut self, frontier: &VClock<A>) {
+        let mut changed = false;
+        self.clock.retain(|actor, eset| match frontier.get(actor) {
+            Some(max) => {
+                let max = max.frontier();
+                if eset.max_event() > max {
+                    eset.truncate(max);
+                    changed = true;
+                }
+                true
+            }
+            None => {
+                changed = true;
+                false
+            }
+        });
+        if changed {
+            self.bump();
+        }
+    }
+
+    /// Drops from the clock all events at or below `watermark`, computed with
+    /// `TClock::gc_watermark`. Events above the watermark are never dropped:
+    /// since a `MaxSet` can't represent events above a gap, an entry of a
+    /// `VClock` is only dropped if all its events are at or below the
+    /// watermark.
+    ///
+    /// Events are dropped with `EventSet::remove_event_range`, and so any
+    /// event set can be used. Note that event sets that store their events
+    /// above a contiguous prefix one by one (e.g. `AboveExSet`) then store the
+    /// events left above the watermark that way.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 3, 5]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2]));
+    /// let mut clock = Clock::from(vec![a, b]);
+    ///
+    /// clock.truncate_to_gc(&VClock::from(vec![("A", MaxSet::from(2))]));
+    /// assert_eq!(clock.snapshot(), vec![("A", 0, vec![3, 5]), ("B", 2, vec![])]);
+    ///
+    /// let mut vclock = VClock::from(vec![("A", MaxSet::from(5)), ("B", MaxSet::from(2))]);
+    /// vclock.truncate_to_gc(&VClock::from(vec![("A", MaxSet::from(3)), ("B", MaxSet::from(3))]));
+    /// assert_eq!(vclock.snapshot(), vec![("A", 5, vec![]), ("B", 0, vec![])]);
+    /// ```
+    pub fn truncate_to_gc(&mut self, watermark: &VClock<A>) {
+        let mut changed = false;
+        for (actor, max) in watermark.iter() {
+            let watermark = max.frontier();
+            if let Some(eset) = self.clock.get_mut(actor) {
+                let mut truncated = eset.clone();
+                if !truncated.remove_event_range(1, watermark) {
+                    continue;
+                }
+                // keep the entry as it is if the events above the watermark
+                // can't be kept without the ones below it
+                let dropped = eset.count_events_in_range(1, watermark);
+                if truncated.event_count() + dropped == eset.event_count() {
+                    *eset = truncated;
+                    changed = true;
+                }
+            }
+        }
+        if changed {
+            self.bump();
+        }
+    }
+
+    /// Returns a `Clock` iterator.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::new();
+    /// clock.next(&"A");
+    /// clock.next(&"A");
+    /// clock.next(&"B");
+    ///
+    /// for (&actor, eset) in clock.iter() {
+    ///     match actor {
+    ///         "A" => assert_eq!(eset, &MaxSet::from_event(2)),
+    ///         "B" => assert_eq!(eset, &MaxSet::from_event(1)),
+    ///         _ => panic!("unexpected actor name"),
+    ///     }
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, A, E> {
+        Iter(self.clock.iter())
+    }
+
+    /// Returns a `Clock` mutable iterator.
+    ///
+    /// As with `Clock::get_mut`, the version of the clock is incremented on
+    /// purpose, even if no event set is changed through the iterator.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::new();
+    /// clock.next(&"A");
+    /// clock.next(&"A");
+    /// clock.next(&"B");
+    ///
+    /// for (&actor, eset) in clock.iter_mut() {
+    ///     if actor == "A" {
+    ///         eset.add_event(3);
+    ///     }
+    /// }
+    ///
+    /// let max_set = clock.get(&"A").expect("there should be an event set");
+    /// assert_eq!(max_set, &MaxSet::from_event(3));
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, A, E> {
+        // event sets may be changed through the iterator, so the clock is
+        // conservatively considered changed
+        self.bump();
+        IterMut(self.clock.iter_mut())
+    }
+
+    /// Returns an iterator over all events in the clock, as `(actor, event)`
+    /// pairs. Events of each actor are returned from lowest to highest, but
+    /// actors are returned in no specific order.
+    ///
+    /// The iterator is lazy and borrows each event set (see `EventSet::iter`).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 4]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 5]));
+    /// let clock = Clock::from(vec![a, b]);
+    ///
+    /// assert_eq!(clock.event_dots().count(), 5);
+    /// assert_eq!(clock.event_dots().filter(|(a, _)| **a == "A").count(), 3);
+    /// assert!(clock.event_dots().any(|dot| dot == (&"B", 5)));
+    /// ```
+    pub fn event_dots(&self) -> impl Iterator<Item = (&A, u64)> + '_ {
+        self.clock
+            .iter()
+            .flat_map(|(actor, eset)| eset.iter().map(move |seq| (actor, seq)))
+    }
+
+    /// Returns at most `limit` entries (sorted by actor) whose actor is after
+    /// the `after` cursor, or the first entries if there's no cursor. Only
+    /// the returned entries are cloned.
+    ///
+    /// To page through the clock, pass the last actor of each page as the
+    /// cursor of the next one. If the clock changes between pages, actors
+    /// added before the cursor are not returned, actors removed are skipped,
+    /// and no actor is returned twice.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let clock = clock::vclock_from_seqs(vec![10, 20, 30]);
+    ///
+    /// let page = clock.page(None, 2);
+    /// assert_eq!(page, vec![(0, MaxSet::from(10)), (1, MaxSet::from(20))]);
+    ///
+    /// let cursor = page.last().map(|(actor, _)| actor);
+    /// assert_eq!(clock.page(cursor, 2), vec![(2, MaxSet::from(30))]);
+    /// ```
+    pub fn page(&self, after: Option<&A>, limit: usize) -> Vec<(A, E)> {
+        let mut entries: Vec<_> = self
+            .clock
+            .iter()
+            .filter(|(actor, _)| after.is_none_or(|after| *actor > after))
+            .collect();
+
+        // only sort the entries in the page
+        if entries.len() > limit {
+            if limit == 0 {
+                return Vec::new();
+            }
+            entries.select_nth_unstable_by(limit - 1, |a, b| a.0.cmp(b.0));
+            entries.truncate(limit);
+        }
+        entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        entries
+            .into_iter()
+            .map(|(actor, eset)| (actor.clone(), eset.clone()))
+            .collect()
+    }
+
+    /// Returns the version of the clock: a counter incremented by every
+    /// mutation that changes the clock. Mutations that change nothing (e.g.
+    /// adding an event that is already in the clock) keep the version.
+    /// Methods that give mutable access to event sets (`get_mut` and
+    /// `iter_mut`) always increment it.
+    ///
+    /// Versions are local to an instance (and its clones): they're not
+    /// serialized nor compared by `PartialEq`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::new();
+    /// assert_eq!(clock.version(), 0);
+    ///
+    /// clock.add(&"A", 1);
+    /// assert_eq!(clock.version(), 1);
+    ///
+    /// // adding an existing event doesn't change the version
+    /// clock.add(&"A", 1);
+    /// assert_eq!(clock.version(), 1);
+    /// ```
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Checks if the clock has changed since it had some `version`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::new();
+    /// clock.next(&"A");
+    /// let version = clock.version();
+    /// assert!(!clock.dirty_since(version));
+    ///
+    /// clock.join(&VClock::from(vec![("A", MaxSet::from(1))]));
+    /// assert!(!clock.dirty_since(version));
+    ///
+    /// clock.next(&"B");
+    /// assert!(clock.dirty_since(version));
+    /// ```
+    pub fn dirty_since(&self, version: u64) -> bool {
+        self.version > version
+    }
+
+    /// Returns a snapshot of all events in the clock: for each actor (sorted
+    /// by actor), the result of `EventSet::events` with the second component
+    /// sorted (ASC).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 6, 4]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2, 3]));
+    /// let clock = Clock::from(vec![b.clone(), a.clone()]);
+    ///
+    /// assert_eq!(
+    ///     clock.snapshot(),
+    ///     vec![("A", 2, vec![4, 6]), ("B", 3, vec![])]
+    /// );
+    ///
+    /// // the snapshot doesn't depend on the order in which actors were added
+    /// let other = Clock::from(vec![a, b]);
+    /// assert_eq!(clock.snapshot(), other.snapshot());
+    /// ```
+    pub fn snapshot(&self) -> Vec<(A, u64, Vec<u64>)> {
+        let mut snapshot: Vec<_> = self
+            .clock
+            .iter()
+            .map(|(actor, eset)| {
+                let (max, mut exs) = eset.events();
+                exs.sort_unstable();
+                (actor.clone(), max, exs)
+            })
+            .collect();
+        snapshot.sort_unstable_by(|(a, _, _), (b, _, _)| a.cmp(b));
+        snapshot
+    }
+
+    /// Returns the `Debug` representation of the clock without eliding any
+    /// exceptions. Prefer `{:?}` for logging, since a single event set may
+    /// have a huge number of exceptions.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let clock = Clock::from(vec![
+    ///     ("B", AboveExSet::from_events(vec![1, 3, 4])),
+    ///     ("A", AboveExSet::from_events(1..=10)),
+    /// ]);
+    /// assert_eq!(
+    ///     clock.debug_full(),
+    ///     r#"AEClock {"A": 10, "B": (1 + {3, 4})}"#
+    /// );
+    /// ```
+    pub fn debug_full(&self) -> String {
+        format!("{:?}", FullDebug(self))
+    }
+
+    /// Writes the clock with actors sorted. At most `limit` exceptions are
+    /// written per actor, if a limit is set.
+    fn fmt_with_limit(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        limit: Option<usize>,
+    ) -> fmt::Result {
+        let clock: std::collections::BTreeMap<_, _> =
+            self.clock.iter().collect();
+        write!(f, "{} {{", E::KIND)?;
+        for (i, (actor, eset)) in clock.into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            match limit {
+                Some(limit) => write!(f, "{:?}: {:.*?}", actor, limit, eset)?,
+                None => write!(f, "{:?}: {:?}", actor, eset)?,
+            }
+        }
+        write!(f, "}}")
+    }
+
+    pub fn subtracted(
+        &self,
+        other: &Self,
+    ) -> std::collections::HashMap<A, Vec<u64>> {
+        self.clock
+            .iter()
             .map(|(actor, eset)| {
                 let subtracted = if let Some(other_eset) = other.get(actor) {
                     eset.subtracted(other_eset)
                 } else {
-                    eset.clone().event_iter().collect()
+                    eset.iter().collect()
                 };
                 (actor.clone(), subtracted)
             })
             .collect()
     }
+
+    /// Returns a clock with the events in `self` that are not in `other`.
+    /// Actors with no such events are not in the returned clock. The same
+    /// clock is returned by `&self - &other`. Entries are built from the
+    /// ranges of both event sets, without enumerating their events.
+    ///
+    /// Since a `MaxSet` can't represent gaps, with `VClock`s the returned
+    /// clock is not a set difference: it has, for each actor, all events up
+    /// to the highest event in `self` (if it's not in `other`).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 3, 5]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2]));
+    /// let clock = Clock::from(vec![a, b]);
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2]));
+    /// let other = Clock::from(vec![a, b]);
+    ///
+    /// let delta = clock.subtracted_clock(&other);
+    /// let a = ("A", AboveExSet::from_events(vec![3, 5]));
+    /// assert_eq!(delta, Clock::from(vec![a]));
+    /// assert_eq!(&clock - &other, delta);
+    ///
+    /// // with `VClock`s, events below the ones not in `other` are kept
+    /// let clock = clock::vclock_from_seqs(vec![10, 5]);
+    /// let other = clock::vclock_from_seqs(vec![8, 5]);
+    /// let delta = clock.subtracted_clock(&other);
+    /// assert_eq!(delta, VClock::from(vec![(0, MaxSet::from(10))]));
+    /// ```
+    pub fn subtracted_clock(&self, other: &Self) -> Self {
+        let clock = self.clock.iter().filter_map(|(actor, eset)| {
+            let ranges = subtracted_ranges(eset, other.get(actor));
+            if ranges.is_empty() {
+                None
+            } else {
+                let mut subtracted = E::new();
+                for (start, end) in ranges {
+                    subtracted.add_event_range(start, end);
+                }
+                Some((actor.clone(), subtracted))
+            }
+        });
+        Clock::from(clock)
+    }
+
+    /// Returns the number of events in `self` that are not in `other`,
+    /// without enumerating them. It's 0 if `self` is empty.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 3, 5]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2]));
+    /// let clock = Clock::from(vec![a, b]);
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2]));
+    /// let other = Clock::from(vec![a]);
+    ///
+    /// assert_eq!(clock.missing_count(&other), 4);
+    /// assert_eq!(other.missing_count(&clock), 0);
+    /// ```
+    pub fn missing_count(&self, other: &Self) -> u64 {
+        self.clock
+            .iter()
+            .map(|(actor, eset)| {
+                if let Some(other_eset) = other.get(actor) {
+                    eset.subtracted_count(other_eset)
+                } else {
+                    eset.subtracted_count(&E::new())
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the number of events in the clock (summed over all actors),
+    /// without enumerating them.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 3, 5]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2]));
+    /// let clock = Clock::from(vec![a, b]);
+    /// assert_eq!(clock.total_events(), 6);
+    /// ```
+    pub fn total_events(&self) -> u64 {
+        self.clock.values().map(|eset| eset.event_count()).sum()
+    }
+
+    /// Returns a hash of the clock that only depends on its entries (and not
+    /// on their order nor on how their event sets were built), combining the
+    /// sorted actors with the `EventSet::canonical_hash` of their event sets.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", BelowExSet::from_events(vec![1, 3]));
+    /// let b = ("B", BelowExSet::from_events(vec![2, 1]));
+    /// let clock = Clock::from(vec![a.clone(), b.clone()]);
+    /// assert_eq!(clock.canonical_hash(), Clock::from(vec![b, a]).canonical_hash());
+    ///
+    /// let mut other = clock.clone();
+    /// other.add(&"A", 2);
+    /// assert_ne!(clock.canonical_hash(), other.canonical_hash());
+    /// ```
+    pub fn canonical_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.canonical_entries().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the actors (sorted) with the `EventSet::canonical_hash` of
+    /// their event sets, skipping actors with no events.
+    fn canonical_entries(&self) -> Vec<(&A, u64)> {
+        let mut entries: Vec<_> = self
+            .clock
+            .iter()
+            .filter(|(_, eset)| !eset.is_empty())
+            .map(|(actor, eset)| (actor, eset.canonical_hash()))
+            .collect();
+        entries.sort_unstable_by_key(|(actor, _)| *actor);
+        entries
+    }
+
+    /// Returns the events in `self` that are not in `other`, in a compact
+    /// form to be sent over the wire: only actors with such events are
+    /// included, and their events are encoded as runs of contiguous events.
+    /// The receiver applies it with `Clock::apply_delta`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(1..=1000));
+    /// let b = ("B", AboveExSet::from_events(1..=10));
+    /// let clock = Clock::from(vec![a, b.clone()]);
+    ///
+    /// let a = ("A", AboveExSet::from_events((1..=995).filter(|e| *e != 10)));
+    /// let mut remote = Clock::from(vec![a, b]);
+    ///
+    /// let delta = clock.delta_since(&remote);
+    /// let json = serde_json::to_string(&delta).unwrap();
+    /// assert_eq!(json, r#"{"A":[10,10,996,1000]}"#);
+    ///
+    /// remote.apply_delta(serde_json::from_str(&json).unwrap());
+    /// assert_eq!(remote, clock);
+    /// ```
+    pub fn delta_since(&self, other: &Self) -> DeltaClock<A> {
+        let runs = self
+            .clock
+            .iter()
+            .filter_map(|(actor, eset)| {
+                let runs = subtracted_ranges(eset, other.get(actor));
+                if runs.is_empty() {
+                    None
+                } else {
+                    Some((actor.clone(), Runs(runs)))
+                }
+            })
+            .collect();
+        DeltaClock { runs }
+    }
+
+    /// Adds all events in `delta` (see `Clock::delta_since`) to the clock.
+    /// As with `Clock::add_range`, events that the event set can't store (see
+    /// `EventSet::MAX_SEQ`) are not added.
+    pub fn apply_delta(&mut self, delta: DeltaClock<A>) {
+        for (actor, Runs(runs)) in delta.runs {
+            for (start, end) in runs {
+                self.add_range(&actor, start, end);
+            }
+        }
+    }
+
+    /// Returns the number of events (of any actor) within `[start, end]`
+    /// (both ends included). It's 0 for an empty clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// // actor 0 has events 1..=10, actor 1 has events 1..=3, and actor 2 has
+    /// // events 1..=7
+    /// let clock = clock::vclock_from_seqs(vec![10, 3, 7]);
+    /// assert_eq!(clock.count_events_in_range(3, 8), 6 + 1 + 5);
+    /// assert_eq!(clock.count_events_in_range(11, 20), 0);
+    /// ```
+    pub fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        self.clock
+            .values()
+            .map(|eset| eset.count_events_in_range(start, end))
+            .sum()
+    }
+
+    /// Compresses the event set of every actor (see `EventSet::compress`).
+    /// The events in the clock don't change, but its version does if some
+    /// event set was compressed.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from(0, vec![2, 1]));
+    /// let mut clock = Clock::from(vec![a]);
+    /// assert_eq!(clock.snapshot(), vec![("A", 0, vec![1, 2])]);
+    ///
+    /// clock.compact_all();
+    /// assert_eq!(clock.snapshot(), vec![("A", 2, vec![])]);
+    /// ```
+    pub fn compact_all(&mut self) {
+        // all event sets are compressed, and so `Iterator::any` can't be used
+        let mut compressed = false;
+        for eset in self.clock.values_mut() {
+            compressed |= eset.compress();
+        }
+        if compressed {
+            self.bump();
+        }
+    }
+
+    /// Returns a new clock where each event `seq` of each actor `actor` is
+    /// replaced by `f(actor, seq)`. Function `f` should be strictly increasing
+    /// for each actor (this is checked in debug builds), e.g. adding an
+    /// offset to all events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 4]));
+    /// let b = ("B", AboveExSet::from_events(vec![1]));
+    /// let clock = Clock::from(vec![a, b]);
+    ///
+    /// let clock = clock
+    ///     .map_events(|actor, seq| if *actor == "A" { seq + 100 } else { seq });
+    /// assert_eq!(
+    ///     clock.snapshot(),
+    ///     vec![("A", 0, vec![101, 102, 104]), ("B", 1, vec![])]
+    /// );
+    /// ```
+    pub fn map_events<F: FnMut(&A, u64) -> u64>(self, mut f: F) -> Self {
+        let clock = self.clock.into_iter().map(|(actor, eset)| {
+            let mut last = None;
+            let events: Vec<_> = eset
+                .event_iter()
+                .map(|seq| {
+                    let mapped = f(&actor, seq);
+                    debug_assert!(
+                        last.is_none_or(|last| last < mapped),
+                        "map_events: function should be strictly increasing"
+                    );
+                    last = Some(mapped);
+                    mapped
+                })
+                .collect();
+            (actor, E::from_events(events))
+        });
+        Clock::from(clock)
+    }
+}
+
+impl<A: Actor + fmt::Display, E: EventSet> Clock<A, E> {
+    /// Returns a multi-line report of the clock, with one line per actor
+    /// (sorted by actor). Each line shows the events of the actor as a list of
+    /// ranges of contiguous events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 3, 5]));
+    /// let b = ("B", AboveExSet::from_events(1..=10));
+    /// let c = ("C", AboveExSet::new());
+    /// let clock = Clock::from(vec![a, b, c]);
+    ///
+    /// assert_eq!(clock.pretty(), "A: 1-3, 5\nB: 1-10\nC:");
+    /// ```
+    pub fn pretty(&self) -> String {
+        let clock: std::collections::BTreeMap<_, _> =
+            self.clock.iter().collect();
+        let lines: Vec<_> = clock
+            .into_iter()
+            .map(|(actor, eset)| {
+                // ranges are not adjacent, and so they're runs of contiguous
+                // events
+                let runs: Vec<_> = eset
+                    .clone()
+                    .range_iter()
+                    .map(|(start, end)| {
+                        if start == end {
+                            format!("{}", start)
+                        } else {
+                            format!("{}-{}", start, end)
+                        }
+                    })
+                    .collect();
+                if runs.is_empty() {
+                    format!("{}:", actor)
+                } else {
+                    format!("{}: {}", actor, runs.join(", "))
+                }
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+/// An operation that can be applied to a `Clock` with `Clock::apply_ops`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op<A: Actor, E: EventSet> {
+    /// Adds an event, as `Clock::add`.
+    AddDot(A, u64),
+    /// Adds a range of events (both ends included), as `Clock::add_range`.
+    AddRange(A, u64, u64),
+    /// Joins an event set into the entry of the actor, as `Clock::join`.
+    JoinEntry(A, E),
+}
+
+/// Summary of the changes made by `Clock::apply_ops`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ApplySummary {
+    /// Number of new events
+    pub events_added: u64,
+    /// Number of actors whose frontier advanced
+    pub frontiers_advanced: usize,
+    /// Number of actors to which some event was added
+    pub actors_touched: usize,
+}
+
+/// Events of a clock that are missing in another, as computed by
+/// `Clock::delta_since`. Serialized as a map from actor to a flat list of
+/// runs of contiguous events (`[start, end, start, end, ...]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct DeltaClock<A: Actor> {
+    runs: std::collections::BTreeMap<A, Runs>,
+}
+
+impl<A: Actor> DeltaClock<A> {
+    /// Checks if the delta has no events.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+}
+
+/// Runs of contiguous events (both ends included).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "Vec<u64>", try_from = "Vec<u64>")]
+struct Runs(Vec<(u64, u64)>);
+
+impl From<Runs> for Vec<u64> {
+    fn from(runs: Runs) -> Self {
+        runs.0
+            .into_iter()
+            .flat_map(|(start, end)| [start, end])
+            .collect()
+    }
+}
+
+impl TryFrom<Vec<u64>> for Runs {
+    type Error = String;
+
+    fn try_from(flat: Vec<u64>) -> Result<Self, Self::Error> {
+        if !flat.len().is_multiple_of(2) {
+            return Err(format!("odd number of run ends: {}", flat.len()));
+        }
+        let runs = flat
+            .chunks(2)
+            .map(|run| match (run[0], run[1]) {
+                // events start at 1, and runs can't be empty
+                (0, _) => Err(format!("run starting at 0: [0, {}]", run[1])),
+                (start, end) if start > end => {
+                    Err(format!("empty run: [{}, {}]", start, end))
+                }
+                run => Ok(run),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Runs(runs))
+    }
+}
+
+/// Returns the highest frontier that at least `threshold` of the `frontiers`
+/// are at or above, if there are that many frontiers.
+fn frontier_at_threshold(
+    mut frontiers: Vec<u64>,
+    threshold: usize,
+) -> Option<u64> {
+    let count = frontiers.len();
+    if threshold <= count {
+        frontiers.sort_unstable();
+        frontiers.into_iter().nth(count - threshold)
+    } else {
+        None
+    }
+}
+
+/// Returns the ranges of events in `eset` that are not in `other` (if any).
+/// The ranges are computed from the ranges of both event sets, without
+/// enumerating their events.
+fn subtracted_ranges<E: EventSet>(
+    eset: &E,
+    other: Option<&E>,
+) -> Vec<(u64, u64)> {
+    let ranges: Vec<_> = eset.clone().range_iter().collect();
+    match other {
+        Some(other) => {
+            let other_ranges: Vec<_> = other.clone().range_iter().collect();
+            crate::set::subtract_intervals(&ranges, &other_ranges)
+        }
+        None => ranges,
+    }
+}
+
+/// Sorts (ASC) ranges of events, merging the ones that overlap or are
+/// adjacent. Event 0 and empty ranges are dropped.
+fn merge_ranges(mut ranges: Vec<(u64, u64)>) -> Vec<(u64, u64)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        let start = cmp::max(start, 1);
+        if start > end {
+            continue;
+        }
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = cmp::max(*last_end, end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
 }
 
 /// Creates a new vector clock from a list of sequences.
@@ -502,16 +2087,52 @@ impl<A: Actor, E: EventSet> Clock<A, E> {
 /// ```
 /// use threshold::{clock, *};
 ///
-/// let clock = clock::vclock_from_seqs(vec![10, 20]);
-/// assert!(clock.contains(&0, 10));
-/// assert!(clock.contains(&1, 20));
+/// let clock = clock::vclock_from_seqs(vec![10, 20]);
+/// assert!(clock.contains(&0, 10));
+/// assert!(clock.contains(&1, 20));
+/// ```
+pub fn vclock_from_seqs<I: IntoIterator<Item = u64>>(iter: I) -> VClock<u64> {
+    Clock::from(
+        iter.into_iter()
+            .enumerate()
+            .map(|(actor, seq)| (actor as u64, MaxSet::from_event(seq))),
+    )
+}
+
+/// Returns the common frontier of a set of clocks: for each actor present in
+/// *all* clocks, the minimum of its frontiers. Actors missing from some clock
+/// are not part of the common frontier (as opposed to being considered with
+/// frontier 0). The common frontier of no clocks is an empty `VClock`.
+///
+/// # Examples
+/// ```
+/// use threshold::{clock, *};
+///
+/// let a = ("A", AboveExSet::from_events(vec![1, 2, 3, 5]));
+/// let b = ("B", AboveExSet::from_events(vec![1, 2]));
+/// let clock_a = Clock::from(vec![a, b]);
+///
+/// let a = ("A", AboveExSet::from_events(vec![1, 2]));
+/// let c = ("C", AboveExSet::from_events(vec![1]));
+/// let clock_b = Clock::from(vec![a, c]);
+///
+/// let frontier = clock::common_frontier(vec![&clock_a, &clock_b]);
+/// assert_eq!(frontier, VClock::from(vec![("A", MaxSet::from(2))]));
 /// ```
-pub fn vclock_from_seqs<I: IntoIterator<Item = u64>>(iter: I) -> VClock<u64> {
-    Clock::from(
-        iter.into_iter()
-            .enumerate()
-            .map(|(actor, seq)| (actor as u64, MaxSet::from_event(seq))),
-    )
+pub fn common_frontier<'a, A, E, I>(iter: I) -> VClock<A>
+where
+    A: Actor + 'a,
+    E: EventSet + 'a,
+    I: IntoIterator<Item = &'a Clock<A, E>>,
+{
+    let mut clocks = iter.into_iter();
+    match clocks.next() {
+        Some(clock) => clocks.fold(clock.frontier(), |mut frontier, clock| {
+            frontier.meet(&clock.frontier());
+            frontier
+        }),
+        None => VClock::new(),
+    }
 }
 
 pub struct IntoIter<A: Actor, E: EventSet>(hash_map::IntoIter<A, E>);
@@ -572,10 +2193,1085 @@ impl<'a, A: Actor, E: EventSet> Iterator for IterMut<'a, A, E> {
     }
 }
 
+impl<A: Actor, E: EventSet + PartialEq> PartialEq for Clock<A, E> {
+    fn eq(&self, other: &Self) -> bool {
+        // the version is not compared
+        self.clock == other.clock
+    }
+}
+
+impl<A: Actor, E: EventSet + Eq> Eq for Clock<A, E> {}
+
+/// Clocks are hashed from their (sorted) actors with the
+/// `EventSet::canonical_hash` of their event sets, skipping actors with no
+/// events (see `Clock::canonical_hash`). Equal clocks have the same hash (even
+/// if their event sets were built differently).
+///
+/// # Examples
+/// ```
+/// use std::collections::HashSet;
+/// use threshold::*;
+///
+/// let mut clock = VClock::new();
+/// clock.add_range(&"A", 1, 10);
+/// let mut memo = HashSet::new();
+/// memo.insert(clock.clone());
+/// assert!(memo.contains(&clock));
+/// ```
+impl<A: Actor, E: EventSet> Hash for Clock<A, E> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical_entries().hash(state);
+    }
+}
+
+/// Clocks are ordered by inclusion (see `Clock::leq`). Clocks with the same
+/// events are only `Equal` if they're `==`, and incomparable otherwise (e.g. if
+/// one has an actor with no events that the other is missing).
+impl<A: Actor, E: EventSet + PartialEq> PartialOrd for Clock<A, E> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        match (self.leq(other), other.leq(self)) {
+            (true, true) if self == other => Some(cmp::Ordering::Equal),
+            (true, true) => None,
+            (true, false) => Some(cmp::Ordering::Less),
+            (false, true) => Some(cmp::Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Returns the events in the left clock that are not in the right clock. See
+/// `Clock::subtracted_clock`.
+///
+/// # Examples
+/// ```
+/// use threshold::*;
+///
+/// let mut a = VClock::new();
+/// a.add_range(&"A", 1, 10);
+/// let mut b = VClock::new();
+/// b.add_range(&"A", 1, 10);
+/// b.add_range(&"B", 1, 5);
+///
+/// let delta = &b - &a;
+/// assert!(!delta.contains(&"A", 10));
+/// assert!(delta.contains(&"B", 5));
+///
+/// a.join(&delta);
+/// assert_eq!(a, b);
+/// ```
+impl<A: Actor, E: EventSet> Sub<&Clock<A, E>> for &Clock<A, E> {
+    type Output = Clock<A, E>;
+
+    fn sub(self, other: &Clock<A, E>) -> Self::Output {
+        self.subtracted_clock(other)
+    }
+}
+
+/// Maximum number of exceptions (or ranges) shown per actor by `Clock`'s
+/// `Debug` implementation. `Clock::debug_full` shows all of them.
+const DEBUG_LIMIT: usize = 16;
+
 impl<A: Actor, E: EventSet> fmt::Debug for Clock<A, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_with_limit(f, Some(DEBUG_LIMIT))
+    }
+}
+
+impl<A: Actor + fmt::Display, E: EventSet + fmt::Display> fmt::Display
+    for Clock<A, E>
+{
+    /// Formats the clock as `{A: 1..5, B: 1..3,7}`, with actors sorted and the
+    /// events of each actor formatted as ranges of contiguous events.
+    ///
+    /// Actors that are empty, have surrounding whitespace or contain any of
+    /// `:,{}"\` are quoted, with `"` and `\` escaped by a backslash (e.g.
+    /// `{"a:b": 1..3}`), so that the clock can be parsed back.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(1..=5));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2, 3, 7]));
+    /// let clock = Clock::from(vec![b, a]);
+    /// assert_eq!(clock.to_string(), "{A: 1..5, B: 1..3,7}");
+    ///
+    /// let clock = VClock::from(vec![("A", MaxSet::new())]);
+    /// assert_eq!(clock.to_string(), "{A: }");
+    /// assert_eq!(VClock::<&str>::new().to_string(), "{}");
+    ///
+    /// let clock = VClock::from(vec![("a:b", MaxSet::from(3))]);
+    /// assert_eq!(clock.to_string(), r#"{"a:b": 1..3}"#);
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let clock: std::collections::BTreeMap<_, _> =
             self.clock.iter().collect();
-        write!(f, "{:?}", clock)
+        write!(f, "{{")?;
+        for (i, (actor, eset)) in clock.into_iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            fmt_actor(f, &actor.to_string())?;
+            write!(f, ": {}", eset)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Characters that can't appear in an unquoted actor (see `Clock`'s
+/// `Display` implementation).
+const ACTOR_SPECIAL_CHARS: &[char] = &[':', ',', '{', '}', '"', '\\'];
+
+/// Formats an actor, quoting it if it can't be parsed back otherwise.
+fn fmt_actor(f: &mut fmt::Formatter<'_>, actor: &str) -> fmt::Result {
+    let quote = actor.is_empty()
+        || actor.trim() != actor
+        || actor.contains(ACTOR_SPECIAL_CHARS);
+    if !quote {
+        return write!(f, "{}", actor);
+    }
+    write!(f, "\"")?;
+    for c in actor.chars() {
+        if c == '"' || c == '\\' {
+            write!(f, "\\")?;
+        }
+        write!(f, "{}", c)?;
+    }
+    write!(f, "\"")
+}
+
+/// Error returned when adding an event that the event set can't store (see
+/// `Clock::try_add`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeqOutOfRange {
+    /// The event rejected.
+    pub seq: u64,
+    /// The highest event the event set can store (see `EventSet::MAX_SEQ`).
+    pub max: u64,
+}
+
+impl fmt::Display for SeqOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "event {} is higher than the maximum {}",
+            self.seq, self.max
+        )
+    }
+}
+
+impl std::error::Error for SeqOutOfRange {}
+
+// Checks that `seq` can be stored by `E`.
+fn check_seq<E: EventSet>(seq: u64) -> Result<(), SeqOutOfRange> {
+    if seq > E::MAX_SEQ {
+        Err(SeqOutOfRange {
+            seq,
+            max: E::MAX_SEQ,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Error returned when parsing an invalid clock (see `Clock::from_str`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseClockError {
+    /// The clock is not enclosed in braces.
+    MissingBraces,
+    /// Some entry is not of the form `actor: events`.
+    InvalidEntry { entry: String },
+    /// Some actor appears more than once.
+    DuplicateActor { actor: String },
+    /// Some event (or range of events) is not valid.
+    InvalidEvents { events: String },
+}
+
+impl fmt::Display for ParseClockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseClockError::MissingBraces => {
+                write!(f, "the clock should be enclosed in braces")
+            }
+            ParseClockError::InvalidEntry { entry } => {
+                write!(f, "invalid entry: {:?} is not `actor: events`", entry)
+            }
+            ParseClockError::DuplicateActor { actor } => {
+                write!(f, "actor {:?} appears more than once", actor)
+            }
+            ParseClockError::InvalidEvents { events } => {
+                write!(f, "invalid events: {:?}", events)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseClockError {}
+
+impl<E: EventSet> FromStr for Clock<String, E> {
+    type Err = ParseClockError;
+
+    /// Parses a clock from its `Display` form, e.g. `{A: 1..5, B: 1..3,7}`.
+    /// The events of each actor are a (comma-separated) list of events and
+    /// ranges of events (both ends included), and may be empty. Whitespace
+    /// around actors and events is ignored.
+    ///
+    /// Actors may be quoted (with `"` and `\` escaped by a backslash), and
+    /// they must be if they contain any of `:,{}"\`.
+    ///
+    /// Events that the event set can't store (see `EventSet::MAX_SEQ`) are
+    /// rejected, as are entries whose events the event set can't represent
+    /// exactly: e.g. an entry like `A: 5` (or `A: 1..3,7`) of a `VClock`,
+    /// which would have all events from 1 to 5 (or to 7).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let clock: VClock<String> = "{A: 1..5, B: 1..3}".parse().unwrap();
+    /// assert_eq!(clock.to_string(), "{A: 1..5, B: 1..3}");
+    ///
+    /// let result: Result<VClock<String>, _> = "{A: 5}".parse();
+    /// assert!(result.is_err());
+    ///
+    /// let clock: AEClock<String> = "{A: 1..3,7, B: }".parse().unwrap();
+    /// assert!(clock.contains(&"A".to_string(), 7));
+    /// assert!(!clock.contains(&"A".to_string(), 4));
+    /// assert_eq!(clock.to_string().parse(), Ok(clock));
+    ///
+    /// let result: Result<VClock<String>, _> = "{A: 3..1}".parse();
+    /// assert!(result.is_err());
+    ///
+    /// let clock: VClock<String> = r#"{"a, \"b\"": 1..2}"#.parse().unwrap();
+    /// assert!(clock.contains(&r#"a, "b""#.to_string(), 2));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inner = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|s| s.strip_suffix('}'))
+            .ok_or(ParseClockError::MissingBraces)?
+            .trim();
+        if inner.is_empty() {
+            return Ok(Clock::new());
+        }
+
+        // entries are separated by commas, as are the events of each entry:
+        // a new entry starts at each part with an actor
+        let mut entries: Vec<(String, Vec<&str>)> = Vec::new();
+        for part in split_unquoted(inner)? {
+            match parse_actor(part)? {
+                Some((actor, events)) => entries.push((actor, vec![events])),
+                None => match entries.last_mut() {
+                    Some((_, events)) => events.push(part),
+                    None => {
+                        let entry = part.trim().to_string();
+                        return Err(ParseClockError::InvalidEntry { entry });
+                    }
+                },
+            }
+        }
+
+        let mut clock = Clock::new();
+        for (actor, events) in entries {
+            let eset = parse_events(&events)?;
+            if clock.clock.contains_key(&actor) {
+                return Err(ParseClockError::DuplicateActor { actor });
+            }
+            clock.clock.insert(actor, eset);
+        }
+        Ok(clock)
+    }
+}
+
+/// Splits the entries of a clock on the commas that are not within a quoted
+/// actor (see `Clock::from_str`).
+fn split_unquoted(s: &str) -> Result<Vec<&str>, ParseClockError> {
+    let mut parts = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (0, false, false);
+    for (i, c) in s.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if quoted {
+        let entry = s[start..].trim().to_string();
+        return Err(ParseClockError::InvalidEntry { entry });
+    }
+    parts.push(&s[start..]);
+    Ok(parts)
+}
+
+/// Parses the (possibly quoted) actor at the start of a part of a clock
+/// entry, returning it along with the rest of the part (i.e. its events). If
+/// the part has no actor, `None` is returned.
+fn parse_actor(part: &str) -> Result<Option<(String, &str)>, ParseClockError> {
+    let invalid = || ParseClockError::InvalidEntry {
+        entry: part.trim().to_string(),
+    };
+    let quoted = match part.trim_start().strip_prefix('"') {
+        Some(quoted) => quoted,
+        None => {
+            return match part.split_once(':') {
+                Some((actor, events)) => {
+                    let actor = actor.trim();
+                    if actor.is_empty() || actor.contains(ACTOR_SPECIAL_CHARS) {
+                        return Err(invalid());
+                    }
+                    Ok(Some((actor.to_string(), events)))
+                }
+                None => Ok(None),
+            };
+        }
+    };
+
+    // unescape the actor until its closing quote, which must be followed by
+    // `:`
+    let mut actor = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                let events = quoted[i + 1..].trim_start().strip_prefix(':');
+                return match events {
+                    Some(events) => Ok(Some((actor, events))),
+                    None => Err(invalid()),
+                };
+            }
+            '\\' => match chars.next() {
+                Some((_, c @ ('"' | '\\'))) => actor.push(c),
+                _ => return Err(invalid()),
+            },
+            c => actor.push(c),
+        }
+    }
+    Err(invalid())
+}
+
+/// Parses the events of a clock entry (see `Clock::from_str`).
+fn parse_events<E: EventSet>(events: &[&str]) -> Result<E, ParseClockError> {
+    let mut eset = E::new();
+    // an entry with no events is a single empty part
+    if let [part] = events {
+        if part.trim().is_empty() {
+            return Ok(eset);
+        }
+    }
+
+    let invalid = || ParseClockError::InvalidEvents {
+        events: events.join(",").trim().to_string(),
+    };
+    // number of events parsed, which must be the number of events in the set
+    let mut count: u64 = 0;
+    for part in events.iter().map(|part| part.trim()) {
+        let parse = |event: &str| match event.trim().parse::<u64>() {
+            // event 0 is never part of the `Display` form, and events that
+            // the event set can't store are rejected
+            Ok(event) if event > 0 && check_seq::<E>(event).is_ok() => {
+                Ok(event)
+            }
+            _ => Err(invalid()),
+        };
+        let (start, end) = match part.split_once("..") {
+            Some((start, end)) => (parse(start)?, parse(end)?),
+            None => {
+                let event = parse(part)?;
+                (event, event)
+            }
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        eset.add_event_range(start, end);
+        count = count.checked_add(end - start + 1).ok_or_else(invalid)?;
+    }
+
+    // the event set may not represent the events parsed exactly (e.g. a
+    // `MaxSet` only has contiguous events), and it may also be that some
+    // ranges overlap: both are rejected
+    if eset.event_count() != count {
+        return Err(invalid());
+    }
+    Ok(eset)
+}
+
+// Wrapper used by `Clock::debug_full`.
+struct FullDebug<'a, A: Actor, E: EventSet>(&'a Clock<A, E>);
+
+impl<A: Actor, E: EventSet> fmt::Debug for FullDebug<'_, A, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt_with_limit(f, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn delta_round_trip() {
+        // both clocks share a large prefix and many extras, and the remote
+        // clock is missing some of them
+        let mut clock = AEClock::new();
+        let mut remote = AEClock::new();
+        for actor in 0..10 {
+            clock.add_range(&actor, 1, 1000);
+            remote.add_range(&actor, 1, 1000 - actor);
+            for event in (1002..2000).step_by(2) {
+                clock.add(&actor, event);
+                if event < 1900 {
+                    remote.add(&actor, event);
+                }
+            }
+        }
+        remote.add_range(&20, 1, 5);
+        clock.add(&30, 3);
+
+        let delta = clock.delta_since(&remote);
+        let delta_json = serde_json::to_string(&delta).unwrap();
+        let full_json = serde_json::to_string(&clock).unwrap();
+        assert!(delta_json.len() < full_json.len() / 4);
+
+        let mut expected = remote.clone();
+        expected.join(&clock);
+        remote.apply_delta(serde_json::from_str(&delta_json).unwrap());
+        assert_eq!(remote, expected);
+        assert!(remote.delta_since(&expected).is_empty());
+        assert!(clock.delta_since(&remote).is_empty());
+
+        // an odd number of run ends, runs starting at 0, and empty runs are
+        // rejected
+        let parse = |json: &str| {
+            serde_json::from_str::<DeltaClock<u64>>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(parse(r#"{"1":[1]}"#).contains("odd number of run ends"));
+        assert!(parse(r#"{"1":[0,3]}"#).contains("run starting at 0"));
+        assert!(parse(r#"{"1":[1,2,5,4]}"#).contains("empty run: [5, 4]"));
+        assert!(
+            serde_json::from_str::<DeltaClock<u64>>(r#"{"1":[3,3]}"#).is_ok()
+        );
+    }
+
+    #[test]
+    fn partial_cmp() {
+        let a = ("A", AboveExSet::from_events(vec![1, 2, 4]));
+        let b = ("B", AboveExSet::from_events(vec![1]));
+        let clock = Clock::from(vec![a.clone(), b.clone()]);
+
+        // identical clocks
+        assert_eq!(clock.partial_cmp(&clock.clone()), Some(Ordering::Equal));
+
+        // strict domination
+        let mut bigger = clock.clone();
+        bigger.add(&"A", 3);
+        assert_eq!(clock.partial_cmp(&bigger), Some(Ordering::Less));
+        assert_eq!(bigger.partial_cmp(&clock), Some(Ordering::Greater));
+        let smaller = Clock::from(vec![a.clone()]);
+        assert_eq!(smaller.partial_cmp(&clock), Some(Ordering::Less));
+
+        // missing actors and actors with no events have the same events, but
+        // they're not `==`, and so they're incomparable
+        let empty_b = Clock::from(vec![a.clone(), ("B", AboveExSet::new())]);
+        assert!(smaller.same_events(&empty_b));
+        assert_ne!(smaller, empty_b);
+        assert_eq!(smaller.partial_cmp(&empty_b), None);
+
+        // and so are event sets with the same events built differently
+        let raw_b =
+            Clock::from(vec![a.clone(), ("B", AboveExSet::from(0, [1]))]);
+        assert!(clock.same_events(&raw_b));
+        assert_eq!(clock.partial_cmp(&raw_b), None);
+
+        // disjoint actor sets
+        let other = Clock::from(vec![("C", AboveExSet::from_event(1))]);
+        assert_eq!(clock.partial_cmp(&other), None);
+        assert!(Clock::new() < other);
+
+        // concurrent clocks
+        let concurrent =
+            Clock::from(vec![("A", AboveExSet::from_events(vec![1, 2, 3])), b]);
+        assert_eq!(clock.partial_cmp(&concurrent), None);
+        assert!(!clock.leq(&concurrent) && !concurrent.leq(&clock));
+    }
+
+    #[test]
+    fn compact_all() {
+        fn extras<E: EventSet>(clock: &Clock<&str, E>) -> usize {
+            clock.iter().map(|(_, eset)| eset.events().1.len()).sum()
+        }
+
+        // out-of-order (but contiguous) dots kept as extras
+        let dots = vec![3, 1, 5, 2, 4];
+        let mut aeclock = Clock::from(vec![
+            ("A", AboveExSet::from(0, dots.clone())),
+            ("B", AboveExSet::from(2, vec![1, 3])),
+        ]);
+        let mut arclock = Clock::from(vec![
+            ("A", AboveRangeSet::from(0, dots)),
+            ("B", AboveRangeSet::from(2, vec![1, 3])),
+        ]);
+        // extra 1 of `B` is dropped by `AboveExSet::from`, as it's not above 2
+        assert_eq!(extras(&aeclock), 6);
+        assert_eq!(extras(&arclock), 7);
+
+        let expected = vec![("A", 5, vec![]), ("B", 3, vec![])];
+        let versions = (aeclock.version(), arclock.version());
+        aeclock.compact_all();
+        arclock.compact_all();
+        assert_eq!(extras(&aeclock), 0);
+        assert_eq!(extras(&arclock), 0);
+        assert_eq!(aeclock.snapshot(), expected);
+        assert_eq!(arclock.snapshot(), expected);
+        assert_eq!(aeclock.version(), versions.0 + 1);
+        assert_eq!(arclock.version(), versions.1 + 1);
+
+        // compacting again changes nothing
+        aeclock.compact_all();
+        arclock.compact_all();
+        assert_eq!(aeclock.version(), versions.0 + 1);
+        assert_eq!(arclock.version(), versions.1 + 1);
+    }
+
+    #[test]
+    fn join_capped_wide_ranges() {
+        // a wide range is a single stored range, and so it's kept
+        let mut other = ARClock::new();
+        other.add_range(&"A", 10, 50_000_000);
+        let mut clock = ARClock::new();
+        clock.add_range(&"A", 1, 5);
+        let version = clock.version();
+        clock.join_capped(&other, 1);
+        assert_eq!(clock.get(&"A").unwrap().stored_extras(), 1);
+        assert_eq!(clock.count_events_in_range(1, u64::MAX), 5 + 49_999_991);
+        assert_eq!(clock.version(), version + 1);
+
+        // collapsing after the join bumps the version once
+        let mut other = ARClock::new();
+        other.add_range(&"A", 100_000_000, 200_000_000);
+        let version = clock.version();
+        clock.join_capped(&other, 1);
+        assert_eq!(clock.snapshot(), vec![("A", 5, vec![])]);
+        assert_eq!(clock.version(), version + 1);
+    }
+
+    #[test]
+    fn pretty_wide_ranges() {
+        // events are not enumerated, and so this is instant
+        let clock = VClock::from(vec![("A", MaxSet::from(200_000_000))]);
+        assert_eq!(clock.pretty(), "A: 1-200000000");
+
+        let mut clock = ARClock::new();
+        clock.add_range(&"A", 1, 100_000_000);
+        clock.add_range(&"A", 100_000_002, 200_000_000);
+        clock.add(&"B", 3);
+        assert_eq!(clock.pretty(), "A: 1-100000000, 100000002-200000000\nB: 3");
+    }
+
+    #[test]
+    fn page_across_mutations() {
+        let mut clock = clock::vclock_from_seqs(vec![1, 2, 3, 4, 5]);
+        let actors = |page: &[(u64, MaxSet)]| -> Vec<u64> {
+            page.iter().map(|(actor, _)| *actor).collect()
+        };
+
+        let page = clock.page(None, 2);
+        assert_eq!(actors(&page), vec![0, 1]);
+
+        // actors added before the cursor are not returned, and removed actors
+        // are skipped
+        clock.next(&0);
+        clock.add(&10, 1);
+        clock.meet(&clock::vclock_from_seqs(vec![9, 9, 9]));
+        clock.add(&2, 3);
+        let page = clock.page(page.last().map(|(actor, _)| actor), 2);
+        assert_eq!(actors(&page), vec![2]);
+        assert_eq!(page[0].1, MaxSet::from(3));
+
+        // actors added after the cursor are returned
+        clock.add(&7, 1);
+        clock.add(&10, 1);
+        let page = clock.page(page.last().map(|(actor, _)| actor), 2);
+        assert_eq!(actors(&page), vec![7, 10]);
+        let page = clock.page(page.last().map(|(actor, _)| actor), 2);
+        assert!(page.is_empty());
+
+        assert!(clock.page(None, 0).is_empty());
+    }
+
+    #[test]
+    fn empty_clock() {
+        let clock = AEClock::<&str>::new();
+        let other = Clock::from(vec![("A", AboveExSet::from_events(1..=3))]);
+        assert!(clock.is_empty());
+
+        assert_eq!(clock.frontier(), VClock::new());
+        assert!(clock.frontier().is_empty());
+        for threshold in 1..=3 {
+            assert_eq!(clock.frontier_threshold(threshold), None);
+        }
+        assert_eq!(clock.missing_count(&other), 0);
+        assert_eq!(other.missing_count(&clock), 3);
+        assert!(clock.subtracted(&other).is_empty());
+        assert_eq!(clock.count_events_in_range(1, u64::MAX), 0);
+        assert!(clock.snapshot().is_empty());
+    }
+
+    #[test]
+    fn frontier_threshold_checked() {
+        let mut clock = VClock::new();
+        clock.add(&"A", 4);
+        clock.add(&"B", 2);
+
+        // with two actors, the gate only passes for `min_actors <= 2`
+        for min_actors in 0..=2 {
+            assert_eq!(
+                clock.frontier_threshold_checked(1, min_actors),
+                Some(4)
+            );
+            assert_eq!(
+                clock.frontier_threshold_checked(2, min_actors),
+                Some(2)
+            );
+            assert_eq!(clock.frontier_threshold_checked(3, min_actors), None);
+        }
+        assert_eq!(clock.frontier_threshold_checked(1, 3), None);
+
+        // once a third actor joins, it passes for `min_actors = 3`
+        clock.add(&"C", 3);
+        assert_eq!(clock.frontier_threshold_checked(1, 3), Some(4));
+        assert_eq!(clock.frontier_threshold_checked(2, 3), Some(3));
+
+        // an empty clock has no frontier threshold, even if the gate passes
+        let empty = VClock::<&str>::new();
+        assert_eq!(empty.frontier_threshold_checked(1, 0), None);
+        assert_eq!(empty.frontier_threshold_checked(1, 1), None);
+    }
+
+    #[test]
+    fn join_larger_clock() {
+        let mut clock = AEClock::new();
+        clock.add_range(&0, 1, 5);
+        clock.add(&0, 10);
+        let peer = AEClock::from(
+            (0..1000).map(|actor| (actor, AboveExSet::from_event(actor + 1))),
+        );
+
+        let mut expected = peer.clone();
+        expected.join(&clock);
+        clock.join(&peer);
+
+        // joining a much larger clock is the same as joining into it
+        assert_eq!(clock, expected);
+        assert_eq!(clock.len(), 1000);
+        assert!(clock.contains(&0, 10));
+        assert!(clock.contains(&999, 1000));
+    }
+
+    #[test]
+    fn hash_equal_clocks() {
+        // the same clock, built in different orders
+        let mut clock = AEClock::new();
+        clock.add(&"A", 3);
+        clock.add_range(&"A", 1, 2);
+        clock.add(&"B", 5);
+        let mut other = AEClock::new();
+        other.add(&"B", 5);
+        other.add_range(&"A", 1, 3);
+        assert_eq!(clock, other);
+
+        let clocks: std::collections::HashSet<_> =
+            vec![clock.clone(), other].into_iter().collect();
+        assert_eq!(clocks.len(), 1);
+
+        clock.add(&"B", 1);
+        assert!(!clocks.contains(&clock));
+    }
+
+    #[test]
+    fn remove_actors() {
+        let mut clock = VClock::new();
+        clock.add(&"A", 4);
+        clock.add(&"B", 2);
+        clock.add(&"C", 3);
+        clock.add(&"D", 1);
+        assert_eq!(clock.frontier_threshold(2), Some(3));
+        assert_eq!(clock.frontier_threshold(4), Some(1));
+
+        // once the actor with the highest frontier is removed, every
+        // threshold is passed by a lower frontier
+        let version = clock.version();
+        assert_eq!(clock.remove_actor(&"A"), Some(MaxSet::from(4)));
+        assert!(clock.dirty_since(version));
+        assert_eq!(clock.len(), 3);
+        assert_eq!(clock.frontier_threshold(1), Some(3));
+        assert_eq!(clock.frontier_threshold(2), Some(2));
+        assert_eq!(clock.frontier_threshold(3), Some(1));
+        assert_eq!(clock.frontier_threshold(4), None);
+
+        // removing an actor that's not in the clock changes nothing
+        let version = clock.version();
+        assert_eq!(clock.remove_actor(&"A"), None);
+        clock.retain_actors(|_| true);
+        assert!(!clock.dirty_since(version));
+
+        // once the actor with the lowest frontier is removed, the threshold
+        // of all remaining actors is passed by a higher frontier
+        clock.retain_actors(|actor| *actor != "D");
+        assert_eq!(clock.len(), 2);
+        assert_eq!(clock.frontier_threshold(2), Some(2));
+        assert_eq!(
+            clock.frontier(),
+            VClock::from(vec![("B", MaxSet::from(2)), ("C", MaxSet::from(3))])
+        );
+
+        clock.retain_actors(|_| false);
+        assert!(clock.is_empty());
+        assert_eq!(clock.frontier_threshold(1), None);
+    }
+
+    #[test]
+    fn debug_small() {
+        let clock = Clock::from(vec![
+            ("C", AboveExSet::new()),
+            ("B", AboveExSet::from_events(vec![1, 2, 5, 7])),
+            ("A", AboveExSet::from_events(1..=3)),
+        ]);
+        let expected = r#"AEClock {"A": 3, "B": (2 + {5, 7}), "C": 0}"#;
+        assert_eq!(format!("{:?}", clock), expected);
+        assert_eq!(clock.debug_full(), expected);
+
+        let clock = Clock::from(vec![
+            ("B", BelowExSet::from_events(vec![2, 4])),
+            ("A", BelowExSet::from_events(1..=3)),
+        ]);
+        let expected = r#"BEClock {"A": 3, "B": (4 - {1, 3})}"#;
+        assert_eq!(format!("{:?}", clock), expected);
+
+        let mut eset = AboveRangeSet::from_event(1);
+        eset.add_event_range(3, 4);
+        eset.add_event(6);
+        let clock = Clock::from(vec![
+            ("B", eset),
+            ("A", AboveRangeSet::from_events(1..=3)),
+        ]);
+        let expected = r#"ARClock {"A": 3, "B": (1 + {3: 4, 6: 6})}"#;
+        assert_eq!(format!("{:?}", clock), expected);
+
+        let clock = Clock::from(vec![
+            ("B", ExplicitSet::from_events(vec![2, 3])),
+            ("A", ExplicitSet::new()),
+        ]);
+        let expected = r#"XClock {"A": {}, "B": {2, 3}}"#;
+        assert_eq!(format!("{:?}", clock), expected);
+
+        let clock = clock::vclock_from_seqs(vec![10, 20]);
+        assert_eq!(format!("{:?}", clock), "VClock {0: 10, 1: 20}");
+    }
+
+    #[test]
+    fn debug_elided() {
+        // 100 exceptions: 3, 5, ..., 201
+        let extras = (1..=100).map(|i| 2 * i + 1);
+        let eset = AboveExSet::from_events(std::iter::once(1).chain(extras));
+        let clock = Clock::from(vec![("B", AboveExSet::new()), ("A", eset)]);
+
+        let shown: Vec<_> =
+            (1..=DEBUG_LIMIT).map(|i| (2 * i + 1).to_string()).collect();
+        let expected = format!(
+            r#"AEClock {{"A": (1 + {{{}, … (+{} more)}}), "B": 0}}"#,
+            shown.join(", "),
+            100 - DEBUG_LIMIT
+        );
+        assert_eq!(format!("{:?}", clock), expected);
+
+        let all: Vec<_> = (1..=100).map(|i| (2 * i + 1).to_string()).collect();
+        let expected =
+            format!(r#"AEClock {{"A": (1 + {{{}}}), "B": 0}}"#, all.join(", "));
+        assert_eq!(clock.debug_full(), expected);
+    }
+
+    #[test]
+    fn version_unchanged() {
+        let mut clock = AEClock::new();
+        clock.add(&"A", 1);
+        clock.add(&"A", 3);
+        clock.add_range(&"B", 1, 5);
+        let version = clock.version();
+
+        // adding existing events
+        assert!(!clock.add(&"A", 1));
+        assert!(!clock.add(&"A", 3));
+        assert!(!clock.add_range(&"B", 2, 4));
+
+        // joining and meeting with a subset
+        let subset = Clock::from(vec![("A", AboveExSet::from_event(1))]);
+        clock.join(&subset);
+        clock.join(&clock.clone());
+        clock.meet(&clock.clone());
+
+        assert_eq!(clock.version(), version);
+        assert!(!clock.dirty_since(version));
+    }
+
+    #[test]
+    fn version_changed() {
+        let mut clock = AEClock::new();
+        let mut version = clock.version();
+
+        let mut check_changed = |clock: &AEClock<&str>| {
+            let changed = clock.dirty_since(version);
+            version = clock.version();
+            changed
+        };
+
+        clock.next(&"A");
+        assert!(check_changed(&clock));
+
+        clock.add(&"A", 3);
+        assert!(check_changed(&clock));
+
+        clock.add_range(&"B", 1, 5);
+        assert!(check_changed(&clock));
+
+        clock.join(&Clock::from(vec![("C", AboveExSet::new())]));
+        assert!(check_changed(&clock));
+
+        clock.join(&Clock::from(vec![("A", AboveExSet::from_event(2))]));
+        assert!(check_changed(&clock));
+
+        clock.meet(&Clock::from(vec![("A", AboveExSet::from_event(1))]));
+        assert!(check_changed(&clock));
+        assert_eq!(clock.len(), 1);
+
+        // the version is not compared
+        let other = Clock::from(vec![("A", AboveExSet::from_event(1))]);
+        assert_ne!(clock.version(), other.version());
+        assert_eq!(clock, other);
+    }
+
+    #[test]
+    fn parse() {
+        let clock: BEClock<String> =
+            " { A : 1..3 , 7 ,B:2,4..5, C: } ".parse().unwrap();
+        let expected = BEClock::from(vec![
+            ("A".to_string(), BelowExSet::from(7, vec![4, 5, 6])),
+            ("B".to_string(), BelowExSet::from(5, vec![1, 3])),
+            ("C".to_string(), BelowExSet::new()),
+        ]);
+        assert_eq!(clock, expected);
+        assert_eq!(clock.to_string(), "{A: 1..3,7, B: 2,4..5, C: }");
+
+        let clock: VClock<String> = "{}".parse().unwrap();
+        assert!(clock.is_empty());
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        fn check<E: EventSet + fmt::Display>(events: Vec<Vec<u64>>) {
+            let clock: Clock<String, E> = Clock::from(
+                events.into_iter().enumerate().map(|(actor, events)| {
+                    (actor.to_string(), E::from_events(events))
+                }),
+            );
+            let parsed = clock.to_string().parse::<Clock<String, E>>();
+            assert_eq!(
+                parsed.map(|parsed| parsed.snapshot()),
+                Ok(clock.snapshot())
+            );
+        }
+
+        let events = || {
+            vec![
+                vec![],
+                vec![1, 2, 3],
+                vec![2, 4, 5, 6, 10],
+                (1..=100).chain(200..=300).collect(),
+            ]
+        };
+        check::<AboveExSet>(events());
+        check::<AboveRangeSet>(events());
+        check::<BelowExSet>(events());
+        check::<BelowRangeSet>(events());
+        check::<ExplicitSet>(events());
+        check::<MaxSet>(vec![vec![], vec![5], vec![1, 2]]);
+    }
+
+    #[test]
+    fn parse_quoted_actors() {
+        let actors = [
+            "a:b",
+            "a, b",
+            "{a}",
+            r#"say "hi""#,
+            r"back\slash",
+            " a ",
+            "",
+            "plain",
+        ];
+        let clock =
+            AEClock::from(actors.iter().enumerate().map(|(i, actor)| {
+                let eset = AboveExSet::from_events(1..=i as u64 + 1);
+                (actor.to_string(), eset)
+            }));
+        let display = clock.to_string();
+        assert!(display.contains(r#""a:b": 1"#));
+        assert!(display.contains(r#""say \"hi\"": 1..4"#));
+        assert!(display.contains(r#""back\\slash": 1..5"#));
+        assert!(display.contains("plain: 1..8"));
+        assert_eq!(display.parse(), Ok(clock));
+
+        let parse = |s: &str| s.parse::<AEClock<String>>().unwrap_err();
+        let invalid_entry = |entry: &str| ParseClockError::InvalidEntry {
+            entry: entry.to_string(),
+        };
+        assert_eq!(parse(r#"{"a: 1}"#), invalid_entry(r#""a: 1"#));
+        assert_eq!(parse(r#"{"a" 1}"#), invalid_entry(r#""a" 1"#));
+        assert_eq!(parse(r#"{"a\b": 1}"#), invalid_entry(r#""a\b": 1"#));
+        assert_eq!(parse(r#"{a"b: 1}"#), invalid_entry(r#"a"b: 1"#));
+    }
+
+    #[test]
+    fn parse_malformed() {
+        let parse = |s: &str| s.parse::<AEClock<String>>().unwrap_err();
+        let invalid_entry = |entry: &str| ParseClockError::InvalidEntry {
+            entry: entry.to_string(),
+        };
+        let invalid_events = |events: &str| ParseClockError::InvalidEvents {
+            events: events.to_string(),
+        };
+
+        assert_eq!(parse(""), ParseClockError::MissingBraces);
+        assert_eq!(parse("A: 1"), ParseClockError::MissingBraces);
+        assert_eq!(parse("{A: 1"), ParseClockError::MissingBraces);
+        assert_eq!(parse("{1, A: 1}"), invalid_entry("1"));
+        assert_eq!(parse("{: 1}"), invalid_entry(": 1"));
+        assert_eq!(
+            parse("{A: 1, A: 2}"),
+            ParseClockError::DuplicateActor {
+                actor: "A".to_string()
+            }
+        );
+        assert_eq!(parse("{A: 0}"), invalid_events("0"));
+        assert_eq!(parse("{A: 1,,2}"), invalid_events("1,,2"));
+        assert_eq!(parse("{A: 3..1}"), invalid_events("3..1"));
+        assert_eq!(parse("{A: 1..}"), invalid_events("1.."));
+        assert_eq!(parse("{A: x}"), invalid_events("x"));
+        assert_eq!(parse("{A: -1}"), invalid_events("-1"));
+        assert_eq!(parse("{A: 1..3,2..4}"), invalid_events("1..3,2..4"));
+
+        // events that the event set can't store
+        let wide = "{A: 5000000000}".parse::<Clock<String, MaxSetOf<u32>>>();
+        assert_eq!(wide, Err(invalid_events("5000000000")));
+        let range =
+            "{A: 1..5000000000}".parse::<Clock<String, MaxSetOf<u32>>>();
+        assert_eq!(range, Err(invalid_events("1..5000000000")));
+        let max = format!("{{A: 1..{}}}", u32::MAX);
+        assert!(max.parse::<Clock<String, MaxSetOf<u32>>>().is_ok());
+
+        // events that the event set can't represent exactly
+        let parse = |s: &str| s.parse::<VClock<String>>().unwrap_err();
+        assert_eq!(parse("{A: 1..3,7}"), invalid_events("1..3,7"));
+        assert_eq!(parse("{A: 5}"), invalid_events("5"));
+        assert_eq!(parse("{A: 2..5}"), invalid_events("2..5"));
+    }
+
+    #[test]
+    fn delta_wide_ranges() {
+        // events are not enumerated, and so this is instant
+        let mut clock = ARClock::new();
+        clock.add_range(&"A", 1, 100_000_000);
+        clock.add_range(&"B", 1_000_000, 200_000_000);
+        let mut remote = ARClock::new();
+        remote.add_range(&"A", 1, 10);
+        remote.add_range(&"A", 50_000_000, 100_000_000);
+
+        let delta = clock.delta_since(&remote);
+        let json = serde_json::to_string(&delta).unwrap();
+        let expected: DeltaClock<&str> = serde_json::from_str(
+            r#"{"A":[11,49999999],"B":[1000000,200000000]}"#,
+        )
+        .unwrap();
+        assert_eq!(delta, expected, "{}", json);
+
+        remote.apply_delta(delta);
+        assert_eq!(remote, clock);
+    }
+
+    #[test]
+    fn hash_wide_ranges() {
+        fn hash<E: EventSet>(clock: &Clock<&str, E>) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            clock.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        // events are not enumerated, and so this is instant
+        let mut clock = ARClock::new();
+        clock.add_range(&"A", 1, 1 << 40);
+        clock.add_range(&"A", (1 << 41) + 1, 1 << 42);
+        let mut other = ARClock::new();
+        other.add_range(&"A", (1 << 41) + 1, 1 << 42);
+        other.add_range(&"A", 1, 1 << 40);
+        other.add_range(&"B", 2, 1);
+        assert!(clock.same_events(&other));
+        assert_eq!(hash(&clock), hash(&other));
+
+        other.add(&"A", (1 << 40) + 1);
+        assert_ne!(hash(&clock), hash(&other));
+    }
+
+    #[test]
+    fn contains_empty_range() {
+        // an empty range is contained, whether the actor is in the clock or
+        // not, as in `EventSet::contains_all_range`
+        let mut clock = ARClock::new();
+        clock.add_range(&"A", 5, 10);
+        assert!(clock.contains_range(&"A", 3, 2));
+        assert!(clock.contains_range(&"B", 3, 2));
+        assert!(clock.get(&"A").unwrap().contains_all_range(3, 2));
+        assert!(ARClock::new().contains_range(&"A", 1, 0));
+    }
+
+    #[test]
+    fn narrow_seq_rejects_wide_events() {
+        let wide = u64::from(u32::MAX) + 1;
+        let mut clock = Clock::<_, MaxSetOf<u32>>::new();
+        assert!(clock.add(&"A", u64::from(u32::MAX)));
+        let version = clock.version();
+        assert!(!clock.add(&"A", wide));
+        assert!(!clock.add_range(&"B", 1, wide));
+        assert_eq!(clock.get(&"A").unwrap().frontier(), u64::from(u32::MAX));
+        assert_eq!(clock.get(&"B"), None);
+        assert!(!clock.add_range(&"B", 2, 1));
+        assert_eq!(clock.get(&"B"), None);
+        assert_eq!(clock.version(), version);
+
+        // `try_add` and `try_add_range` leave the clock unchanged
+        let error = SeqOutOfRange {
+            seq: wide,
+            max: u64::from(u32::MAX),
+        };
+        assert_eq!(clock.try_add(&"C", wide), Err(error.clone()));
+        assert_eq!(clock.try_add_range(&"C", 1, wide), Err(error));
+        assert_eq!(clock.get(&"C"), None);
+        assert_eq!(clock.try_add_range(&"C", wide, 1), Ok(false));
+
+        // events coming from a `u64` clock are rejected as well
+        let mut remote = VClock::new();
+        remote.add(&"B", wide);
+        let delta = remote.delta_since(&VClock::new());
+        let mut narrow = Clock::<_, MaxSetOf<u32>>::new();
+        narrow.apply_delta(delta);
+        assert_eq!(narrow.get(&"B"), None);
     }
 }
@@ -0,0 +1,152 @@
+//! This module contains `Event`, an opt-in newtype for events.
+//!
+//! Events are raw `u64`s across the crate. `Event` can be used instead to
+//! avoid mixing them up with other `u64`s: methods such as `Clock::add` and
+//! `Clock::contains` accept both (through the `IntoSeq` trait).
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! let mut clock = VClock::new();
+//! let event = Event::from(clock.next(&"A"));
+//! assert!(clock.contains(&"A", event));
+//! assert!(clock.contains(&"A", 1));
+//!
+//! clock.add(&"A", event.next());
+//! assert!(clock.contains(&"A", 2));
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// An event. Serialized as a bare integer.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[serde(transparent)]
+pub struct Event(pub u64);
+
+impl Event {
+    /// Returns the event after this one.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// assert_eq!(Event(1).next(), Event(2));
+    /// ```
+    pub fn next(self) -> Self {
+        Event(self.0 + 1)
+    }
+
+    /// Returns the event before this one, or `None` if this is the first
+    /// event (or event 0).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// assert_eq!(Event(2).prev(), Some(Event(1)));
+    /// assert_eq!(Event(1).prev(), None);
+    /// ```
+    pub fn prev(self) -> Option<Self> {
+        if self.0 > 1 {
+            Some(Event(self.0 - 1))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<u64> for Event {
+    fn from(seq: u64) -> Self {
+        Event(seq)
+    }
+}
+
+impl From<Event> for u64 {
+    fn from(event: Event) -> Self {
+        event.0
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Conversion into a raw event, implemented by `u64` and `Event`.
+///
+/// Methods taking `impl IntoSeq` accept both. As opposed to `Into<u64>`,
+/// integer literals (e.g. `clock.add(&"A", 1)`) are still inferred as `u64`.
+pub trait IntoSeq {
+    /// Returns the raw event.
+    fn into_seq(self) -> u64;
+}
+
+impl IntoSeq for u64 {
+    fn into_seq(self) -> u64 {
+        self
+    }
+}
+
+impl IntoSeq for Event {
+    fn into_seq(self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use serde::{Deserialize, Serialize};
+
+    #[test]
+    fn both_call_forms() {
+        let mut clock_a = AEClock::new();
+        let mut clock_b = AEClock::new();
+
+        assert_eq!(clock_a.add(&"A", 3), clock_b.add(&"A", Event(3)));
+        assert_eq!(
+            clock_a.add_range(&"A", 5, 6),
+            clock_b.add_range(&"A", Event(5), Event(6))
+        );
+        assert_eq!(clock_a, clock_b);
+
+        for seq in 0..10 {
+            assert_eq!(
+                clock_a.contains(&"A", seq),
+                clock_b.contains(&"A", Event(seq))
+            );
+        }
+    }
+
+    #[test]
+    fn serde() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Message {
+            actor: String,
+            event: Event,
+        }
+
+        let message = Message {
+            actor: String::from("A"),
+            event: Event(10),
+        };
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(json, r#"{"actor":"A","event":10}"#);
+        assert_eq!(serde_json::from_str::<Message>(&json).unwrap(), message);
+    }
+}
@@ -0,0 +1,61 @@
+//! This module contains the hasher used by all hash-backed data structures.
+//!
+//! By default, the standard library's `RandomState` is used, and thus the
+//! iteration order of these data structures may change from run to run.
+//!
+//! With the `deterministic` feature enabled, a SipHash hasher with fixed
+//! (zero) keys is used instead, making the iteration order stable across runs
+//! and platforms. This is meant for reproducing issues in tests, not for
+//! production: with fixed keys, an adversary that controls the keys being
+//! inserted can force collisions (HashDoS).
+
+use std::collections;
+#[cfg(feature = "deterministic")]
+use std::collections::hash_map::DefaultHasher;
+#[cfg(feature = "deterministic")]
+use std::hash::BuildHasherDefault;
+
+/// The hasher builder used by all hash-backed data structures.
+#[cfg(feature = "deterministic")]
+pub type BuildHasher = BuildHasherDefault<DefaultHasher>;
+
+/// The hasher builder used by all hash-backed data structures.
+#[cfg(not(feature = "deterministic"))]
+pub type BuildHasher = collections::hash_map::RandomState;
+
+/// A `HashMap` using the crate's hasher builder.
+pub type HashMap<K, V> = collections::HashMap<K, V, BuildHasher>;
+
+/// A `HashSet` using the crate's hasher builder.
+pub type HashSet<T> = collections::HashSet<T, BuildHasher>;
+
+#[cfg(all(test, feature = "deterministic"))]
+mod tests {
+    use crate::*;
+
+    fn populated_clock() -> BEClock<u64> {
+        let mut clock = BEClock::new();
+        for actor in 1..=100 {
+            clock.add_range(&actor, 1, actor);
+            clock.add(&actor, actor + 10);
+        }
+        clock
+    }
+
+    #[test]
+    fn iteration_order() {
+        // create two clocks in separate hash map instances
+        let clock_a = populated_clock();
+        let clock_b = populated_clock();
+
+        // the iteration order (of actors and of exceptions) is the same
+        let debug = |clock: &BEClock<u64>| {
+            let entries: Vec<_> = clock
+                .iter()
+                .map(|(actor, eset)| (actor, eset.events()))
+                .collect();
+            format!("{:?}", entries)
+        };
+        assert_eq!(debug(&clock_a), debug(&clock_b));
+    }
+}
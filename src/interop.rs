@@ -0,0 +1,66 @@
+//! This module contains traits that allow this crate's data structures to be
+//! used by generic CRDT frameworks (e.g. [rust-crdt](https://github.com/rust-crdt/rust-crdt)).
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! // a generic function that records a new write in any causal context
+//! fn write<C: CausalContext<&'static str>>(ctx: &mut C) -> Dot<&'static str> {
+//!     let dot = ctx.next_dot(&"A");
+//!     assert!(ctx.contains_dot(&dot));
+//!     dot
+//! }
+//!
+//! let mut clock = VClock::new();
+//! assert_eq!(write(&mut clock), ("A", 1));
+//! assert_eq!(write(&mut clock), ("A", 2));
+//! assert_eq!(clock.dots().collect::<Vec<_>>(), vec![("A", 1), ("A", 2)]);
+//! ```
+
+use crate::*;
+
+/// A dot identifies an event: the actor that generated it, and its sequence
+/// number.
+pub type Dot<A> = (A, u64);
+
+/// A causal context, i.e. a compact representation of a set of dots.
+pub trait CausalContext<A: Actor> {
+    /// Generates the next dot of `actor`, adding it to the context.
+    fn next_dot(&mut self, actor: &A) -> Dot<A>;
+
+    /// Checks if a dot is part of the context.
+    fn contains_dot(&self, dot: &Dot<A>) -> bool;
+
+    /// Merges `other` context into `self`.
+    fn join(&mut self, other: &Self);
+
+    /// Returns an iterator with all dots in the context.
+    fn dots(&self) -> Box<dyn Iterator<Item = Dot<A>> + '_>;
+}
+
+impl<A: Actor, E: EventSet> CausalContext<A> for Clock<A, E> {
+    /// Generates the next dot of `actor`, adding it to the clock.
+    fn next_dot(&mut self, actor: &A) -> Dot<A> {
+        let seq = self.next(actor);
+        (actor.clone(), seq)
+    }
+
+    /// Checks if a dot is part of the clock.
+    fn contains_dot(&self, (actor, seq): &Dot<A>) -> bool {
+        self.contains(actor, *seq)
+    }
+
+    /// Merges `other` clock into `self`.
+    fn join(&mut self, other: &Self) {
+        Clock::join(self, other)
+    }
+
+    /// Returns an iterator with all dots in the clock. Dots of the same actor
+    /// are sorted (ASC), but actors are in no specific order.
+    fn dots(&self) -> Box<dyn Iterator<Item = Dot<A>> + '_> {
+        Box::new(self.iter().flat_map(|(actor, eset)| {
+            eset.iter().map(move |seq| (actor.clone(), seq))
+        }))
+    }
+}
@@ -1,28 +1,63 @@
-// This module contains the definition of `Count`, `Actor` and `EventSet`
-// traits.
+// This module contains the definition of `Count`, `CountSub`, `Actor` and
+// `EventSet` traits.
 mod traits;
 
+// This module contains the `Event` newtype.
+mod event;
+
+// This module contains the hasher used by hash-backed data structures.
+mod hash;
+
 // This module contains implementations of the `EventSet` trait.
 mod set;
 
 // This module contains the implementation of a Clock.
 pub mod clock;
 
+// This module contains a read-only view of a Clock.
+pub mod view;
+
+// This module contains builders for event sets and clocks.
+pub mod builder;
+
+// This module contains traits for interoperability with CRDT frameworks.
+pub mod interop;
+
 // This module contains the implementation of a Multi Set.
 pub mod multiset;
 
 // This module contains the implementation of Threshold Clock.
 pub mod tclock;
 
+// This module contains the implementation of a persistent Clock.
+#[cfg(feature = "persistent")]
+pub mod pclock;
+
+// This module contains a simulation harness for tests.
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
 // Top-level re-exports.
-pub use crate::clock::{AEClock, ARClock, BEClock, Clock, VClock};
+pub use crate::builder::{BuildError, ClockBuilder, EventSetBuilder};
+pub use crate::clock::{
+    AEClock, ARClock, BEClock, BRClock, Clock, ParseClockError, SeqOutOfRange,
+    VClock, XClock,
+};
+pub use crate::event::{Event, IntoSeq};
+pub use crate::interop::{CausalContext, Dot};
 pub use crate::multiset::MultiSet;
+#[cfg(feature = "persistent")]
+pub use crate::pclock::PClock;
 pub use crate::set::AboveExSet;
 pub use crate::set::AboveRangeSet;
 pub use crate::set::BelowExSet;
-pub use crate::set::MaxSet;
-pub use crate::tclock::TClock;
-pub use crate::traits::{subtract_iter, Actor, Count, EventSet};
+pub use crate::set::BelowRangeSet;
+pub use crate::set::ExplicitSet;
+pub use crate::set::{choose_representation, Representation};
+pub use crate::set::{MaxSet, MaxSetOf};
+pub use crate::tclock::{PreparedTClock, Report, TClock};
+pub use crate::traits::{subtract_iter, Actor, Count, CountSub, EventSet, Seq};
+pub use crate::view::ClockView;
 
 // Tests
 #[cfg(test)]
@@ -19,7 +19,7 @@
 //! assert_eq!(mset.threshold(3), vec![&42]);
 //! ```
 
-use crate::Count;
+use crate::{Count, CountSub};
 use std::collections::btree_map::{self, BTreeMap};
 use std::iter::FromIterator;
 
@@ -91,6 +91,22 @@ impl<E: Ord, C: Count> MultiSet<E, C> {
         count.add(by);
     }
 
+    /// Checks if the `MultiSet` has no elements.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut mset = MultiSet::new();
+    /// assert!(mset.is_empty());
+    ///
+    /// mset.add_elem(17, 1);
+    /// assert!(!mset.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.occurrences.is_empty()
+    }
+
     /// Returns the `Count` of an element.
     ///
     /// # Examples
@@ -123,6 +139,61 @@ impl<E: Ord, C: Count> MultiSet<E, C> {
     }
 }
 
+impl<E: Ord, C: CountSub> MultiSet<E, C> {
+    /// Removes a single element (with an associated count) from the
+    /// `MultiSet`. If the element count reaches zero, the element is removed.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut mset = MultiSet::new();
+    /// mset.add_elem(17, 2);
+    ///
+    /// mset.remove_elem(17, 1);
+    /// assert_eq!(mset.count(&17), 1);
+    ///
+    /// mset.remove_elem(17, 1);
+    /// assert_eq!(mset.count(&17), 0);
+    /// assert!(mset.is_empty());
+    /// ```
+    pub fn remove_elem(&mut self, elem: E, by: C) {
+        if let btree_map::Entry::Occupied(mut entry) =
+            self.occurrences.entry(elem)
+        {
+            // decrease element count
+            let count = entry.get_mut();
+            count.sub(by);
+
+            // remove the element if its count is now zero
+            if *count == Count::zero() {
+                entry.remove();
+            }
+        }
+    }
+
+    /// Removes several elements (each with an associated count) from the
+    /// `MultiSet`. Elements whose count reaches zero are removed, and elements
+    /// that are not in the `MultiSet` are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut mset = MultiSet::from(vec![(17, 1), (23, 2)]);
+    ///
+    /// mset.remove(vec![(17, 1), (23, 1), (42, 1)]);
+    /// assert_eq!(mset.count(&17), 0);
+    /// assert_eq!(mset.count(&23), 1);
+    /// assert_eq!(mset.count(&42), 0);
+    /// ```
+    pub fn remove<I: IntoIterator<Item = (E, C)>>(&mut self, iter: I) {
+        for (elem, by) in iter {
+            self.remove_elem(elem, by);
+        }
+    }
+}
+
 impl<E: Ord> MultiSet<E, u64> {
     /// Returns the elements in the `MultiSet` such that its multiplicity is
     /// bigger or equal than a given [threshold](https://vitorenes.org/post/2018/11/threshold-union/).
@@ -156,9 +227,121 @@ impl<E: Ord> MultiSet<E, u64> {
             .map(|(elem, _)| elem)
     }
 
+    /// Consumes the `MultiSet`, returning the elements such that its
+    /// multiplicity is bigger or equal than a given threshold (sorted ASC).
+    /// As opposed to `MultiSet::threshold_iter`, elements are moved out of
+    /// the `MultiSet`, and so they don't need to be cloned to outlive it.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut mset = MultiSet::new();
+    /// mset.add(vec![(String::from("a"), 2), (String::from("b"), 1)]);
+    ///
+    /// let winners: Vec<String> = mset.into_threshold_iter(2).collect();
+    /// assert_eq!(winners, vec![String::from("a")]);
+    /// ```
+    pub fn into_threshold_iter(
+        self,
+        threshold: u64,
+    ) -> impl Iterator<Item = E> {
+        self.occurrences
+            .into_iter()
+            .filter(move |(_, count)| *count >= threshold)
+            .map(|(elem, _)| elem)
+    }
+
+    /// Consumes the `MultiSet`, returning the elements such that its
+    /// multiplicity is bigger or equal than a given threshold (sorted ASC).
+    /// See `MultiSet::into_threshold_iter`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut mset = MultiSet::new();
+    /// mset.add(vec![(String::from("a"), 2), (String::from("b"), 1)]);
+    ///
+    /// assert_eq!(mset.into_threshold(1), vec!["a", "b"]);
+    /// ```
+    pub fn into_threshold(self, threshold: u64) -> Vec<E> {
+        self.into_threshold_iter(threshold).collect()
+    }
+
+    /// Returns (clones of) the elements in the `MultiSet` such that its
+    /// multiplicity is bigger or equal than a given threshold. As opposed to
+    /// `MultiSet::threshold`, the result doesn't borrow the `MultiSet`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let winners = {
+    ///     let mut mset = MultiSet::new();
+    ///     mset.add(vec![(String::from("a"), 2), (String::from("b"), 1)]);
+    ///     mset.threshold_owned(2)
+    /// };
+    /// assert_eq!(winners, vec![String::from("a")]);
+    /// ```
+    pub fn threshold_owned(&self, threshold: u64) -> Vec<E>
+    where
+        E: Clone,
+    {
+        self.threshold_iter(threshold).cloned().collect()
+    }
+
     pub fn elem_count(&self) -> usize {
         self.occurrences.len()
     }
+
+    /// Adds a single element (with an associated count) to the `MultiSet`,
+    /// keeping at most `cap` distinct elements: while there are more than
+    /// `cap` elements, the element with the lowest count is evicted (ties are
+    /// broken by evicting the lowest element).
+    ///
+    /// This is an approximation: an evicted element loses its count, and if
+    /// it's added again, it starts counting from zero. As counts are never
+    /// over-estimated, `threshold` returns a subset of the elements that would
+    /// be returned without eviction, i.e. its result is a lower bound.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut mset = MultiSet::new();
+    /// mset.add_capped(17, 3, 2);
+    /// mset.add_capped(23, 1, 2);
+    /// mset.add_capped(42, 2, 2);
+    ///
+    /// // 23 had the lowest count and was evicted
+    /// assert_eq!(mset.elem_count(), 2);
+    /// assert_eq!(mset.threshold(1), vec![&17, &42]);
+    ///
+    /// // if 23 is added again, its count starts from zero
+    /// mset.add_capped(23, 3, 2);
+    /// assert_eq!(mset.count(&23), 3);
+    /// assert_eq!(mset.threshold(1), vec![&17, &23]);
+    /// ```
+    pub fn add_capped(&mut self, elem: E, by: u64, cap: usize) {
+        self.add_elem(elem, by);
+
+        while self.occurrences.len() > cap {
+            // find the lowest count
+            let min = match self.occurrences.values().min() {
+                Some(&min) => min,
+                None => break,
+            };
+
+            // evict the first element with that count
+            let mut evicted = false;
+            self.occurrences.retain(|_, &mut count| {
+                let evict = !evicted && count == min;
+                evicted = evicted || evict;
+                !evict
+            });
+        }
+    }
 }
 
 pub struct IntoIter<E: Ord, C: Count>(btree_map::IntoIter<E, C>);
@@ -193,3 +376,30 @@ impl<E: Ord, C: Count> IntoIterator for MultiSet<E, C> {
         IntoIter(self.occurrences.into_iter())
     }
 }
+
+impl<E: Ord, C: Count> FromIterator<(E, C)> for MultiSet<E, C> {
+    /// Creates a new `MultiSet` from an iterator of tuples (elem, elem count),
+    /// e.g. another element-to-count aggregator. As opposed to
+    /// `MultiSet::from`, counts of repeated elements are added.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use threshold::*;
+    ///
+    /// let mut counts = HashMap::new();
+    /// counts.insert(17, 2);
+    /// counts.insert(23, 1);
+    ///
+    /// let mset: MultiSet<_, u64> = counts.into_iter().collect();
+    /// assert_eq!(mset.threshold(2), vec![&17]);
+    ///
+    /// let mset: MultiSet<_, u64> = vec![(17, 1), (17, 1)].into_iter().collect();
+    /// assert_eq!(mset.count(&17), 2);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = (E, C)>>(iter: I) -> Self {
+        let mut mset = MultiSet::new();
+        mset.add(iter);
+        mset
+    }
+}
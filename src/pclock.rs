@@ -0,0 +1,365 @@
+//! This module contains an implementation of a persistent clock, i.e. a clock
+//! backed by a persistent map (`im::OrdMap`).
+//!
+//! Cloning a `PClock` is O(1), and versions of the same clock share all
+//! entries that haven't changed: mutating a clone copies only the touched
+//! nodes of the map (and the touched event sets). This makes `PClock`
+//! suitable to keep many versions of a large clock around.
+//!
+//! The API mirrors `Clock`'s, and conversions between both are provided.
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! let mut clock = PClock::<_, MaxSet>::new();
+//! clock.next(&"A");
+//!
+//! // keep the current version and keep changing the clock
+//! let version = clock.clone();
+//! clock.next(&"A");
+//!
+//! assert!(version.contains(&"A", 1));
+//! assert!(!version.contains(&"A", 2));
+//! assert!(clock.contains(&"A", 2));
+//! ```
+
+use crate::*;
+use im::ordmap::{self, OrdMap};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PClock<A: Actor, E: EventSet> {
+    /// Mapping from actor identifier to an event set
+    clock: OrdMap<A, E>,
+}
+
+impl<A: Actor, E: EventSet> PClock<A, E> {
+    /// Returns a new `PClock` instance.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        PClock {
+            clock: OrdMap::new(),
+        }
+    }
+
+    /// Returns a new `PClock` mapping each actor to a bottom entry.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let actors = vec!["A", "B"];
+    /// let pclock = PClock::<_, MaxSet>::with(actors);
+    /// assert_eq!(pclock.len(), 2);
+    /// assert!(!pclock.contains(&"A", 1));
+    /// ```
+    pub fn with<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        PClock {
+            clock: iter.into_iter().map(|actor| (actor, E::new())).collect(),
+        }
+    }
+
+    /// Creates a `PClock` from an iterator of tuples (actor identifier and
+    /// event set), e.g. a `Clock`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let clock = VClock::from(vec![("A", MaxSet::from(10))]);
+    /// let pclock = PClock::from(clock.clone());
+    /// assert!(pclock.contains(&"A", 10));
+    ///
+    /// // and back
+    /// assert_eq!(Clock::from(pclock), clock);
+    /// ```
+    pub fn from<I: IntoIterator<Item = (A, E)>>(iter: I) -> Self {
+        PClock {
+            clock: iter.into_iter().collect(),
+        }
+    }
+
+    /// Returns a `Clock` with the same entries.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut pclock = PClock::new();
+    /// pclock.add(&"A", 1);
+    ///
+    /// let clock: VClock<_> = pclock.to_clock();
+    /// assert!(clock.contains(&"A", 1));
+    /// ```
+    pub fn to_clock(&self) -> Clock<A, E> {
+        Clock::from(self.clone())
+    }
+
+    /// Returns the number of actors in the clock.
+    pub fn len(&self) -> usize {
+        self.clock.len()
+    }
+
+    /// Returns `true` if the clock has no actors.
+    pub fn is_empty(&self) -> bool {
+        self.clock.is_empty()
+    }
+
+    /// Returns the next event for the `actor` while updating its entry in the
+    /// clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut pclock = PClock::<_, MaxSet>::new();
+    /// assert_eq!(pclock.next(&"A"), 1);
+    /// assert_eq!(pclock.next(&"A"), 2);
+    /// assert_eq!(pclock.next(&"B"), 1);
+    /// ```
+    pub fn next(&mut self, actor: &A) -> u64 {
+        self.upsert(actor, |eset| eset.next_event(), || (E::from_event(1), 1))
+    }
+
+    /// If the actor is in already the clock, its entry is updated using
+    /// function `map`. Otherwise, the output of `default` is inserted.
+    fn upsert<F, D, R>(&mut self, actor: &A, mut map: F, default: D) -> R
+    where
+        F: FnMut(&mut E) -> R,
+        D: FnOnce() -> (E, R),
+    {
+        match self.clock.get_mut(actor) {
+            Some(eset) => map(eset),
+            None => {
+                let (value, result) = default();
+                self.clock.insert(actor.clone(), value);
+                result
+            }
+        }
+    }
+
+    /// Retrieves the event set associated with some `actor`.
+    pub fn get(&self, actor: &A) -> Option<&E> {
+        self.clock.get(actor)
+    }
+
+    /// Adds an event to the clock.
+    /// If the clock did not have this event present, `true` is returned.
+    /// If the clock did have this event present, `false` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut pclock = PClock::<_, AboveExSet>::new();
+    /// assert!(pclock.add(&"A", 2));
+    /// assert!(!pclock.add(&"A", 2));
+    /// assert!(!pclock.contains(&"A", 1));
+    /// assert!(pclock.contains(&"A", Event(2)));
+    /// ```
+    pub fn add(&mut self, actor: &A, seq: impl IntoSeq) -> bool {
+        let seq = seq.into_seq();
+        self.upsert(
+            actor,
+            |eset| eset.add_event(seq),
+            || (E::from_event(seq), true),
+        )
+    }
+
+    /// Adds a range of events to the clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut pclock = PClock::<_, MaxSet>::new();
+    /// pclock.add_range(&"A", 10, 20);
+    /// assert!(pclock.contains(&"A", 10));
+    /// assert!(pclock.contains(&"A", 20));
+    /// assert!(!pclock.contains(&"A", 21));
+    /// ```
+    pub fn add_range(
+        &mut self,
+        actor: &A,
+        start: impl IntoSeq,
+        end: impl IntoSeq,
+    ) -> bool {
+        let (start, end) = (start.into_seq(), end.into_seq());
+        self.upsert(
+            actor,
+            |eset| eset.add_event_range(start, end),
+            || (E::from_event_range(start, end), true),
+        )
+    }
+
+    /// Checks if an event is part of the clock.
+    pub fn contains(&self, actor: &A, seq: impl IntoSeq) -> bool {
+        let seq = seq.into_seq();
+        self.clock.get(actor).is_some_and(|eset| eset.is_event(seq))
+    }
+
+    /// Returns the clock frontier.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = ("A", AboveExSet::from_events(vec![1, 2, 4]));
+    /// let b = ("B", AboveExSet::from_events(vec![1, 2, 3, 5, 6]));
+    /// let pclock = PClock::from(vec![a, b]);
+    ///
+    /// assert_eq!(
+    ///     pclock.frontier(),
+    ///     PClock::from(vec![("A", MaxSet::from(2)), ("B", MaxSet::from(3))])
+    /// );
+    /// ```
+    pub fn frontier(&self) -> PClock<A, MaxSet> {
+        let frontier = self.clock.iter().map(|(actor, eset)| {
+            (actor.clone(), MaxSet::from(eset.frontier()))
+        });
+        PClock::from(frontier)
+    }
+
+    /// Merges clock `other` passed as argument into `self`.
+    /// After merge, all events in `other` are events in `self`.
+    ///
+    /// Entries of `self` that don't change are still shared with previous
+    /// versions of `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut pclock_a = PClock::<_, MaxSet>::new();
+    /// let mut pclock_b = PClock::new();
+    ///
+    /// pclock_a.next(&"A");
+    /// let event = pclock_a.next(&"A");
+    ///
+    /// pclock_b.join(&pclock_a);
+    /// assert!(pclock_b.contains(&"A", event));
+    /// ```
+    pub fn join(&mut self, other: &Self) {
+        for (actor, eset) in other.clock.iter() {
+            // only touch entries that change, so that the remaining ones are
+            // still shared
+            if let Some(current_eset) = self.clock.get(actor) {
+                if eset.leq(current_eset) {
+                    continue;
+                }
+            }
+            self.upsert(
+                actor,
+                |current_eset| current_eset.join(eset),
+                || (eset.clone(), ()),
+            );
+        }
+    }
+
+    /// Intersects clock `other` passed as argument with `self`.
+    /// After intersection, only the common events are in `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut pclock_a = PClock::<_, MaxSet>::new();
+    /// let mut pclock_b = PClock::new();
+    ///
+    /// let event = pclock_a.next(&"A");
+    /// pclock_b.next(&"B");
+    ///
+    /// pclock_b.meet(&pclock_a);
+    /// assert!(pclock_b.is_empty());
+    /// ```
+    pub fn meet(&mut self, other: &Self) {
+        // compute the entries that change
+        let mut to_meet = Vec::new();
+        let mut to_remove = Vec::new();
+        for (actor, eset) in self.clock.iter() {
+            match other.get(actor) {
+                Some(other_eset) if !eset.leq(other_eset) => {
+                    to_meet.push((actor.clone(), other_eset))
+                }
+                Some(_) => {}
+                None => to_remove.push(actor.clone()),
+            }
+        }
+
+        // only touch these entries, so that the remaining ones are still
+        // shared
+        for (actor, other_eset) in to_meet {
+            if let Some(eset) = self.clock.get_mut(&actor) {
+                eset.meet(other_eset);
+            }
+        }
+        for actor in to_remove {
+            self.clock.remove(&actor);
+        }
+    }
+
+    /// Returns a `PClock` iterator (sorted by actor).
+    pub fn iter(&self) -> Iter<'_, A, E> {
+        Iter(self.clock.iter())
+    }
+
+    /// Returns, for each actor in `self`, the events that remain when `other`
+    /// is subtracted from `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let pclock = PClock::from(vec![("A", MaxSet::from(5))]);
+    /// let other = PClock::from(vec![("A", MaxSet::from(2))]);
+    ///
+    /// assert_eq!(pclock.subtracted(&other)[&"A"], vec![3, 4, 5]);
+    /// ```
+    pub fn subtracted(
+        &self,
+        other: &Self,
+    ) -> std::collections::HashMap<A, Vec<u64>> {
+        self.clock
+            .iter()
+            .map(|(actor, eset)| {
+                let subtracted = if let Some(other_eset) = other.get(actor) {
+                    eset.subtracted(other_eset)
+                } else {
+                    eset.iter().collect()
+                };
+                (actor.clone(), subtracted)
+            })
+            .collect()
+    }
+}
+
+pub struct IntoIter<A: Actor, E: EventSet>(ordmap::ConsumingIter<(A, E)>);
+
+impl<A: Actor, E: EventSet> Iterator for IntoIter<A, E> {
+    type Item = (A, E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<A: Actor, E: EventSet> IntoIterator for PClock<A, E> {
+    type Item = (A, E);
+    type IntoIter = IntoIter<A, E>;
+
+    /// Returns a `PClock` into-iterator (sorted by actor).
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter(self.clock.into_iter())
+    }
+}
+
+pub struct Iter<'a, A: Actor, E: EventSet>(ordmap::Iter<'a, A, E>);
+
+impl<'a, A: Actor, E: EventSet> Iterator for Iter<'a, A, E> {
+    type Item = (&'a A, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
@@ -24,31 +24,45 @@ use crate::EventSet;
 use serde::{Deserialize, Serialize};
 use std::cmp::{self, Ordering};
 use std::collections::btree_set::{self, BTreeSet};
-use std::collections::HashSet;
 use std::fmt;
-use std::iter::FromIterator;
 
 #[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "RawAboveExSet")]
 pub struct AboveExSet {
     // Highest contiguous event seen
     max: u64,
     // Set of extra events above the highest (sorted ASC)
-    exs: HashSet<u64>,
+    exs: BTreeSet<u64>,
+}
+
+// Unvalidated `AboveExSet`, used when deserializing.
+#[derive(Deserialize)]
+struct RawAboveExSet {
+    max: u64,
+    exs: BTreeSet<u64>,
+}
+
+impl From<RawAboveExSet> for AboveExSet {
+    fn from(raw: RawAboveExSet) -> Self {
+        AboveExSet::from(raw.max, raw.exs)
+    }
 }
 
 impl EventSet for AboveExSet {
     type EventIter = EventIter;
+    const KIND: &'static str = "AEClock";
 
     /// Returns a new `AboveExSet` instance.
     fn new() -> Self {
         AboveExSet {
             max: 0,
-            exs: HashSet::new(),
+            exs: BTreeSet::new(),
         }
     }
 
-    /// Generates the next event.
-    /// There should be no extras when calling this.
+    /// Generates the next event, i.e. the event right after the highest
+    /// contiguous event. If this event fills the gap before some of the
+    /// extras, these are compressed.
     ///
     /// # Examples
     /// ```
@@ -57,11 +71,16 @@ impl EventSet for AboveExSet {
     /// let mut above_exset = AboveExSet::new();
     /// assert_eq!(above_exset.next_event(), 1);
     /// assert_eq!(above_exset.next_event(), 2);
+    ///
+    /// let mut above_exset = AboveExSet::from_events(vec![1, 3]);
+    /// assert_eq!(above_exset.next_event(), 2);
+    /// assert_eq!(above_exset.frontier(), 3);
+    /// assert_eq!(above_exset.events(), (3, vec![]));
     /// ```
     fn next_event(&mut self) -> u64 {
-        debug_assert!(self.exs.is_empty());
-        self.max += 1;
-        self.max
+        let next = self.max + 1;
+        self.add_event(next);
+        next
     }
 
     /// Adds an event to the set.
@@ -115,7 +134,10 @@ impl EventSet for AboveExSet {
 
     /// Adds a range of events to the set.
     fn add_event_range(&mut self, start: u64, end: u64) -> bool {
-        if start <= self.max + 1 && end > self.max {
+        if start > end {
+            // empty range
+            false
+        } else if start <= self.max + 1 && end > self.max {
             // the end of the range is now the new max
             self.max = end;
 
@@ -130,14 +152,103 @@ impl EventSet for AboveExSet {
             true
         } else if start > self.max + 1 {
             // add all events as extra
+            let exs_before = self.exs.len();
             self.exs.extend(start..=end);
-            true
+            self.exs.len() > exs_before
         } else {
             // else all events are already an event
             false
         }
     }
 
+    /// Removes an event from the set.
+    /// Returns `true` if the event was part of the set.
+    ///
+    /// Removing an event below the highest contiguous event changes it to the
+    /// event before the removed one, and the events above the removed one
+    /// become extras.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_exset = AboveExSet::from_events(vec![1, 2, 3, 4, 6]);
+    /// assert!(above_exset.remove_event(6));
+    /// assert_eq!(above_exset.events(), (4, vec![]));
+    ///
+    /// assert!(above_exset.remove_event(4));
+    /// assert_eq!(above_exset.events(), (3, vec![]));
+    ///
+    /// assert!(above_exset.remove_event(2));
+    /// assert_eq!(above_exset.events(), (1, vec![3]));
+    /// assert!(!above_exset.remove_event(2));
+    /// ```
+    fn remove_event(&mut self, event: u64) -> bool {
+        if event == 0 {
+            false
+        } else if event <= self.max {
+            // the events above the removed one become extras
+            self.exs.extend(event + 1..=self.max);
+            self.max = event - 1;
+            true
+        } else {
+            self.exs.remove(&event)
+        }
+    }
+
+    /// Removes a range of events from the set.
+    /// Returns `true` if some event in the range was part of the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_exset = AboveExSet::from_events(vec![1, 2, 3, 4, 6, 8]);
+    /// assert!(above_exset.remove_event_range(3, 6));
+    /// assert_eq!(above_exset.events(), (2, vec![8]));
+    /// assert!(!above_exset.remove_event_range(3, 6));
+    /// ```
+    fn remove_event_range(&mut self, start: u64, end: u64) -> bool {
+        let start = cmp::max(start, 1);
+        if start > end {
+            return false;
+        }
+
+        let mut removed = false;
+        if start <= self.max {
+            // the events above the range become extras
+            if end < self.max {
+                self.exs.extend(end + 1..=self.max);
+            }
+            self.max = start - 1;
+            removed = true;
+        }
+
+        // remove the extras within the range
+        let len = self.exs.len();
+        self.exs.retain(|ex| *ex < start || *ex > end);
+        removed || self.exs.len() < len
+    }
+
+    /// Removes all events higher than `max_event` from the set: extras above
+    /// it are dropped, and the highest contiguous event is capped.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_exset = AboveExSet::from_events(vec![1, 2, 3, 5, 7]);
+    /// above_exset.truncate(5);
+    /// assert_eq!(above_exset.events(), (3, vec![5]));
+    ///
+    /// above_exset.truncate(2);
+    /// assert_eq!(above_exset.events(), (2, vec![]));
+    /// ```
+    fn truncate(&mut self, max_event: u64) {
+        self.max = cmp::min(self.max, max_event);
+        self.exs.retain(|ex| *ex <= max_event);
+    }
+
     /// Checks if an event is part of the set.
     ///
     /// # Examples
@@ -158,7 +269,7 @@ impl EventSet for AboveExSet {
 
     /// Returns all events seen as a tuple.
     /// The first component is the highest event seen, while the second is a
-    /// vector with the exceptions (in no specific order).
+    /// vector with the exceptions (sorted ASC).
     ///
     /// # Examples
     /// ```
@@ -180,11 +291,13 @@ impl EventSet for AboveExSet {
     ///
     /// above_exset.add_event(6);
     /// assert_eq!(above_exset.events(), (4, vec![6]));
+    ///
+    /// above_exset.add_event_range(10, 11);
+    /// above_exset.add_event(8);
+    /// assert_eq!(above_exset.events(), (4, vec![6, 8, 10, 11]));
     /// ```
     fn events(&self) -> (u64, Vec<u64>) {
-        let mut exs: Vec<_> = self.exs.clone().into_iter().collect();
-        exs.sort_unstable();
-        (self.max, exs)
+        (self.max, self.exs.iter().cloned().collect())
     }
 
     /// Returns the frontier (the highest contiguous event seen).
@@ -215,6 +328,20 @@ impl EventSet for AboveExSet {
         self.max
     }
 
+    /// Returns the highest event in the set: the highest extra, if any.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 4, 7, 5]);
+    /// assert_eq!(above_exset.max_event(), 7);
+    /// ```
+    fn max_event(&self) -> u64 {
+        // extras are above the highest contiguous event
+        self.exs.iter().next_back().map_or(self.max, |ex| *ex)
+    }
+
     /// Merges `other` `AboveExSet` into `self`.
     ///
     /// # Examples
@@ -243,8 +370,11 @@ impl EventSet for AboveExSet {
         // the new max value is the max of both max values
         self.max = cmp::max(self.max, other.max);
 
-        // add extras higher than `self.max` as extras
+        // remove local extras that are now below `self.max`
         let max = self.max;
+        self.exs.retain(|ex| *ex > max);
+
+        // add extras higher than `self.max` as extras
         other.exs.iter().filter(|ex| **ex > max).for_each(|ex| {
             self.exs.insert(*ex);
         });
@@ -273,8 +403,27 @@ impl EventSet for AboveExSet {
         self.try_compress();
     }
 
+    /// Return a list of events that remain when `other` is subtracted from
+    /// `self` (in no specific order).
+    ///
+    /// This works directly on the representation of both sets: it only goes
+    /// through the extras of `self` and the events of `self` above
+    /// `other`'s highest contiguous event, and so it doesn't depend on how
+    /// high the highest contiguous events are.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from(1_000_000, vec![1_000_002]);
+    /// let other = AboveExSet::from(999_998, vec![1_000_000]);
+    ///
+    /// let mut subtracted = above_exset.subtracted(&other);
+    /// subtracted.sort_unstable();
+    /// assert_eq!(subtracted, vec![999_999, 1_000_002]);
+    /// ```
     fn subtracted(&self, other: &Self) -> Vec<u64> {
-        //include only extras that are not events in `other`
+        // include only extras that are not events in `other`
         let iter = self.exs.iter().filter(|ex| !other.is_event(**ex)).cloned();
         if self.max > other.max {
             iter.chain(
@@ -287,8 +436,109 @@ impl EventSet for AboveExSet {
         }
     }
 
+    /// Returns the number of events that remain when `other` is subtracted
+    /// from `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// let other = AboveExSet::from_events(vec![1, 3, 4, 8]);
+    /// assert_eq!(above_exset.subtracted_count(&other), 2);
+    /// ```
+    fn subtracted_count(&self, other: &Self) -> u64 {
+        // count extras that are not events in `other`
+        let extras =
+            self.exs.iter().filter(|ex| !other.is_event(**ex)).count() as u64;
+
+        if self.max > other.max {
+            // count contiguous events above `other.max` that are not extras in
+            // `other`
+            let other_extras =
+                other.exs.iter().filter(|ex| **ex <= self.max).count() as u64;
+            extras + (self.max - other.max - other_extras)
+        } else {
+            extras
+        }
+    }
+
+    /// Returns the number of events in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// assert_eq!(above_exset.event_count(), 5);
+    /// ```
+    fn event_count(&self) -> u64 {
+        self.max + self.exs.len() as u64
+    }
+
+    /// Returns the number of extras the set stores.
+    fn stored_extras(&self) -> usize {
+        self.exs.len()
+    }
+
+    /// Returns the number of events in the set that are within `[start, end]`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// assert_eq!(above_exset.count_events_in_range(2, 6), 3);
+    /// ```
+    fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        let extras = self
+            .exs
+            .iter()
+            .filter(|ex| start <= **ex && **ex <= end)
+            .count() as u64;
+        super::count_up_to(start, end, self.max) + extras
+    }
+
+    /// Checks if all events within `[start, end]` are in the set: events above
+    /// the highest contiguous event must all be extras.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 3, 5, 6, 8]);
+    /// assert!(above_exset.contains_all_range(2, 3));
+    /// assert!(above_exset.contains_all_range(5, 6));
+    /// assert!(!above_exset.contains_all_range(5, 8));
+    /// ```
+    fn contains_all_range(&self, start: u64, end: u64) -> bool {
+        if start > end || end <= self.max {
+            return true;
+        }
+        let start = cmp::max(start, self.max + 1);
+        let len = end - start + 1;
+        len <= self.exs.len() as u64
+            && self
+                .exs
+                .iter()
+                .filter(|ex| start <= **ex && **ex <= end)
+                .count() as u64
+                == len
+    }
+
+    /// Compresses the set: extras up to the highest contiguous event are
+    /// dropped, and extras that follow it are moved into it.
+    fn compress(&mut self) -> bool {
+        // extras are only ever dropped
+        let len = self.exs.len();
+        let max = self.max;
+        self.exs.retain(|ex| *ex > max);
+        self.try_compress();
+        self.exs.len() != len
+    }
+
     /// Returns a `AboveExSet` event iterator with all events from lowest to
-    /// highest.
+    /// highest. The iterator can also be walked from highest to lowest.
     ///
     /// # Examples
     /// ```
@@ -298,18 +548,41 @@ impl EventSet for AboveExSet {
     /// above_exset.add_event(3);
     /// above_exset.add_event(5);
     ///
-    /// let mut iter = above_exset.event_iter();
+    /// let mut iter = above_exset.clone().event_iter();
     /// assert_eq!(iter.next(), Some(3));
     /// assert_eq!(iter.next(), Some(5));
     /// assert_eq!(iter.next(), None);
+    ///
+    /// above_exset.add_event(1);
+    /// let latest: Vec<_> = above_exset.event_iter().rev().take(2).collect();
+    /// assert_eq!(latest, vec![5, 3]);
     /// ```
     fn event_iter(self) -> Self::EventIter {
         EventIter {
             current: 0,
             max: self.max,
-            exs: BTreeSet::from_iter(self.exs).into_iter(),
+            exs: self.exs.into_iter(),
+        }
+    }
+
+    /// Returns an `AboveExSet` event iterator with all events from lowest to
+    /// highest, borrowing its (sorted) extras.
+    #[allow(refining_impl_trait)]
+    fn iter(&self) -> Iter<'_> {
+        Iter {
+            current: 0,
+            max: self.max,
+            exs: self.exs.iter(),
         }
     }
+
+    /// Returns an `AboveExSet` iterator with all events as ranges, from lowest
+    /// to highest: the contiguous events, followed by the (coalesced) extras.
+    fn range_iter(self) -> impl Iterator<Item = (u64, u64)> {
+        let first = Some((1, self.max)).filter(|_| self.max > 0);
+        let exs = self.exs.into_iter().map(|ex| (ex, ex));
+        super::coalesce(first.into_iter().chain(exs)).into_iter()
+    }
 }
 
 impl AboveExSet {
@@ -317,12 +590,13 @@ impl AboveExSet {
     fn try_compress(&mut self) {
         // only keep in extras those that can't be compressed
         while self.exs.remove(&(self.max + 1)) {
-            self.max = self.max + 1;
+            self.max += 1;
         }
     }
 
     /// Creates a new instance from the highest contiguous event, and a sequence
-    /// of extra events.
+    /// of extra events. Extra events not above the highest contiguous event
+    /// are dropped (the same happens when deserializing).
     ///
     /// # Examples
     /// ```
@@ -335,11 +609,19 @@ impl AboveExSet {
     /// assert!(above_exset.is_event(4));
     /// assert!(above_exset.is_event(5));
     /// assert!(!above_exset.is_event(6));
+    ///
+    /// let above_exset = AboveExSet::from(3, vec![2, 3, 5]);
+    /// assert_eq!(above_exset, AboveExSet::from(3, vec![5]));
+    /// assert_eq!(above_exset.event_count(), 4);
+    ///
+    /// let json = r#"{"max":3,"exs":[2,3,5]}"#;
+    /// let above_exset: AboveExSet = serde_json::from_str(json).unwrap();
+    /// assert_eq!(above_exset.event_count(), 4);
     /// ```
     pub fn from<I: IntoIterator<Item = u64>>(max: u64, iter: I) -> Self {
         AboveExSet {
             max,
-            exs: HashSet::from_iter(iter),
+            exs: iter.into_iter().filter(|ex| *ex > max).collect(),
         }
     }
 
@@ -350,6 +632,47 @@ impl AboveExSet {
         // only keep as events those that are not in the extras
         below.filter(move |event| !self.exs.contains(event))
     }
+
+    /// Returns the highest contiguous event. This accessor doesn't allocate,
+    /// and it's stable for FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 4]);
+    /// assert_eq!(above_exset.max(), 2);
+    /// ```
+    #[inline]
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the number of extra events. This accessor doesn't allocate,
+    /// and it's stable for FFI wrapping.
+    #[inline]
+    pub fn extras_len(&self) -> usize {
+        self.exs.len()
+    }
+
+    /// Copies the extra events (sorted ASC) into `buf`, returning the number
+    /// of extra events. If `buf` is too small, nothing is copied and the
+    /// required length is returned. This accessor doesn't allocate, and it's
+    /// stable for FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 4, 6]);
+    /// let mut buf = [0; 4];
+    /// let len = above_exset.copy_extras_into(&mut buf);
+    /// assert_eq!(&buf[..len], &[4, 6]);
+    /// ```
+    #[inline]
+    pub fn copy_extras_into(&self, buf: &mut [u64]) -> usize {
+        super::copy_sorted_into(self.exs.iter().cloned(), self.exs.len(), buf)
+    }
 }
 
 pub struct EventIter {
@@ -375,6 +698,79 @@ impl Iterator for EventIter {
             Some(self.current)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.max - self.current) as usize + self.exs.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for EventIter {}
+
+impl DoubleEndedIterator for EventIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // start with the extras, and then go through the contiguous events
+        self.exs.next_back().or_else(|| {
+            if self.current == self.max {
+                None
+            } else {
+                self.max -= 1;
+                Some(self.max + 1)
+            }
+        })
+    }
+}
+
+pub struct Iter<'a> {
+    // Last contiguous value returned by the iterator
+    current: u64,
+    // Last contiguous value that should be returned by the iterator
+    max: u64,
+    // Iterator of (borrowed) extras
+    exs: btree_set::Iter<'a, u64>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current == self.max {
+            // we've reached the last contiguous, just call next on the extras
+            // iterator
+            self.exs.next().cloned()
+        } else {
+            // compute next value
+            self.current += 1;
+            Some(self.current)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.max - self.current) as usize + self.exs.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // start with the extras, and then go through the contiguous events
+        self.exs.next_back().cloned().or_else(|| {
+            if self.current == self.max {
+                None
+            } else {
+                self.max -= 1;
+                Some(self.max + 1)
+            }
+        })
+    }
+}
+
+impl PartialOrd for AboveExSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        crate::traits::partial_cmp(self, other)
+    }
 }
 
 impl fmt::Debug for AboveExSet {
@@ -382,8 +778,25 @@ impl fmt::Debug for AboveExSet {
         if self.exs.is_empty() {
             write!(f, "{}", self.max)
         } else {
-            let exs: std::collections::BTreeSet<_> = self.exs.iter().collect();
-            write!(f, "({} + {:?})", self.max, exs)
+            let exs: Vec<_> = self.exs.iter().collect();
+            write!(f, "({} + ", self.max)?;
+            super::fmt_items(f, &exs)?;
+            write!(f, ")")
         }
     }
 }
+
+impl fmt::Display for AboveExSet {
+    /// Formats the events as ranges of contiguous events, e.g. `1..3,5,7..8`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from(3, vec![5, 7, 8]);
+    /// assert_eq!(above_exset.to_string(), "1..3,5,7..8");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        super::fmt_ranges(f, self.clone().range_iter())
+    }
+}
@@ -25,9 +25,10 @@ use serde::{Deserialize, Serialize};
 use std::cmp;
 use std::cmp::Ordering;
 use std::collections::btree_map::{self, BTreeMap};
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
-use std::iter::FromIterator;
+use std::hash::{Hash, Hasher};
+use std::ops::Bound;
 
 #[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct AboveRangeSet {
@@ -40,11 +41,12 @@ pub struct AboveRangeSet {
 #[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Ranges {
     // Mapping from start of the range to its end (sorted ASC)
-    ranges: HashMap<u64, u64>,
+    ranges: BTreeMap<u64, u64>,
 }
 
 impl EventSet for AboveRangeSet {
     type EventIter = EventIter;
+    const KIND: &'static str = "ARClock";
 
     /// Returns a new `AboveRangeSet` instance.
     fn new() -> Self {
@@ -54,8 +56,9 @@ impl EventSet for AboveRangeSet {
         }
     }
 
-    /// Generates the next event.
-    /// There should be no extra ranges when calling this.
+    /// Generates the next event, i.e. the event right after the highest
+    /// contiguous event. If this event fills the gap before some of the
+    /// extra ranges, these are compressed.
     ///
     /// # Examples
     /// ```
@@ -64,11 +67,16 @@ impl EventSet for AboveRangeSet {
     /// let mut above_range_set = AboveRangeSet::new();
     /// assert_eq!(above_range_set.next_event(), 1);
     /// assert_eq!(above_range_set.next_event(), 2);
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_events(vec![1, 3]);
+    /// assert_eq!(above_range_set.next_event(), 2);
+    /// assert_eq!(above_range_set.frontier(), 3);
+    /// assert_eq!(above_range_set.events(), (3, vec![]));
     /// ```
     fn next_event(&mut self) -> u64 {
-        debug_assert!(self.ranges.is_empty());
-        self.max += 1;
-        self.max
+        let next = self.max + 1;
+        self.add_event(next);
+        next
     }
 
     /// Adds an event to the set.
@@ -108,9 +116,8 @@ impl EventSet for AboveRangeSet {
                 true
             }
             Ordering::Greater => {
-                // add as a range: assumes it's a new range
-                self.ranges.add(event, event);
-                true
+                // add as a range (merged with any range that contains it)
+                self.ranges.add(event, event)
             }
             Ordering::Less => {
                 // else it's already an event
@@ -121,7 +128,10 @@ impl EventSet for AboveRangeSet {
 
     /// Adds a range of events to the set.
     fn add_event_range(&mut self, start: u64, end: u64) -> bool {
-        if start <= self.max + 1 && end > self.max {
+        if start > end {
+            // empty range
+            false
+        } else if start <= self.max + 1 && end > self.max {
             // the end of the range is now the new max
             self.max = end;
 
@@ -131,15 +141,112 @@ impl EventSet for AboveRangeSet {
             // new event, so `true`
             true
         } else if start > self.max + 1 {
-            // add as a range: assumes it's a new range
-            self.ranges.add(start, end);
-            true
+            // add as a range (merged with the ranges it overlaps)
+            self.ranges.add(start, end)
         } else {
             // else all events are already an event
             false
         }
     }
 
+    /// Removes an event from the set.
+    /// Returns `true` if the event was part of the set.
+    ///
+    /// Removing an event below the highest contiguous event changes it to the
+    /// event before the removed one, and the events above the removed one
+    /// become a range.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_events(vec![1, 2, 3, 5]);
+    /// assert!(above_range_set.remove_event(2));
+    /// assert_eq!(above_range_set.events(), (1, vec![3, 5]));
+    ///
+    /// assert!(!above_range_set.remove_event(4));
+    /// assert!(above_range_set.remove_event(5));
+    /// assert_eq!(above_range_set.events(), (1, vec![3]));
+    /// ```
+    fn remove_event(&mut self, event: u64) -> bool {
+        if event == 0 {
+            false
+        } else if event <= self.max {
+            // the events above the removed one become a range
+            if event < self.max {
+                self.ranges.add(event + 1, self.max);
+            }
+            self.max = event - 1;
+            true
+        } else {
+            self.ranges.remove(event)
+        }
+    }
+
+    /// Removes a range of events from the set.
+    /// Returns `true` if some event in the range was part of the set.
+    ///
+    /// The ranges are manipulated directly, without enumerating the events
+    /// removed.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 100);
+    /// above_range_set.add_event_range(200, 300);
+    ///
+    /// // the range straddles the highest contiguous event
+    /// assert!(above_range_set.remove_event_range(50, 250));
+    /// assert_eq!(above_range_set.frontier(), 49);
+    /// assert_eq!(above_range_set.count_events_in_range(50, 300), 50);
+    /// assert!(!above_range_set.remove_event_range(50, 250));
+    /// ```
+    fn remove_event_range(&mut self, start: u64, end: u64) -> bool {
+        let start = cmp::max(start, 1);
+        if start > end {
+            return false;
+        }
+
+        // remove the events above the highest contiguous event
+        let above = cmp::max(start, self.max + 1);
+        let mut removed = above <= end && self.ranges.remove_range(above, end);
+
+        // remove the events up to the highest contiguous event
+        if start <= self.max {
+            // the events above the range become a range
+            if end < self.max {
+                self.ranges.add(end + 1, self.max);
+            }
+            self.max = start - 1;
+            removed = true;
+        }
+        removed
+    }
+
+    /// Removes all events higher than `max_event` from the set: ranges above
+    /// it are dropped (or cut), and the highest contiguous event is capped.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 10);
+    /// above_range_set.add_event_range(20, 30);
+    /// above_range_set.add_event_range(40, 50);
+    ///
+    /// above_range_set.truncate(25);
+    /// assert_eq!(above_range_set.max_event(), 25);
+    /// assert!(above_range_set.contains_all_range(20, 25));
+    ///
+    /// above_range_set.truncate(5);
+    /// assert_eq!(above_range_set.events(), (5, vec![]));
+    /// ```
+    fn truncate(&mut self, max_event: u64) {
+        self.max = cmp::min(self.max, max_event);
+        self.ranges.truncate(max_event);
+    }
+
     /// Checks if an event is part of the set.
     ///
     /// # Examples
@@ -160,7 +267,7 @@ impl EventSet for AboveRangeSet {
 
     /// Returns all events seen as a tuple.
     /// The first component is the highest event seen, while the second is a
-    /// vector with the exceptions (in no specific order).
+    /// vector with the exceptions (sorted ASC).
     ///
     /// # Examples
     /// ```
@@ -182,6 +289,10 @@ impl EventSet for AboveRangeSet {
     ///
     /// above_range_set.add_event(6);
     /// assert_eq!(above_range_set.events(), (4, vec![6]));
+    ///
+    /// above_range_set.add_event_range(10, 11);
+    /// above_range_set.add_event(8);
+    /// assert_eq!(above_range_set.events(), (4, vec![6, 8, 10, 11]));
     /// ```
     fn events(&self) -> (u64, Vec<u64>) {
         (self.max, self.ranges.clone().event_iter().collect())
@@ -215,6 +326,21 @@ impl EventSet for AboveRangeSet {
         self.max
     }
 
+    /// Returns the highest event in the set: the end of the last range, if
+    /// any.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 10);
+    /// above_range_set.add_event_range(20, 30);
+    /// assert_eq!(above_range_set.max_event(), 30);
+    /// ```
+    fn max_event(&self) -> u64 {
+        cmp::max(self.max, self.ranges.max_event())
+    }
+
     /// Merges `other` `AboveRangeSet` into `self`.
     ///
     /// # Examples
@@ -254,8 +380,147 @@ impl EventSet for AboveRangeSet {
         todo!("AboveRangeSet::meet not yet implemented")
     }
 
-    fn subtracted(&self, _other: &Self) -> Vec<u64> {
-        todo!("AboveRangeSet::subtracted not yet implemented")
+    /// Return a list of events that remain when `other` is subtracted from
+    /// `self`. See `AboveRangeSet::subtracted_ranges` for a version that
+    /// doesn't enumerate events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_range_set = AboveRangeSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// let other = AboveRangeSet::from_events(vec![1, 3, 4, 8]);
+    /// assert_eq!(above_range_set.subtracted(&other), vec![2, 5]);
+    /// ```
+    fn subtracted(&self, other: &Self) -> Vec<u64> {
+        self.subtracted_ranges(other)
+            .into_iter()
+            .flat_map(|(start, end)| start..=end)
+            .collect()
+    }
+
+    /// Returns the number of events that remain when `other` is subtracted
+    /// from `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_range_set = AboveRangeSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// let other = AboveRangeSet::from_events(vec![1, 3, 4, 8]);
+    /// assert_eq!(above_range_set.subtracted_count(&other), 2);
+    /// ```
+    fn subtracted_count(&self, other: &Self) -> u64 {
+        let intervals = self.intervals();
+        let other_intervals = other.intervals();
+
+        // count the events in `self`
+        let total: u64 =
+            intervals.iter().map(|(start, end)| end - start + 1).sum();
+
+        // count the events in common by walking both (sorted) lists of
+        // intervals
+        let mut common = 0;
+        let mut i = 0;
+        let mut j = 0;
+        while i < intervals.len() && j < other_intervals.len() {
+            let (start, end) = intervals[i];
+            let (other_start, other_end) = other_intervals[j];
+            let overlap_start = cmp::max(start, other_start);
+            let overlap_end = cmp::min(end, other_end);
+            if overlap_start <= overlap_end {
+                common += overlap_end - overlap_start + 1;
+            }
+            // advance the interval that ends first
+            if end < other_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        total - common
+    }
+
+    /// Returns the number of events in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 10);
+    /// above_range_set.add_event_range(20, 29);
+    /// assert_eq!(above_range_set.event_count(), 20);
+    /// ```
+    fn event_count(&self) -> u64 {
+        self.intervals()
+            .into_iter()
+            .map(|(start, end)| end - start + 1)
+            .sum()
+    }
+
+    /// Returns the number of ranges the set stores above its highest
+    /// contiguous event.
+    fn stored_extras(&self) -> usize {
+        self.ranges.ranges.len()
+    }
+
+    /// Checks if the set has no events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::new();
+    /// assert!(above_range_set.is_empty());
+    ///
+    /// above_range_set.add_event_range(3, 4);
+    /// assert!(!above_range_set.is_empty());
+    /// ```
+    fn is_empty(&self) -> bool {
+        self.max == 0 && self.ranges.is_empty()
+    }
+
+    /// Checks if all events within `[start, end]` are in the set, without
+    /// enumerating them.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 10);
+    /// above_range_set.add_event_range(20, 30);
+    /// above_range_set.add_event_range(31, 40);
+    /// assert!(above_range_set.contains_all_range(5, 10));
+    /// assert!(above_range_set.contains_all_range(25, 35));
+    /// assert!(!above_range_set.contains_all_range(5, 20));
+    /// ```
+    fn contains_all_range(&self, start: u64, end: u64) -> bool {
+        if start > end || end <= self.max {
+            return true;
+        }
+        self.ranges
+            .contains_range(cmp::max(start, self.max + 1), end)
+    }
+
+    /// Compresses the set: ranges (or parts of them) up to the highest
+    /// contiguous event are dropped, overlapping and adjacent ranges are
+    /// merged, and ranges that follow the highest contiguous event are moved
+    /// into it.
+    fn compress(&mut self) -> bool {
+        let before = self.ranges.clone();
+        self.ranges.join(&Ranges::new(), self.max);
+        self.try_compress();
+        self.ranges != before
+    }
+
+    /// Returns a hash of the set that only depends on its events, computed
+    /// over its ranges (see `EventSet::range_iter`) without enumerating its
+    /// events.
+    fn canonical_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.intervals().hash(&mut hasher);
+        hasher.finish()
     }
 
     /// Returns a `AboveRangeSet` event iterator with all events from lowest to
@@ -281,17 +546,81 @@ impl EventSet for AboveRangeSet {
             ranges: self.ranges.event_iter(),
         }
     }
+
+    /// Returns an `AboveRangeSet` event iterator with all events from lowest
+    /// to highest, borrowing its ranges.
+    #[allow(refining_impl_trait)]
+    fn iter(&self) -> Iter<'_> {
+        Iter {
+            current: 0,
+            max: self.max,
+            range: None,
+            remaining: self.ranges.event_count(),
+            ranges: self.ranges.ranges.iter(),
+        }
+    }
+
+    /// Returns an `AboveRangeSet` iterator with all events as ranges, from
+    /// lowest to highest. Events are never enumerated.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 100);
+    /// above_range_set.add_event_range(200, 205);
+    ///
+    /// let ranges: Vec<_> = above_range_set.range_iter().collect();
+    /// assert_eq!(ranges, vec![(1, 100), (200, 205)]);
+    /// ```
+    fn range_iter(self) -> impl Iterator<Item = (u64, u64)> {
+        self.intervals().into_iter()
+    }
 }
 
 impl AboveRangeSet {
     /// Tries to set a new max contiguous event.
     fn try_compress(&mut self) {
-        // drop the first range while its start is right after the max
-        while let Some(new_max) = self.ranges.try_drop(self.max + 1) {
-            self.max = new_max;
+        // drop the first range while it starts at or before the event right
+        // after the max (it may start before it if the max has jumped over it)
+        while let Some(end) = self.ranges.try_drop(self.max + 1) {
+            self.max = cmp::max(self.max, end);
         }
     }
 
+    /// Returns all events as a sorted list of disjoint intervals (both ends
+    /// included).
+    fn intervals(&self) -> Vec<(u64, u64)> {
+        // the ranges are sorted, so `(1, max)` goes first
+        let first = Some((1, self.max)).filter(|_| self.max > 0);
+        let ranges = self.ranges.ranges.iter().map(|(&s, &e)| (s, e));
+        super::coalesce(first.into_iter().chain(ranges))
+    }
+
+    /// Returns the events that remain when `other` is subtracted from `self`,
+    /// as a sorted list of disjoint (and non-adjacent) ranges, with both ends
+    /// included. Events are never enumerated: the cost is linear in the number
+    /// of ranges.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 1_000_000);
+    /// above_range_set.add_event_range(2_000_000, 3_000_000);
+    ///
+    /// let mut other = AboveRangeSet::from_event_range(1, 10);
+    /// other.add_event_range(500_000, 2_500_000);
+    ///
+    /// assert_eq!(
+    ///     above_range_set.subtracted_ranges(&other),
+    ///     vec![(11, 499_999), (2_500_001, 3_000_000)]
+    /// );
+    /// ```
+    pub fn subtracted_ranges(&self, other: &Self) -> Vec<(u64, u64)> {
+        super::subtract_intervals(&self.intervals(), &other.intervals())
+    }
+
     /// Creates a new instance from the highest contiguous event, and a sequence
     /// of extra events.
     ///
@@ -311,6 +640,49 @@ impl AboveRangeSet {
         let ranges = Ranges::from::<I>(iter);
         AboveRangeSet { max, ranges }
     }
+
+    /// Returns the highest contiguous event. This accessor doesn't allocate,
+    /// and it's stable for FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_range_set = AboveRangeSet::from_events(vec![1, 2, 4]);
+    /// assert_eq!(above_range_set.max(), 2);
+    /// ```
+    #[inline]
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the number of ranges above the highest contiguous event. This
+    /// accessor doesn't allocate, and it's stable for FFI wrapping.
+    #[inline]
+    pub fn ranges_len(&self) -> usize {
+        self.ranges.ranges.len()
+    }
+
+    /// Copies the ranges above the highest contiguous event (sorted ASC, both
+    /// ends included) into `buf`, returning the number of ranges. If `buf` is
+    /// too small, nothing is copied and the required length is returned. This
+    /// accessor doesn't allocate, and it's stable for FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event(1);
+    /// above_range_set.add_event_range(4, 6);
+    /// let mut buf = [(0, 0); 2];
+    /// let len = above_range_set.copy_ranges_into(&mut buf);
+    /// assert_eq!(&buf[..len], &[(4, 6)]);
+    /// ```
+    #[inline]
+    pub fn copy_ranges_into(&self, buf: &mut [(u64, u64)]) -> usize {
+        let ranges = self.ranges.ranges.iter().map(|(&s, &e)| (s, e));
+        super::copy_sorted_into(ranges, self.ranges_len(), buf)
+    }
 }
 
 pub struct EventIter {
@@ -336,6 +708,81 @@ impl Iterator for EventIter {
             Some(self.current)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.max - self.current) as usize + self.ranges.len();
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for EventIter {}
+
+impl DoubleEndedIterator for EventIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // start with the extra ranges, and then go through the contiguous
+        // events
+        self.ranges.next_back().or_else(|| {
+            if self.current == self.max {
+                None
+            } else {
+                self.max -= 1;
+                Some(self.max + 1)
+            }
+        })
+    }
+}
+
+pub struct Iter<'a> {
+    // Last contiguous value returned by the iterator
+    current: u64,
+    // Last contiguous value that should be returned by the iterator
+    max: u64,
+    // Next value and last value of the extra range being iterated
+    range: Option<(u64, u64)>,
+    // Number of events in extra ranges not yet returned by the iterator
+    remaining: u64,
+    // Iterator of extra ranges
+    ranges: btree_map::Iter<'a, u64, u64>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current < self.max {
+            // compute next value
+            self.current += 1;
+            return Some(self.current);
+        }
+
+        // we've reached the last contiguous, so iterate the extra ranges
+        loop {
+            match self.range {
+                Some((next, end)) if next <= end => {
+                    self.range = Some((next + 1, end));
+                    self.remaining -= 1;
+                    return Some(next);
+                }
+                _ => {
+                    let (&start, &end) = self.ranges.next()?;
+                    self.range = Some((start, end));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.max - self.current + self.remaining) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl PartialOrd for AboveRangeSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        crate::traits::partial_cmp(self, other)
+    }
 }
 
 impl fmt::Debug for AboveRangeSet {
@@ -343,16 +790,36 @@ impl fmt::Debug for AboveRangeSet {
         if self.ranges.is_empty() {
             write!(f, "{}", self.max)
         } else {
-            write!(f, "({} + {:?})", self.max, self.ranges)
+            write!(f, "({} + ", self.max)?;
+            // forward the formatter so that its precision is respected
+            fmt::Debug::fmt(&self.ranges, f)?;
+            write!(f, ")")
         }
     }
 }
 
+impl fmt::Display for AboveRangeSet {
+    /// Formats the events as ranges of contiguous events, e.g. `1..3,5,7..8`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_range_set = AboveRangeSet::from_event_range(1, 3);
+    /// above_range_set.add_event(5);
+    /// above_range_set.add_event_range(7, 8);
+    /// assert_eq!(above_range_set.to_string(), "1..3,5,7..8");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        super::fmt_ranges(f, self.intervals())
+    }
+}
+
 impl Ranges {
     /// Creates a new `Ranges` instance.
     fn new() -> Self {
         Ranges {
-            ranges: HashMap::new(),
+            ranges: BTreeMap::new(),
         }
     }
 
@@ -361,99 +828,194 @@ impl Ranges {
         self.ranges.is_empty()
     }
 
-    /// Adds a new range, assuming it is new, i.e.:
-    /// - none of the events within the range have already been added.
-    fn add(&mut self, start: u64, end: u64) {
+    /// Adds a new range, merging it with the ranges it overlaps or is adjacent
+    /// to, so that ranges are always disjoint and separated by some gap.
+    /// Returns `true` if some event in the range wasn't in the ranges yet.
+    fn add(&mut self, mut start: u64, mut end: u64) -> bool {
+        if self.contains_range(start, end) {
+            return false;
+        }
+
+        // merge with the range that starts before, if it overlaps or ends right
+        // before the new range
+        if let Some((&prev_start, &prev_end)) =
+            self.ranges.range(..start).next_back()
+        {
+            if prev_end + 1 >= start {
+                start = prev_start;
+                end = cmp::max(end, prev_end);
+            }
+        }
+
+        // merge with the ranges that start within the new range, or right
+        // after it
+        let overlapping: Vec<_> = self
+            .ranges
+            .range(start..=end.saturating_add(1))
+            .map(|(&start, &end)| (start, end))
+            .collect();
+        for (overlapping_start, overlapping_end) in overlapping {
+            self.ranges.remove(&overlapping_start);
+            end = cmp::max(end, overlapping_end);
+        }
+
         self.ranges.insert(start, end);
+        true
     }
 
-    /// Adds a new range, assuming it is new, i.e.:
-    /// - none of the events within the range have already been added.
-    /// TODO it didn't look worth compressing so we moved from BTreeMap to
-    /// HashMap
-    // fn add_and_compress(&mut self, start: u64, mut end: u64) {
-    //     // split map where the new range should be inserted
-    //     let mut after_new_range = self.ranges.split_off(&start);
-
-    //     let mut inserted = false;
-
-    //     // check if the previous range can be extended with the new range
-    //     if let Some(mut before) = self.ranges.last_entry() {
-    //         let before_end = before.get_mut();
-    //         if *before_end + 1 == start {
-    //             // extend the previous range
-    //             *before_end = end;
-
-    //             // check if we can also extend this range with the first
-    // range             // in the splitted off ranges
-    //             if let Some(after) = after_new_range.first_entry() {
-    //                 if *before_end + 1 == *after.key() {
-    //                     // remove entry and extend range again
-    //                     *before_end = after.remove();
-    //                 }
-    //             }
-    //             // we're done, we only need to merge the splitted off ranges
-    //             inserted = true;
-    //         }
-    //     }
-
-    //     // if here haven't extended the previous range, then we need to
-    // create a     // new one
-    //     if !inserted {
-    //         // check if we should create a new one with the provided `end`,
-    // or         // with the end of the next range (in case they can be
-    // merged)         if let Some(after) = after_new_range.first_entry() {
-    //             if end + 1 == *after.key() {
-    //                 // remove entry and extend new range to be added
-    //                 end = after.remove();
-    //             }
-    //         }
-
-    //         // insert new range
-    //         self.ranges.insert(start, end);
-    //     }
-
-    //     // extend map with the ranges that have been splitted off
-    //     self.ranges.append(&mut after_new_range);
-    // }
-
-    /// Checks if the event is part of any of the ranges. This implementation
-    /// makes no effort in being efficient.
+    /// Returns the highest event in the ranges (or 0 if there are no ranges).
+    fn max_event(&self) -> u64 {
+        // ranges are disjoint, so the last range is the one ending last
+        self.ranges.values().next_back().cloned().unwrap_or(0)
+    }
+
+    /// Checks if all events within `[start, end]` are covered by the ranges.
+    fn contains_range(&self, start: u64, end: u64) -> bool {
+        // since ranges are disjoint, only the last range that starts at or
+        // before `start`, and the ones that start within the range, can cover
+        // it
+        let first = self.ranges.range(..=start).next_back();
+        let rest = self
+            .ranges
+            .range((Bound::Excluded(start), Bound::Included(end)));
+
+        // next event that needs to be covered
+        let mut next = start;
+        for (&range_start, &range_end) in first.into_iter().chain(rest) {
+            if range_end < next {
+                continue;
+            }
+            if range_start > next {
+                return false;
+            }
+            if range_end >= end {
+                return true;
+            }
+            next = range_end + 1;
+        }
+        false
+    }
+
+    /// Removes an event from the ranges, splitting the range that contains it.
+    /// Returns `true` if the event was part of some range.
+    fn remove(&mut self, event: u64) -> bool {
+        // find the last range that starts at or before the event
+        let found = self
+            .ranges
+            .range(..=event)
+            .next_back()
+            .map(|(&start, &end)| (start, end))
+            .filter(|&(_, end)| event <= end);
+        match found {
+            Some((start, end)) => {
+                self.ranges.remove(&start);
+                if start < event {
+                    self.ranges.insert(start, event - 1);
+                }
+                if event < end {
+                    self.ranges.insert(event + 1, end);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the events within `[start, end]` from the ranges, clipping the
+    /// ranges that overlap with it. Returns `true` if some event was removed.
+    fn remove_range(&mut self, start: u64, end: u64) -> bool {
+        // find all ranges that overlap with `[start, end]`
+        let overlapping: Vec<_> = self
+            .ranges
+            .range(..=end)
+            .filter(|(_, &range_end)| range_end >= start)
+            .map(|(&range_start, &range_end)| (range_start, range_end))
+            .collect();
+
+        for &(range_start, range_end) in overlapping.iter() {
+            self.ranges.remove(&range_start);
+            if range_start < start {
+                self.ranges.insert(range_start, start - 1);
+            }
+            if range_end > end {
+                self.ranges.insert(end + 1, range_end);
+            }
+        }
+        !overlapping.is_empty()
+    }
+
+    /// Returns the number of events in the ranges.
+    fn event_count(&self) -> u64 {
+        self.ranges.iter().map(|(start, end)| end - start + 1).sum()
+    }
+
+    /// Removes all events higher than `max` from the ranges.
+    fn truncate(&mut self, max: u64) {
+        if max < u64::MAX {
+            self.ranges.split_off(&(max + 1));
+        }
+        // cut the last range, if it ends after `max`
+        if let Some(end) = self.ranges.values_mut().next_back() {
+            *end = cmp::min(*end, max);
+        }
+    }
+
+    /// Checks if the event is part of any of the ranges. Since ranges are
+    /// disjoint, only the last range that starts at or before the event can
+    /// contain it.
     fn contains(&self, event: &u64) -> bool {
         self.ranges
-            .iter()
-            .any(|(start, end)| start <= event && event <= end)
+            .range(..=event)
+            .next_back()
+            .is_some_and(|(_, end)| event <= end)
     }
 
-    /// Joins two ranges. This implementation makes no effort in being
-    /// efficient.
+    /// Joins two ranges, keeping only the events higher than `max`.
+    /// Since both maps are sorted, this is a single merge pass over both
+    /// (without expanding the ranges into events), and the resulting ranges
+    /// are disjoint and non-adjacent.
     fn join(&mut self, other: &Self, max: u64) {
-        let mut result = Ranges::new();
+        let mut result: Vec<(u64, u64)> = Vec::new();
+        let mut left = self.ranges.iter().peekable();
+        let mut right = other.ranges.iter().peekable();
 
-        // add all events from self that are higher than the new max
-        for event in self.clone().event_iter() {
-            if event > max {
-                result.add(event, event);
+        loop {
+            // pick the range that starts first
+            let next = match (left.peek(), right.peek()) {
+                (Some(l), Some(r)) if l.0 <= r.0 => left.next(),
+                (Some(_), Some(_)) => right.next(),
+                (Some(_), None) => left.next(),
+                (None, Some(_)) => right.next(),
+                (None, None) => break,
+            };
+            let (&start, &end) = next.expect("there should be a next range");
+
+            // skip the events that are not higher than `max`
+            if end <= max {
+                continue;
             }
-        }
+            let start = cmp::max(start, max + 1);
 
-        // add all events from `other` that are higher than the new max
-        // AND haven't been added yet
-        for event in other.clone().event_iter() {
-            if event > max && !result.contains(&event) {
-                result.add(event, event);
+            // merge with the last range if they overlap or are adjacent
+            match result.last_mut() {
+                Some((_, last_end)) if start <= *last_end + 1 => {
+                    *last_end = cmp::max(*last_end, end);
+                }
+                _ => result.push((start, end)),
             }
         }
 
-        self.ranges = result.ranges;
+        self.ranges = result.into_iter().collect();
     }
 
     /// Creates a iterator for all events represented by the ranges. This
     /// implementation makes no effort in being efficient.
     fn event_iter(self) -> RangesIter {
         RangesIter {
-            current: None,
-            ranges: BTreeMap::from_iter(self.ranges).into_iter(),
+            front: None,
+            back: None,
+            remaining: self.event_count(),
+            ranges: self.ranges.into_iter(),
         }
     }
 
@@ -467,15 +1029,27 @@ impl Ranges {
         result
     }
 
-    /// Try to drop the range. If it succeeds then it can be used to update the
-    /// maximum value.
+    /// Try to drop the first range, if it starts at or before `next`. If it
+    /// succeeds then its end can be used to update the maximum value.
     fn try_drop(&mut self, next: u64) -> Option<u64> {
-        self.ranges.remove(&next)
+        let (&start, _) = self.ranges.iter().next()?;
+        if start <= next {
+            self.ranges.remove(&start)
+        } else {
+            None
+        }
     }
 }
 
 pub struct RangesIter {
-    current: Option<(u64, u64)>,
+    // Events (both ends included) remaining in the range being iterated from
+    // the front
+    front: Option<(u64, u64)>,
+    // Events (both ends included) remaining in the range being iterated from
+    // the back
+    back: Option<(u64, u64)>,
+    // Number of events not yet returned by the iterator
+    remaining: u64,
     ranges: btree_map::IntoIter<u64, u64>,
 }
 
@@ -483,28 +1057,171 @@ impl Iterator for RangesIter {
     type Item = u64;
 
     fn next(&mut self) -> Option<Self::Item> {
-        // if currently iterating a range, then keep going
-        if let Some((val, end)) = self.current {
-            if val <= end {
-                self.current = Some((val + 1, end));
-                return Some(val);
+        loop {
+            // if currently iterating a range, then keep going
+            if let Some((start, end)) = self.front {
+                self.front = Some((start + 1, end)).filter(|_| start < end);
+                self.remaining -= 1;
+                return Some(start);
+            }
+
+            // otherwise, try again in the next range; if there's no next
+            // range, then the remaining events are in the back range
+            match self.ranges.next() {
+                Some(range) => self.front = Some(range),
+                None => {
+                    let (start, end) = self.back?;
+                    self.back = Some((start + 1, end)).filter(|_| start < end);
+                    self.remaining -= 1;
+                    return Some(start);
+                }
             }
         }
+    }
 
-        // if we haven't returned a new value from the current range, try again
-        // in the next range
-        self.current = self.ranges.next();
-        if self.current.is_none() {
-            // if there's no next range, we're done
-            None
-        } else {
-            self.next()
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for RangesIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            // if currently iterating a range, then keep going
+            if let Some((start, end)) = self.back {
+                self.back = Some((start, end - 1)).filter(|_| start < end);
+                self.remaining -= 1;
+                return Some(end);
+            }
+
+            // otherwise, try again in the previous range; if there's no
+            // previous range, then the remaining events are in the front range
+            match self.ranges.next_back() {
+                Some(range) => self.back = Some(range),
+                None => {
+                    let (start, end) = self.front?;
+                    self.front = Some((start, end - 1)).filter(|_| start < end);
+                    self.remaining -= 1;
+                    return Some(end);
+                }
+            }
         }
     }
 }
 
+impl ExactSizeIterator for RangesIter {}
+
 impl fmt::Debug for Ranges {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self.ranges)
+        let ranges: Vec<_> = self
+            .ranges
+            .iter()
+            .map(|(start, end)| format!("{}: {}", start, end))
+            .collect();
+        super::fmt_items(f, &ranges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_many_ranges() {
+        // ranges [10, 14], [20, 24], ... with gaps in between
+        let mut above_range_set = AboveRangeSet::new();
+        for i in 1..=5_000 {
+            above_range_set.add_event_range(i * 10, i * 10 + 4);
+        }
+
+        for event in 1..=50_010 {
+            let expected = (10..=50_004).contains(&event) && event % 10 < 5;
+            assert_eq!(above_range_set.is_event(event), expected);
+        }
+    }
+
+    #[test]
+    fn add_overlapping_ranges() {
+        let mut above_range_set = AboveRangeSet::new();
+        above_range_set.add_event_range(10, 20);
+        above_range_set.add_event_range(15, 16);
+        above_range_set.add_event_range(30, 40);
+        above_range_set.add_event_range(5, 12);
+        above_range_set.add_event_range(18, 35);
+
+        // all ranges were merged into one
+        assert_eq!(above_range_set.ranges.ranges.len(), 1);
+        assert!(above_range_set.contains_all_range(5, 40));
+        assert!(!above_range_set.is_event(4));
+        assert!(!above_range_set.is_event(41));
+
+        // adding events that are already in some range is a no-op
+        assert!(!above_range_set.add_event_range(10, 20));
+        assert!(!above_range_set.add_event(5));
+        assert!(above_range_set.add_event_range(40, 41));
+        assert_eq!(above_range_set.event_iter().count(), 37);
+    }
+
+    #[test]
+    fn add_range_over_ranges() {
+        let mut above_range_set = AboveRangeSet::new();
+        above_range_set.add_event_range(5, 10);
+        above_range_set.add_event_range(12, 14);
+        above_range_set.add_event_range(20, 30);
+
+        // the max jumps over the first ranges, which are then dropped
+        above_range_set.add_event_range(1, 11);
+        assert_eq!(
+            above_range_set.events(),
+            (14, vec![20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30])
+        );
+        assert_eq!(above_range_set.frontier(), 14);
+        assert_eq!(above_range_set.event_iter().count(), 25);
+    }
+
+    #[test]
+    fn join_wide_ranges() {
+        let mut above_range_set = AboveRangeSet::from_event_range(1, 10);
+        above_range_set.add_event_range(1_000_000, 2_000_000);
+        above_range_set.add_event_range(5_000_000, 6_000_000);
+
+        let mut other = AboveRangeSet::from_event_range(1, 20);
+        other.add_event_range(1_500_000, 3_000_000);
+        other.add_event_range(3_000_001, 4_000_000);
+        above_range_set.join(&other);
+
+        // overlapping and adjacent ranges are merged, without enumerating
+        // their events
+        let ranges: Vec<_> = above_range_set
+            .ranges
+            .ranges
+            .iter()
+            .map(|(&start, &end)| (start, end))
+            .collect();
+        assert_eq!(above_range_set.frontier(), 20);
+        assert_eq!(
+            ranges,
+            vec![(1_000_000, 4_000_000), (5_000_000, 6_000_000)]
+        );
+    }
+
+    #[test]
+    fn add_adjacent_events() {
+        // events 10, 12, 14, ... leave a gap between each pair of ranges
+        let events = 10..1_000_010;
+        let mut above_range_set = AboveRangeSet::new();
+        for event in events.clone().step_by(2) {
+            above_range_set.add_event(event);
+        }
+        assert_eq!(above_range_set.ranges.ranges.len(), 500_000);
+
+        // filling the gaps merges all the ranges into one
+        for event in events.clone().skip(1).step_by(2) {
+            above_range_set.add_event(event);
+        }
+        assert_eq!(above_range_set.ranges.ranges.len(), 1);
+        assert!(above_range_set.contains_all_range(10, 1_000_009));
+        assert_eq!(above_range_set.event_count(), 1_000_000);
     }
 }
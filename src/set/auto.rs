@@ -0,0 +1,120 @@
+//! This module contains a heuristic to choose an event set representation.
+
+/// An event set representation (see `choose_representation`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Representation {
+    /// `MaxSet`
+    Max,
+    /// `AboveExSet`
+    AboveEx,
+    /// `AboveRangeSet`
+    AboveRange,
+    /// `BelowExSet`
+    BelowEx,
+}
+
+/// Chooses the cheapest representation (i.e. the one storing the fewest
+/// integers) for a set of events, given its contiguity and the density of its
+/// gaps:
+/// - contiguous events (from 1) are represented exactly by a `MaxSet`
+/// - an `AboveExSet` stores each event above the highest contiguous event
+/// - a `BelowExSet` stores each event missing below the highest event
+/// - an `AboveRangeSet` stores each run of events above the highest
+///   contiguous event (as two integers)
+///
+/// `MaxSet` is only chosen if it represents the events exactly. Ties are
+/// broken in the order above. Events may be unsorted and repeated, and event
+/// 0 is ignored. The caller then constructs the chosen event set.
+///
+/// # Examples
+/// ```
+/// use threshold::*;
+///
+/// let events = vec![1, 2, 3, 10, 11, 12, 13, 14, 15];
+/// let eset = match choose_representation(&events) {
+///     Representation::AboveRange => AboveRangeSet::from_events(events),
+///     _ => unreachable!(),
+/// };
+/// assert_eq!(eset.frontier(), 3);
+///
+/// assert_eq!(choose_representation(&[1, 2, 3]), Representation::Max);
+/// assert_eq!(choose_representation(&[1, 2, 5]), Representation::AboveEx);
+/// assert_eq!(choose_representation(&[1, 3, 4, 5]), Representation::BelowEx);
+/// ```
+pub fn choose_representation(events: &[u64]) -> Representation {
+    let mut events: Vec<_> =
+        events.iter().cloned().filter(|event| *event != 0).collect();
+    events.sort_unstable();
+    events.dedup();
+
+    let count = events.len() as u64;
+    let max = events.last().cloned().unwrap_or(0);
+    if count == max {
+        // all events from 1 to `max`
+        return Representation::Max;
+    }
+
+    // highest contiguous event
+    let frontier = events
+        .iter()
+        .zip(1..)
+        .take_while(|(event, expected)| **event == *expected)
+        .count();
+
+    // runs of events above the highest contiguous event
+    let above = &events[frontier..];
+    let runs = 1 + above.windows(2).filter(|w| w[0] + 1 != w[1]).count();
+
+    // number of integers stored by each representation (besides the highest
+    // contiguous or highest event)
+    let costs = [
+        (Representation::AboveEx, above.len() as u64),
+        (Representation::BelowEx, max - count),
+        (Representation::AboveRange, 2 * runs as u64),
+    ];
+    // `min_by_key` returns the first representation on ties
+    let (repr, _) = costs
+        .iter()
+        .min_by_key(|(_, cost)| *cost)
+        .expect("there should be at least one representation");
+    *repr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contiguous() {
+        assert_eq!(choose_representation(&[]), Representation::Max);
+        assert_eq!(choose_representation(&[0]), Representation::Max);
+        assert_eq!(choose_representation(&[1]), Representation::Max);
+
+        // order, repetitions and event 0 don't matter
+        let events: Vec<_> = (0..=100).rev().chain(1..=50).collect();
+        assert_eq!(choose_representation(&events), Representation::Max);
+    }
+
+    #[test]
+    fn dense_gapped() {
+        // a few long runs above the highest contiguous event
+        let events: Vec<_> =
+            (1..=10).chain(20..=100).chain(200..=300).collect();
+        assert_eq!(choose_representation(&events), Representation::AboveRange);
+
+        // without a highest contiguous event
+        let events: Vec<_> = (5..=1000).chain(2000..=3000).collect();
+        assert_eq!(choose_representation(&events), Representation::AboveRange);
+    }
+
+    #[test]
+    fn sparse() {
+        // a few scattered events above the highest contiguous event
+        let events = vec![1, 2, 3, 10, 20, 30];
+        assert_eq!(choose_representation(&events), Representation::AboveEx);
+
+        // a few events missing below the highest event
+        let events: Vec<_> = (1..=1000).filter(|e| e % 100 != 0).collect();
+        assert_eq!(choose_representation(&events), Representation::BelowEx);
+    }
+}
@@ -20,29 +20,76 @@
 //! assert!(below_exset.is_event(3));
 //! ```
 
+use crate::hash::HashSet;
 use crate::EventSet;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use std::cmp::{self, Ordering};
-use std::collections::HashSet;
 use std::fmt;
-use std::iter::FromIterator;
 
 #[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "RawBelowExSet")]
 pub struct BelowExSet {
     // Highest event seen
     max: u64,
     // Set of exceptions
+    #[serde(serialize_with = "serialize_sorted")]
     exs: HashSet<u64>,
 }
 
+// Serializes the exceptions sorted, so that equal sets are serialized
+// identically (regardless of the iteration order of the `HashSet`).
+fn serialize_sorted<S: Serializer>(
+    exs: &HashSet<u64>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut exs: Vec<_> = exs.iter().collect();
+    exs.sort_unstable();
+    exs.serialize(serializer)
+}
+
+// Unvalidated `BelowExSet`, used when deserializing.
+#[derive(Deserialize)]
+struct RawBelowExSet {
+    max: u64,
+    exs: HashSet<u64>,
+}
+
+impl From<RawBelowExSet> for BelowExSet {
+    fn from(raw: RawBelowExSet) -> Self {
+        BelowExSet::from(raw.max, raw.exs)
+    }
+}
+
 impl EventSet for BelowExSet {
     type EventIter = EventIter;
+    const KIND: &'static str = "BEClock";
 
     /// Returns a new `BelowExSet` instance.
     fn new() -> Self {
         BelowExSet {
             max: 0,
-            exs: HashSet::new(),
+            exs: HashSet::default(),
+        }
+    }
+
+    /// Creates a new instance from a range of events.
+    /// All events below the range are exceptions.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_event_range(3, 5);
+    /// assert_eq!(below_exset, BelowExSet::from(5, vec![1, 2]));
+    /// ```
+    fn from_event_range(start: u64, end: u64) -> Self {
+        if start > end {
+            // empty range
+            return BelowExSet::new();
+        }
+        BelowExSet {
+            max: end,
+            exs: (1..start).collect(),
         }
     }
 
@@ -110,6 +157,180 @@ impl EventSet for BelowExSet {
         }
     }
 
+    /// Adds a range of events to the set.
+    /// Returns `true` if a new event was added.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_exset = BelowExSet::new();
+    /// below_exset.add_event_range(3, 5);
+    /// assert_eq!(below_exset.events().0, 5);
+    /// assert!(!below_exset.is_event(2));
+    /// assert!(below_exset.is_event(3));
+    /// assert!(below_exset.is_event(5));
+    ///
+    /// below_exset.add_event_range(1, 2);
+    /// assert_eq!(below_exset.events(), (5, vec![]));
+    /// ```
+    fn add_event_range(&mut self, start: u64, end: u64) -> bool {
+        if start > end {
+            // empty range
+            return false;
+        }
+        // exceptions within the range are now events
+        let exs_before = self.exs.len();
+        self.exs.retain(|ex| *ex < start || *ex > end);
+        let mut res = self.exs.len() < exs_before;
+
+        if end > self.max {
+            // events between the current max and the start of the range are
+            // now exceptions
+            let new_exs = (self.max + 1)..start;
+            self.exs.extend(new_exs);
+            // the end of the range is now the new max
+            self.max = end;
+            // new event, so `true`
+            res = true;
+        }
+        res
+    }
+
+    /// Creates a new instance from several sorted `events`: the gaps between
+    /// them are added as exceptions as the events are read.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_sorted_events(vec![1, 3, 4, 4, 7]);
+    /// assert_eq!(below_exset, BelowExSet::from(7, vec![2, 5, 6]));
+    /// ```
+    fn from_sorted_events<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut below_exset = BelowExSet::new();
+        for event in iter {
+            debug_assert!(event >= below_exset.max, "events should be sorted");
+            if event > below_exset.max {
+                let gap = (below_exset.max + 1)..event;
+                below_exset.exs.extend(gap);
+                below_exset.max = event;
+            }
+        }
+        below_exset
+    }
+
+    /// Removes an event from the set.
+    /// Returns `true` if the event was part of the set.
+    ///
+    /// Removing an event below the highest event adds it as an exception.
+    /// Removing the highest event lowers it to the highest event left.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_exset = BelowExSet::from_events(vec![1, 2, 4, 5]);
+    /// assert!(below_exset.remove_event(2));
+    /// assert!(!below_exset.is_event(2));
+    /// assert_eq!(below_exset.frontier(), 1);
+    ///
+    /// assert!(below_exset.remove_event(5));
+    /// assert!(below_exset.remove_event(4));
+    /// assert_eq!(below_exset.events(), (1, vec![]));
+    /// assert!(!below_exset.remove_event(4));
+    /// ```
+    fn remove_event(&mut self, event: u64) -> bool {
+        if event == 0 || event > self.max {
+            false
+        } else if event < self.max {
+            self.exs.insert(event)
+        } else {
+            // lower the max to the highest event left, dropping the
+            // exceptions that are no longer below it
+            self.max -= 1;
+            while self.exs.remove(&self.max) {
+                self.max -= 1;
+            }
+            true
+        }
+    }
+
+    /// Removes a range of events from the set.
+    /// Returns `true` if some event in the range was part of the set.
+    ///
+    /// The events removed below the highest event become exceptions. If the
+    /// range includes the highest event, the highest event is lowered instead
+    /// (without adding exceptions).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_exset = BelowExSet::from(10, vec![4]);
+    /// assert!(below_exset.remove_event_range(6, 20));
+    /// assert_eq!(below_exset.events(), (5, vec![4]));
+    ///
+    /// assert!(below_exset.remove_event_range(2, 3));
+    /// assert_eq!(below_exset.frontier(), 1);
+    ///
+    /// assert!(below_exset.remove_event_range(2, 5));
+    /// assert_eq!(below_exset.events(), (1, vec![]));
+    /// ```
+    fn remove_event_range(&mut self, start: u64, end: u64) -> bool {
+        let start = cmp::max(start, 1);
+        let end = cmp::min(end, self.max);
+        if start > end {
+            return false;
+        }
+
+        // some event was removed iff not all events in the range are
+        // exceptions
+        let exs = self.exs.iter().filter(|ex| start <= **ex && **ex <= end);
+        let removed = (exs.count() as u64) < end - start + 1;
+
+        if end == self.max {
+            // lower the max to the highest event left, dropping the
+            // exceptions that are no longer below it
+            self.max = start - 1;
+            self.exs.retain(|ex| *ex < start);
+            while self.exs.remove(&self.max) {
+                self.max -= 1;
+            }
+        } else {
+            self.exs.extend(start..=end);
+        }
+        removed
+    }
+
+    /// Removes all events higher than `max_event` from the set: the highest
+    /// event is capped, and exceptions above it are dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_exset = BelowExSet::from_events(vec![1, 3, 6, 8]);
+    /// below_exset.truncate(7);
+    /// assert_eq!(below_exset.max_event(), 6);
+    ///
+    /// // the highest event is lowered past exceptions
+    /// below_exset.truncate(5);
+    /// assert_eq!(below_exset.events(), (3, vec![2]));
+    /// ```
+    fn truncate(&mut self, max_event: u64) {
+        if max_event < self.max {
+            self.max = max_event;
+            self.exs.retain(|ex| *ex <= max_event);
+
+            // lower the max to the highest event left, dropping the
+            // exceptions that are no longer below it
+            while self.exs.remove(&self.max) {
+                self.max -= 1;
+            }
+        }
+    }
+
     /// Checks if an event is part of the set.
     ///
     /// # Examples
@@ -130,7 +351,7 @@ impl EventSet for BelowExSet {
 
     /// Returns all events seen as a tuple.
     /// The first component is the highest event seen, while the second is a
-    /// vector with the exceptions (in no specific order).
+    /// vector with the exceptions (sorted ASC).
     ///
     /// # Examples
     /// ```
@@ -152,17 +373,23 @@ impl EventSet for BelowExSet {
     ///
     /// below_exset.add_event(6);
     /// assert_eq!(below_exset.events(), (6, vec![5]));
+    ///
+    /// below_exset.add_event(9);
+    /// assert_eq!(below_exset.events(), (9, vec![5, 7, 8]));
     /// ```
     fn events(&self) -> (u64, Vec<u64>) {
-        (self.max, self.exs.clone().into_iter().collect())
+        let mut exs: Vec<_> = self.exs.iter().cloned().collect();
+        exs.sort_unstable();
+        (self.max, exs)
     }
 
     /// Returns the frontier (the highest contiguous event seen).
     ///
-    /// __Note:__ this method's implementation will sort all exceptions on each
-    /// call, and with that, the performance will not be great. If this
-    /// becomes a problem, we could cache the frontier (as in `AboveExSet`)
-    /// so that it doesn't have to be computed here on each call.
+    /// __Note:__ this method's implementation will go through all exceptions
+    /// on each call, and with that, the performance will not be great. If
+    /// this becomes a problem, we could cache the frontier (as in
+    /// `AboveExSet`) so that it doesn't have to be computed here on each
+    /// call.
     ///
     /// # Examples
     /// ```
@@ -189,18 +416,29 @@ impl EventSet for BelowExSet {
     fn frontier(&self) -> u64 {
         // if there are no exceptions, then the highest contiguous event is
         // self.max otherwise, it's the smallest exception - 1
-        if self.exs.is_empty() {
-            self.max
-        } else {
-            // sort exceptions
-            let mut exs: Vec<_> = self.exs.iter().collect();
-            exs.sort_unstable();
-
-            // return the smallest one -1
-            (**exs.iter().next().unwrap()) - 1
+        // - exception 0 is never stored (see `BelowExSet::from`), but the
+        //   subtraction saturates anyway so that the frontier can never wrap
+        //   around
+        match self.exs.iter().min() {
+            None => self.max,
+            Some(smallest) => smallest.saturating_sub(1),
         }
     }
 
+    /// Returns the highest event in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_events(vec![1, 3, 5]);
+    /// assert_eq!(below_exset.max_event(), 5);
+    /// ```
+    fn max_event(&self) -> u64 {
+        // the highest event is never an exception
+        self.max
+    }
+
     /// Merges `other` `BelowExSet` into `self`.
     ///
     /// # Examples
@@ -251,8 +489,118 @@ impl EventSet for BelowExSet {
         todo!("BelowExSet::meet not yet implemented")
     }
 
-    fn subtracted(&self, _other: &Self) -> Vec<u64> {
-        todo!("BelowExSet::subtracted not yet implemented")
+    /// Return a list of events that remain when `other` is subtracted from
+    /// `self`. The events are computed from the exceptions and the highest
+    /// event of both sets, without enumerating the events in common.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from(1_000_002, vec![1_000_001]);
+    /// let other = BelowExSet::from(1_000_000, vec![10, 999_999]);
+    ///
+    /// let mut subtracted = below_exset.subtracted(&other);
+    /// subtracted.sort_unstable();
+    /// assert_eq!(subtracted, vec![10, 999_999, 1_000_002]);
+    /// ```
+    fn subtracted(&self, other: &Self) -> Vec<u64> {
+        // the events in `self` (up to the lowest max) that are exceptions in
+        // `other`
+        let max = cmp::min(self.max, other.max);
+        let missing = other
+            .exs
+            .iter()
+            .filter(|ex| **ex <= max && !self.exs.contains(ex))
+            .cloned();
+
+        // the events in `self` above `other`'s max
+        let above =
+            (other.max + 1..=self.max).filter(|e| !self.exs.contains(e));
+
+        missing.chain(above).collect()
+    }
+
+    /// Returns the number of events that remain when `other` is subtracted
+    /// from `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// let other = BelowExSet::from_events(vec![1, 3, 4, 8]);
+    /// assert_eq!(below_exset.subtracted_count(&other), 2);
+    /// ```
+    fn subtracted_count(&self, other: &Self) -> u64 {
+        // the events in common are all events up to the lowest max, except the
+        // ones that are an exception in either set
+        let max = cmp::min(self.max, other.max);
+        let self_exs = self.exs.iter().filter(|ex| **ex <= max).count() as u64;
+        let other_exs = other
+            .exs
+            .iter()
+            .filter(|ex| **ex <= max && !self.exs.contains(ex))
+            .count() as u64;
+        let common = max - self_exs - other_exs;
+
+        // the number of events in `self` minus the events in common
+        self.max - self.exs.len() as u64 - common
+    }
+
+    /// Returns the number of events in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from(10, vec![4, 6]);
+    /// assert_eq!(below_exset.event_count(), 8);
+    /// ```
+    fn event_count(&self) -> u64 {
+        self.max - self.exs.len() as u64
+    }
+
+    /// Returns the number of exceptions the set stores.
+    fn stored_extras(&self) -> usize {
+        self.exs.len()
+    }
+
+    /// Returns the number of events in the set that are within `[start, end]`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// assert_eq!(below_exset.count_events_in_range(2, 6), 3);
+    /// ```
+    fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        let exs = self
+            .exs
+            .iter()
+            .filter(|ex| start <= **ex && **ex <= end)
+            .count() as u64;
+        super::count_up_to(start, end, self.max) - exs
+    }
+
+    /// Checks if all events within `[start, end]` are in the set: no
+    /// exception can be within the range.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_events(vec![1, 2, 3, 5, 6, 8]);
+    /// assert!(below_exset.contains_all_range(1, 3));
+    /// assert!(below_exset.contains_all_range(5, 6));
+    /// assert!(!below_exset.contains_all_range(3, 5));
+    /// assert!(!below_exset.contains_all_range(8, 9));
+    /// ```
+    fn contains_all_range(&self, start: u64, end: u64) -> bool {
+        start > end
+            || (end <= self.max
+                && !self.exs.iter().any(|ex| start <= *ex && *ex <= end))
     }
 
     /// Returns a `BelowExSet` event iterator with all events from lowest to
@@ -275,14 +623,49 @@ impl EventSet for BelowExSet {
         EventIter {
             current: 0,
             max: self.max,
+            remaining: self.event_count(),
             exs: self.exs,
         }
     }
+
+    /// Returns a `BelowExSet` event iterator with all events from lowest to
+    /// highest, borrowing its exceptions.
+    #[allow(refining_impl_trait)]
+    fn iter(&self) -> Iter<'_> {
+        Iter {
+            current: 0,
+            max: self.max,
+            remaining: self.event_count(),
+            exs: &self.exs,
+        }
+    }
+
+    /// Returns a `BelowExSet` iterator with all events as ranges, from lowest
+    /// to highest: the gaps between the (sorted) exceptions.
+    fn range_iter(self) -> impl Iterator<Item = (u64, u64)> {
+        let mut exs: Vec<_> = self.exs.into_iter().collect();
+        exs.sort_unstable();
+
+        let mut ranges = Vec::with_capacity(exs.len() + 1);
+        // first event not yet in some range
+        let mut start = 1;
+        for ex in exs {
+            if ex > start {
+                ranges.push((start, ex - 1));
+            }
+            start = ex + 1;
+        }
+        if start <= self.max {
+            ranges.push((start, self.max));
+        }
+        ranges.into_iter()
+    }
 }
 
 impl BelowExSet {
     /// Creates a new instance from the highest event, and a sequence of
-    /// exceptions.
+    /// exceptions. Exception 0 and exceptions higher than the highest event
+    /// are dropped, and the highest event is lowered if it's an exception.
     ///
     /// # Examples
     /// ```
@@ -295,12 +678,67 @@ impl BelowExSet {
     /// assert!(below_exset.is_event(4));
     /// assert!(below_exset.is_event(5));
     /// assert!(!below_exset.is_event(6));
+    ///
+    /// // the highest event is lowered if it's an exception
+    /// let below_exset = BelowExSet::from(5, vec![4, 5]);
+    /// assert_eq!(below_exset.events(), (3, vec![]));
     /// ```
     pub fn from<I: IntoIterator<Item = u64>>(max: u64, iter: I) -> Self {
-        BelowExSet {
-            max,
-            exs: HashSet::from_iter(iter),
+        // event 0 is always an event, and events higher than `max` are never
+        // events, so none of them can be an exception
+        let mut exs: HashSet<_> = iter
+            .into_iter()
+            .filter(|ex| *ex != 0 && *ex <= max)
+            .collect();
+
+        // the highest event can't be an exception either: lower it past the
+        // exceptions right below it
+        let mut max = max;
+        while exs.remove(&max) {
+            max -= 1;
         }
+        BelowExSet { max, exs }
+    }
+
+    /// Returns the highest event. This accessor doesn't allocate, and it's
+    /// stable for FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_events(vec![1, 2, 4]);
+    /// assert_eq!(below_exset.max(), 4);
+    /// ```
+    #[inline]
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the number of exceptions. This accessor doesn't allocate, and
+    /// it's stable for FFI wrapping.
+    #[inline]
+    pub fn exceptions_len(&self) -> usize {
+        self.exs.len()
+    }
+
+    /// Copies the exceptions (sorted ASC) into `buf`, returning the number of
+    /// exceptions. If `buf` is too small, nothing is copied and the required
+    /// length is returned. This accessor doesn't allocate, and it's stable for
+    /// FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_events(vec![1, 4, 6]);
+    /// let mut buf = [0; 4];
+    /// let len = below_exset.copy_exceptions_into(&mut buf);
+    /// assert_eq!(&buf[..len], &[2, 3, 5]);
+    /// ```
+    #[inline]
+    pub fn copy_exceptions_into(&self, buf: &mut [u64]) -> usize {
+        super::copy_sorted_into(self.exs.iter().cloned(), self.exs.len(), buf)
     }
 }
 
@@ -309,6 +747,8 @@ pub struct EventIter {
     current: u64,
     // Last value that should be returned by the iterator
     max: u64,
+    // Number of events not yet returned by the iterator
+    remaining: u64,
     // Set of exceptions to be skipped by the iterator
     exs: HashSet<u64>,
 }
@@ -329,10 +769,73 @@ impl Iterator for EventIter {
                 self.next()
             } else {
                 // otherwise, return it
+                self.remaining -= 1;
                 Some(self.current)
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for EventIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        // skip exceptions until the previous event (or the end of the
+        // iterator)
+        while self.current < self.max {
+            self.max -= 1;
+            if !self.exs.contains(&(self.max + 1)) {
+                self.remaining -= 1;
+                return Some(self.max + 1);
+            }
+        }
+        None
+    }
+}
+
+impl ExactSizeIterator for EventIter {}
+
+pub struct Iter<'a> {
+    // Last value returned by the iterator
+    current: u64,
+    // Last value that should be returned by the iterator
+    max: u64,
+    // Number of events not yet returned by the iterator
+    remaining: u64,
+    // Set of exceptions to be skipped by the iterator
+    exs: &'a HashSet<u64>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // skip exceptions until the next event (or the end of the iterator)
+        while self.current < self.max {
+            self.current += 1;
+            if !self.exs.contains(&self.current) {
+                self.remaining -= 1;
+                return Some(self.current);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl PartialOrd for BelowExSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        crate::traits::partial_cmp(self, other)
+    }
 }
 
 impl fmt::Debug for BelowExSet {
@@ -340,14 +843,34 @@ impl fmt::Debug for BelowExSet {
         if self.exs.is_empty() {
             write!(f, "{}", self.max)
         } else {
-            write!(f, "({} - {:?})", self.max, self.exs)
+            let mut exs: Vec<_> = self.exs.iter().collect();
+            exs.sort_unstable();
+            write!(f, "({} - ", self.max)?;
+            super::fmt_items(f, &exs)?;
+            write!(f, ")")
         }
     }
 }
 
+impl fmt::Display for BelowExSet {
+    /// Formats the events as ranges of contiguous events, e.g. `1..3,5,7..8`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from(8, vec![4, 6]);
+    /// assert_eq!(below_exset.to_string(), "1..3,5,7..8");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        super::fmt_ranges(f, self.clone().range_iter())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::*;
 
     #[test]
     fn range() {
@@ -363,4 +886,169 @@ mod tests {
         // check it's the same
         assert_eq!(eset, BelowExSet::from_event_range(start, end));
     }
+
+    #[test]
+    fn from_event_range() {
+        let eset = BelowExSet::from_event_range(5, 10);
+        let expected: HashSet<_> = vec![1, 2, 3, 4].into_iter().collect();
+        assert_eq!(eset.max, 10);
+        assert_eq!(eset.exs, expected);
+
+        // same as adding the range to an empty set
+        let mut other = BelowExSet::new();
+        other.add_event_range(5, 10);
+        assert_eq!(eset, other);
+    }
+
+    #[test]
+    fn range_with_forward_gap() {
+        let mut eset = BelowExSet::new();
+        assert!(eset.add_event_range(100, 110));
+        assert!(eset.is_event(105));
+        assert!(!eset.is_event(50));
+
+        // only events below the range are exceptions
+        let (max, exs) = eset.events();
+        assert_eq!(max, 110);
+        assert_eq!(exs.len(), 99);
+
+        // adding a range already seen is not new
+        assert!(!eset.add_event_range(101, 105));
+
+        // filling some of the gap is new
+        assert!(eset.add_event_range(40, 60));
+        assert!(eset.is_event(50));
+        assert!(!eset.is_event(39));
+        assert!(!eset.is_event(61));
+        assert_eq!(eset.events().1.len(), 78);
+    }
+
+    #[test]
+    fn wide_range() {
+        // events are not added one by one, and so this is instant
+        let mut eset = BelowExSet::new();
+        assert!(eset.add_event_range(1, 10_000_000));
+        assert_eq!(eset.events(), (10_000_000, vec![]));
+
+        // only the exceptions outside the range survive
+        let mut eset = BelowExSet::from_events(vec![1, 3, 5]);
+        assert!(eset.add_event_range(4, 10_000_000));
+        assert_eq!(eset.events(), (10_000_000, vec![2]));
+        assert!(!eset.add_event_range(3, 9_999_999));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let a = ("A", BelowExSet::from(10, vec![2, 5, 6]));
+        let b = ("B", BelowExSet::from_event_range(1, 3));
+        let c = ("C", BelowExSet::from_events(vec![4, 100]));
+        let clock = BEClock::from(vec![a, b, c]);
+
+        let bytes = bincode::serialize(&clock).unwrap();
+        let deserialized: BEClock<&str> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, clock);
+
+        let json = serde_json::to_string(&clock).unwrap();
+        let deserialized: BEClock<&str> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, clock);
+
+        // exceptions higher than max are tolerated (and dropped)
+        let invalid = BelowExSet {
+            max: 5,
+            exs: vec![2, 7].into_iter().collect(),
+        };
+        let bytes = bincode::serialize(&invalid).unwrap();
+        let eset: BelowExSet = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(eset, BelowExSet::from(5, vec![2]));
+    }
+
+    #[test]
+    fn serde_sorted_exceptions() {
+        // equal sets, with exceptions inserted in a different order
+        let a = BelowExSet::from(100, (1..50).rev());
+        let mut b = BelowExSet::from_event(100);
+        for event in 50..100 {
+            b.add_event(event);
+        }
+        assert_eq!(a, b);
+
+        let json = serde_json::to_string(&a).unwrap();
+        assert!(json.starts_with(r#"{"max":100,"exs":[1,2,3,"#));
+        assert_eq!(json, serde_json::to_string(&b).unwrap());
+        let bytes = bincode::serialize(&a).unwrap();
+        assert_eq!(bytes, bincode::serialize(&b).unwrap());
+
+        let clock = BEClock::from(vec![
+            (String::from("A"), a),
+            (String::from("B"), BelowExSet::from(7, vec![2, 5])),
+        ]);
+        let bytes = bincode::serialize(&clock).unwrap();
+        let deserialized: BEClock<String> =
+            bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, clock);
+
+        let json = serde_json::to_string(&clock).unwrap();
+        let deserialized: BEClock<String> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, clock);
+    }
+
+    #[test]
+    fn invalid_exceptions() {
+        // exception 0 and exceptions higher than max are dropped
+        let eset = BelowExSet::from(5, vec![0, 2, 7]);
+        assert_eq!(eset, BelowExSet::from(5, vec![2]));
+        assert_eq!(eset.frontier(), 1);
+        assert!(eset.is_event(0));
+
+        let eset = BelowExSet::from(0, vec![0]);
+        assert_eq!(eset, BelowExSet::new());
+        assert_eq!(eset.frontier(), 0);
+
+        // the same happens when deserializing
+        let json = r#"{"max":5,"exs":[0,2,7]}"#;
+        let eset: BelowExSet = serde_json::from_str(json).unwrap();
+        assert_eq!(eset, BelowExSet::from(5, vec![2]));
+        assert_eq!(eset.frontier(), 1);
+
+        // adding event 0 never creates an exception
+        let mut eset = BelowExSet::new();
+        assert!(!eset.add_event(0));
+        eset.add_event_range(0, 3);
+        assert_eq!(eset.events(), (3, vec![]));
+        eset.join(&BelowExSet::from(3, vec![0]));
+        assert_eq!(eset.frontier(), 3);
+
+        // frontiers upstream are not poisoned
+        let eset = BelowExSet::from(3, vec![0]);
+        let clock = BEClock::from(vec![("A", eset.clone())]);
+        assert_eq!(
+            clock.frontier(),
+            VClock::from(vec![("A", MaxSet::from(3))])
+        );
+        assert_eq!(clock.frontier_threshold(1), Some(3));
+
+        let mut tclock = TClock::new();
+        tclock.add(clock);
+        assert_eq!(tclock.threshold_union(1).get(&"A"), Some(&eset));
+    }
+
+    #[test]
+    fn exception_at_max() {
+        // the highest event (and the exceptions right below it) are dropped
+        let mut eset = BelowExSet::from(5, vec![2, 4, 5]);
+        assert_eq!(eset.events(), (3, vec![2]));
+        assert!(!eset.is_event(5));
+
+        // and so event 5 can be added, but not removed
+        assert!(!eset.remove_event(5));
+        assert!(eset.add_event(5));
+        assert!(eset.is_event(5));
+        assert_eq!(eset.events(), (5, vec![2, 4]));
+
+        // the same happens when deserializing
+        let json = r#"{"max":5,"exs":[4,5]}"#;
+        let eset: BelowExSet = serde_json::from_str(json).unwrap();
+        assert_eq!(eset.events(), (3, vec![]));
+    }
 }
@@ -0,0 +1,859 @@
+//! This module contains an implementation of a below-exception set with the
+//! exceptions encoded as ranges.
+//!
+//! Unlike `BelowExSet`, a gap of missing events is stored as a single range,
+//! and so learning about a high event first doesn't create an exception per
+//! event below it.
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! let mut below_range_set = BelowRangeSet::new();
+//! assert_eq!(below_range_set.next_event(), 1);
+//! assert!(below_range_set.is_event(1));
+//! assert!(!below_range_set.is_event(2));
+//!
+//! let other = BelowRangeSet::from_event(1_000_000);
+//! assert!(!other.is_event(1));
+//! assert!(!other.is_event(999_999));
+//! assert!(other.is_event(1_000_000));
+//!
+//! below_range_set.join(&other);
+//! assert!(below_range_set.is_event(1));
+//! assert!(!below_range_set.is_event(2));
+//! assert!(below_range_set.is_event(1_000_000));
+//! assert_eq!(below_range_set.ranges_len(), 1);
+//! ```
+
+use crate::EventSet;
+use serde::{Deserialize, Serialize};
+use std::cmp::{self, Ordering};
+use std::collections::btree_map::{self, BTreeMap};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{Flatten, Peekable};
+use std::ops::RangeInclusive;
+use std::vec;
+
+#[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "RawBelowRangeSet")]
+pub struct BelowRangeSet {
+    // Highest event seen
+    max: u64,
+    // Mapping from start of each range of exceptions to its end (sorted ASC)
+    // - ranges are disjoint and non-adjacent
+    // - all exceptions are below `max`
+    exs: BTreeMap<u64, u64>,
+}
+
+// Unvalidated `BelowRangeSet`, used when deserializing.
+#[derive(Deserialize)]
+struct RawBelowRangeSet {
+    max: u64,
+    exs: BTreeMap<u64, u64>,
+}
+
+impl From<RawBelowRangeSet> for BelowRangeSet {
+    fn from(raw: RawBelowRangeSet) -> Self {
+        BelowRangeSet::from(raw.max, raw.exs)
+    }
+}
+
+impl EventSet for BelowRangeSet {
+    type EventIter = EventIter;
+    const KIND: &'static str = "BRClock";
+
+    /// Returns a new `BelowRangeSet` instance.
+    fn new() -> Self {
+        BelowRangeSet {
+            max: 0,
+            exs: BTreeMap::new(),
+        }
+    }
+
+    /// Generates the next event, i.e. the event right after the highest
+    /// event.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::new();
+    /// assert_eq!(below_range_set.next_event(), 1);
+    /// assert_eq!(below_range_set.next_event(), 2);
+    /// ```
+    fn next_event(&mut self) -> u64 {
+        self.max += 1;
+        self.max
+    }
+
+    /// Adds an event to the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::new();
+    ///
+    /// below_range_set.add_event(1);
+    /// assert!(below_range_set.is_event(1));
+    /// assert!(!below_range_set.is_event(2));
+    ///
+    /// below_range_set.add_event(3);
+    /// assert!(below_range_set.is_event(1));
+    /// assert!(!below_range_set.is_event(2));
+    /// assert!(below_range_set.is_event(3));
+    ///
+    /// below_range_set.add_event(2);
+    /// assert!(below_range_set.is_event(1));
+    /// assert!(below_range_set.is_event(2));
+    /// assert!(below_range_set.is_event(3));
+    /// ```
+    fn add_event(&mut self, event: u64) -> bool {
+        self.add_event_range(event, event)
+    }
+
+    /// Adds a range of events to the set.
+    /// Returns `true` if a new event was added.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::new();
+    /// assert!(below_range_set.add_event_range(3, 5));
+    /// assert_eq!(below_range_set.events(), (5, vec![1, 2]));
+    ///
+    /// assert!(below_range_set.add_event_range(1, 2));
+    /// assert_eq!(below_range_set.events(), (5, vec![]));
+    /// assert!(!below_range_set.add_event_range(2, 4));
+    /// ```
+    fn add_event_range(&mut self, start: u64, end: u64) -> bool {
+        // event 0 is always an event
+        let start = cmp::max(start, 1);
+        if start > end {
+            return false;
+        }
+
+        // exceptions within the range are now events
+        let mut res = self.remove_exceptions(start, end);
+
+        if end > self.max {
+            // events between the current max and the start of the range are
+            // now exceptions
+            if start > self.max + 1 {
+                self.exs.insert(self.max + 1, start - 1);
+            }
+            // the end of the range is now the new max
+            self.max = end;
+            // new event, so `true`
+            res = true;
+        }
+        res
+    }
+
+    /// Removes an event from the set.
+    /// Returns `true` if the event was part of the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::from_events(vec![1, 2, 4, 5]);
+    /// assert!(below_range_set.remove_event(2));
+    /// assert_eq!(below_range_set.events(), (5, vec![2, 3]));
+    ///
+    /// assert!(below_range_set.remove_event(5));
+    /// assert!(below_range_set.remove_event(4));
+    /// assert_eq!(below_range_set.events(), (1, vec![]));
+    /// assert!(!below_range_set.remove_event(4));
+    /// ```
+    fn remove_event(&mut self, event: u64) -> bool {
+        self.remove_event_range(event, event)
+    }
+
+    /// Removes a range of events from the set.
+    /// Returns `true` if some event in the range was part of the set.
+    ///
+    /// The events removed below the highest event become exceptions. If the
+    /// range includes the highest event, the highest event is lowered instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::from_event_range(1, 10);
+    /// assert!(below_range_set.remove_event_range(6, 20));
+    /// assert_eq!(below_range_set.events(), (5, vec![]));
+    ///
+    /// assert!(below_range_set.remove_event_range(2, 3));
+    /// assert_eq!(below_range_set.ranges_len(), 1);
+    ///
+    /// assert!(below_range_set.remove_event_range(2, 5));
+    /// assert_eq!(below_range_set.events(), (1, vec![]));
+    /// ```
+    fn remove_event_range(&mut self, start: u64, end: u64) -> bool {
+        let start = cmp::max(start, 1);
+        let end = cmp::min(end, self.max);
+        if start > end {
+            return false;
+        }
+
+        let removed = self.count_events_in_range(start, end) > 0;
+        if end == self.max {
+            self.truncate(start - 1);
+        } else {
+            self.add_exceptions(start, end);
+        }
+        removed
+    }
+
+    /// Removes all events higher than `max_event` from the set: the highest
+    /// event is capped, and exceptions above it are dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::from_events(vec![1, 3, 6, 8]);
+    /// below_range_set.truncate(7);
+    /// assert_eq!(below_range_set.max_event(), 6);
+    ///
+    /// // the highest event is lowered past exceptions
+    /// below_range_set.truncate(5);
+    /// assert_eq!(below_range_set.events(), (3, vec![2]));
+    /// ```
+    fn truncate(&mut self, max_event: u64) {
+        if max_event < self.max {
+            self.max = max_event;
+            // drop the exceptions above the new max
+            self.exs.split_off(&(max_event + 1));
+            if let Some(end) = self.exs.values_mut().next_back() {
+                *end = cmp::min(*end, max_event);
+            }
+
+            // lower the max to the highest event left: since ranges are
+            // non-adjacent, this drops at most one range
+            if let Some((&start, &end)) = self.exs.iter().next_back() {
+                if end == self.max {
+                    self.exs.remove(&start);
+                    self.max = start - 1;
+                }
+            }
+        }
+    }
+
+    /// Checks if an event is part of the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::new();
+    /// let event = below_range_set.next_event();
+    /// assert!(below_range_set.is_event(event));
+    ///
+    /// below_range_set.add_event(4);
+    /// assert!(!below_range_set.is_event(2));
+    /// assert!(!below_range_set.is_event(3));
+    /// assert!(below_range_set.is_event(4));
+    /// ```
+    fn is_event(&self, event: u64) -> bool {
+        event <= self.max && !self.is_exception(event)
+    }
+
+    /// Returns all events seen as a tuple.
+    /// The first component is the highest event seen, while the second is a
+    /// vector with the exceptions (sorted ASC).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::new();
+    ///
+    /// below_range_set.add_event(1);
+    /// assert_eq!(below_range_set.events(), (1, vec![]));
+    ///
+    /// below_range_set.add_event(3);
+    /// assert_eq!(below_range_set.events(), (3, vec![2]));
+    ///
+    /// below_range_set.add_event(6);
+    /// assert_eq!(below_range_set.events(), (6, vec![2, 4, 5]));
+    /// ```
+    fn events(&self) -> (u64, Vec<u64>) {
+        let exs = self.exs.iter().flat_map(|(&start, &end)| start..=end);
+        (self.max, exs.collect())
+    }
+
+    /// Returns the frontier (the highest contiguous event seen).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::new();
+    /// assert_eq!(below_range_set.frontier(), 0);
+    ///
+    /// below_range_set.add_event(1);
+    /// below_range_set.add_event(3);
+    /// assert_eq!(below_range_set.frontier(), 1);
+    ///
+    /// below_range_set.add_event(2);
+    /// assert_eq!(below_range_set.frontier(), 3);
+    /// ```
+    fn frontier(&self) -> u64 {
+        // the smallest exception - 1, or the highest event if there are none
+        self.exs.keys().next().map_or(self.max, |start| start - 1)
+    }
+
+    /// Returns the highest event in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from_events(vec![1, 3, 5]);
+    /// assert_eq!(below_range_set.max_event(), 5);
+    /// ```
+    fn max_event(&self) -> u64 {
+        self.max
+    }
+
+    /// Merges `other` `BelowRangeSet` into `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::from_events(vec![1, 3, 4]);
+    /// below_range_set.join(&BelowRangeSet::from_event(5));
+    /// assert_eq!(below_range_set.events(), (5, vec![2]));
+    ///
+    /// let other = BelowRangeSet::from_events(vec![2, 7]);
+    /// below_range_set.join(&other);
+    /// assert_eq!(below_range_set.events(), (7, vec![6]));
+    /// ```
+    fn join(&mut self, other: &Self) {
+        let mut intervals = self.intervals();
+        intervals.extend(other.intervals());
+        intervals.sort_unstable();
+        *self = BelowRangeSet::from_intervals(super::coalesce(intervals));
+    }
+
+    /// Intersects `other` `BelowRangeSet` with `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::from_events(vec![1, 2, 4, 6]);
+    /// below_range_set.meet(&BelowRangeSet::from_events(vec![2, 3, 4, 5]));
+    /// assert_eq!(below_range_set.events(), (4, vec![1, 3]));
+    /// ```
+    fn meet(&mut self, other: &Self) {
+        let intervals =
+            super::intersect_intervals(&self.intervals(), &other.intervals());
+        *self = BelowRangeSet::from_intervals(intervals);
+    }
+
+    /// Return a list of events that remain when `other` is subtracted from
+    /// `self`. See `BelowRangeSet::subtracted_ranges` for a version that
+    /// doesn't enumerate events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// let other = BelowRangeSet::from_events(vec![1, 3, 4, 8]);
+    /// assert_eq!(below_range_set.subtracted(&other), vec![2, 5]);
+    /// ```
+    fn subtracted(&self, other: &Self) -> Vec<u64> {
+        self.subtracted_ranges(other)
+            .into_iter()
+            .flat_map(|(start, end)| start..=end)
+            .collect()
+    }
+
+    /// Returns the number of events that remain when `other` is subtracted
+    /// from `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from_event_range(1, 1_000_000);
+    /// let other = BelowRangeSet::from_event_range(11, 1_000_000);
+    /// assert_eq!(below_range_set.subtracted_count(&other), 10);
+    /// ```
+    fn subtracted_count(&self, other: &Self) -> u64 {
+        self.subtracted_ranges(other)
+            .into_iter()
+            .map(|(start, end)| end - start + 1)
+            .sum()
+    }
+
+    /// Returns the number of events in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from(10, vec![(4, 6)]);
+    /// assert_eq!(below_range_set.event_count(), 7);
+    /// ```
+    fn event_count(&self) -> u64 {
+        let exs: u64 =
+            self.exs.iter().map(|(start, end)| end - start + 1).sum();
+        self.max - exs
+    }
+
+    /// Returns the number of ranges of exceptions the set stores.
+    fn stored_extras(&self) -> usize {
+        self.exs.len()
+    }
+
+    /// Returns the number of events in the set that are within `[start, end]`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// assert_eq!(below_range_set.count_events_in_range(2, 6), 3);
+    /// ```
+    fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        if start > end {
+            return 0;
+        }
+        let exs: u64 = self
+            .overlapping(start, end)
+            .map(|(ex_start, ex_end)| {
+                cmp::min(ex_end, end) - cmp::max(ex_start, start) + 1
+            })
+            .sum();
+        super::count_up_to(start, end, self.max) - exs
+    }
+
+    /// Checks if all events within `[start, end]` are in the set: no
+    /// exception can be within the range.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from_events(vec![1, 2, 3, 5, 6, 8]);
+    /// assert!(below_range_set.contains_all_range(1, 3));
+    /// assert!(below_range_set.contains_all_range(5, 6));
+    /// assert!(!below_range_set.contains_all_range(3, 5));
+    /// assert!(!below_range_set.contains_all_range(8, 9));
+    /// ```
+    fn contains_all_range(&self, start: u64, end: u64) -> bool {
+        start > end
+            || (end <= self.max
+                && self.overlapping(start, end).next().is_none())
+    }
+
+    /// Returns a hash of the set that only depends on its events, computed
+    /// over its ranges (see `EventSet::range_iter`) without enumerating its
+    /// events.
+    fn canonical_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.intervals().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns a `BelowRangeSet` event iterator with all events from lowest to
+    /// highest.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut below_range_set = BelowRangeSet::new();
+    /// below_range_set.add_event(3);
+    /// below_range_set.add_event(5);
+    ///
+    /// let mut iter = below_range_set.event_iter();
+    /// assert_eq!(iter.next(), Some(3));
+    /// assert_eq!(iter.next(), Some(5));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    fn event_iter(self) -> Self::EventIter {
+        let remaining = self.event_count();
+        let ranges: Vec<_> = self
+            .intervals()
+            .into_iter()
+            .map(|(start, end)| start..=end)
+            .collect();
+        EventIter {
+            events: ranges.into_iter().flatten(),
+            remaining,
+        }
+    }
+
+    /// Returns a `BelowRangeSet` event iterator with all events from lowest to
+    /// highest, borrowing its exceptions.
+    #[allow(refining_impl_trait)]
+    fn iter(&self) -> Iter<'_> {
+        Iter {
+            current: 0,
+            max: self.max,
+            remaining: self.event_count(),
+            exs: self.exs.iter().peekable(),
+        }
+    }
+
+    /// Returns a `BelowRangeSet` iterator with all events as ranges, from
+    /// lowest to highest: the gaps between the ranges of exceptions.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from(10, vec![(3, 4), (8, 8)]);
+    /// let ranges: Vec<_> = below_range_set.range_iter().collect();
+    /// assert_eq!(ranges, vec![(1, 2), (5, 7), (9, 10)]);
+    /// ```
+    fn range_iter(self) -> impl Iterator<Item = (u64, u64)> {
+        self.intervals().into_iter()
+    }
+}
+
+impl BelowRangeSet {
+    /// Checks if an event is an exception.
+    fn is_exception(&self, event: u64) -> bool {
+        self.exs
+            .range(..=event)
+            .next_back()
+            .is_some_and(|(_, &end)| event <= end)
+    }
+
+    /// Returns the ranges of exceptions overlapping with `[start, end]` (from
+    /// highest to lowest).
+    fn overlapping(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> impl Iterator<Item = (u64, u64)> + '_ {
+        // ranges are disjoint and sorted, so the ones overlapping are the last
+        // ones starting at or before `end`
+        self.exs
+            .range(..=end)
+            .rev()
+            .take_while(move |(_, ex_end)| **ex_end >= start)
+            .map(|(&ex_start, &ex_end)| (ex_start, ex_end))
+    }
+
+    /// Removes the exceptions within `[start, end]`, splitting the ranges that
+    /// are partially within it. Returns `true` if some exception was removed.
+    fn remove_exceptions(&mut self, start: u64, end: u64) -> bool {
+        let overlapping: Vec<_> = self.overlapping(start, end).collect();
+        for &(ex_start, ex_end) in overlapping.iter() {
+            self.exs.remove(&ex_start);
+            if ex_start < start {
+                self.exs.insert(ex_start, start - 1);
+            }
+            if ex_end > end {
+                self.exs.insert(end + 1, ex_end);
+            }
+        }
+        !overlapping.is_empty()
+    }
+
+    /// Adds `[start, end]` as exceptions, merging it with the ranges that
+    /// overlap with it or are adjacent to it.
+    fn add_exceptions(&mut self, mut start: u64, mut end: u64) {
+        let touching: Vec<_> = self
+            .overlapping(start.saturating_sub(1), end.saturating_add(1))
+            .collect();
+        for (ex_start, ex_end) in touching {
+            self.exs.remove(&ex_start);
+            start = cmp::min(start, ex_start);
+            end = cmp::max(end, ex_end);
+        }
+        self.exs.insert(start, end);
+    }
+
+    /// Returns all events as a sorted list of disjoint (and non-adjacent)
+    /// intervals (both ends included).
+    fn intervals(&self) -> Vec<(u64, u64)> {
+        let mut intervals = Vec::with_capacity(self.exs.len() + 1);
+        // first event not yet in some interval
+        let mut start = 1;
+        for (&ex_start, &ex_end) in self.exs.iter() {
+            if ex_start > start {
+                intervals.push((start, ex_start - 1));
+            }
+            start = ex_end + 1;
+        }
+        if start <= self.max {
+            intervals.push((start, self.max));
+        }
+        intervals
+    }
+
+    /// Creates a new instance from a sorted list of disjoint intervals (both
+    /// ends included): the gaps between them are the exceptions.
+    fn from_intervals(intervals: Vec<(u64, u64)>) -> Self {
+        let mut below_range_set = BelowRangeSet::new();
+        for (start, end) in intervals {
+            if start > below_range_set.max + 1 {
+                below_range_set
+                    .exs
+                    .insert(below_range_set.max + 1, start - 1);
+            }
+            below_range_set.max = end;
+        }
+        below_range_set
+    }
+
+    /// Returns the events that remain when `other` is subtracted from `self`,
+    /// as a sorted list of disjoint (and non-adjacent) ranges, with both ends
+    /// included. Events are never enumerated: the cost is linear in the number
+    /// of ranges of exceptions.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from_event_range(1, 3_000_000);
+    /// let other = BelowRangeSet::from(2_500_000, vec![(11, 499_999)]);
+    ///
+    /// assert_eq!(
+    ///     below_range_set.subtracted_ranges(&other),
+    ///     vec![(11, 499_999), (2_500_001, 3_000_000)]
+    /// );
+    /// ```
+    pub fn subtracted_ranges(&self, other: &Self) -> Vec<(u64, u64)> {
+        super::subtract_intervals(&self.intervals(), &other.intervals())
+    }
+
+    /// Creates a new instance from the highest event, and a sequence of
+    /// ranges of exceptions (both ends included). Exception 0 and exceptions
+    /// higher than the highest event are dropped, and overlapping ranges are
+    /// merged.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from(6, vec![(1, 2), (4, 4)]);
+    /// assert!(!below_range_set.is_event(1));
+    /// assert!(!below_range_set.is_event(2));
+    /// assert!(below_range_set.is_event(3));
+    /// assert!(!below_range_set.is_event(4));
+    /// assert!(below_range_set.is_event(5));
+    /// assert!(below_range_set.is_event(6));
+    /// assert!(!below_range_set.is_event(7));
+    ///
+    /// // the highest event is lowered if it's an exception
+    /// let below_range_set = BelowRangeSet::from(5, vec![(4, 5)]);
+    /// assert_eq!(below_range_set.max_event(), 3);
+    /// ```
+    pub fn from<I: IntoIterator<Item = (u64, u64)>>(max: u64, iter: I) -> Self {
+        let mut below_range_set = BelowRangeSet::from_event_range(1, max);
+        for (start, end) in iter {
+            below_range_set.remove_event_range(start, end);
+        }
+        below_range_set
+    }
+
+    /// Returns the highest event. This accessor doesn't allocate, and it's
+    /// stable for FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from_events(vec![1, 2, 4]);
+    /// assert_eq!(below_range_set.max(), 4);
+    /// ```
+    #[inline]
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the number of ranges of exceptions. This accessor doesn't
+    /// allocate, and it's stable for FFI wrapping.
+    #[inline]
+    pub fn ranges_len(&self) -> usize {
+        self.exs.len()
+    }
+}
+
+pub struct EventIter {
+    // Events not yet returned by the iterator
+    events: Flatten<vec::IntoIter<RangeInclusive<u64>>>,
+    // Number of events not yet returned by the iterator
+    remaining: u64,
+}
+
+impl Iterator for EventIter {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.events.next()?;
+        self.remaining -= 1;
+        Some(event)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for EventIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let event = self.events.next_back()?;
+        self.remaining -= 1;
+        Some(event)
+    }
+}
+
+impl ExactSizeIterator for EventIter {}
+
+pub struct Iter<'a> {
+    // Last value returned by the iterator
+    current: u64,
+    // Last value that should be returned by the iterator
+    max: u64,
+    // Number of events not yet returned by the iterator
+    remaining: u64,
+    // Ranges of exceptions to be skipped by the iterator
+    exs: Peekable<btree_map::Iter<'a, u64, u64>>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current < self.max {
+            self.current += 1;
+            match self.exs.peek() {
+                Some((&start, &end)) if start == self.current => {
+                    // skip the whole range of exceptions
+                    self.current = end;
+                    self.exs.next();
+                }
+                _ => {
+                    self.remaining -= 1;
+                    return Some(self.current);
+                }
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.remaining as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl PartialOrd for BelowRangeSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        crate::traits::partial_cmp(self, other)
+    }
+}
+
+impl fmt::Debug for BelowRangeSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.exs.is_empty() {
+            write!(f, "{}", self.max)
+        } else {
+            let exs: Vec<_> = self
+                .exs
+                .iter()
+                .map(|(start, end)| format!("{}: {}", start, end))
+                .collect();
+            write!(f, "({} - ", self.max)?;
+            super::fmt_items(f, &exs)?;
+            write!(f, ")")
+        }
+    }
+}
+
+impl fmt::Display for BelowRangeSet {
+    /// Formats the events as ranges of contiguous events, e.g. `1..3,5,7..8`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_range_set = BelowRangeSet::from(8, vec![(4, 4), (6, 6)]);
+    /// assert_eq!(below_range_set.to_string(), "1..3,5,7..8");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        super::fmt_ranges(f, self.intervals())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn wide_gap() {
+        // a gap is a single range of exceptions, no matter how wide
+        let mut eset = BelowRangeSet::from_event(10_000_000);
+        assert_eq!(eset.ranges_len(), 1);
+        assert_eq!(eset.frontier(), 0);
+        assert_eq!(eset.event_count(), 1);
+
+        // filling the middle of the gap splits it
+        assert!(eset.add_event_range(100, 200));
+        assert_eq!(eset.ranges_len(), 2);
+        assert_eq!(eset.count_events_in_range(1, 1_000), 101);
+        assert!(!eset.add_event_range(150, 160));
+
+        // removing events next to a gap merges them into it
+        assert!(eset.remove_event_range(100, 150));
+        assert!(eset.remove_event_range(151, 200));
+        assert_eq!(eset.ranges_len(), 1);
+        assert_eq!(eset, BelowRangeSet::from_event(10_000_000));
+    }
+
+    #[test]
+    fn join_and_meet() {
+        let a = BelowRangeSet::from(1_000_000, vec![(10, 500_000)]);
+        let b = BelowRangeSet::from(2_000_000, vec![(1, 999_999)]);
+
+        let mut join = a.clone();
+        join.join(&b);
+        assert_eq!(join, BelowRangeSet::from(2_000_000, vec![(10, 500_000)]));
+
+        let mut meet = a.clone();
+        meet.meet(&b);
+        assert_eq!(meet, BelowRangeSet::from_event(1_000_000));
+    }
+
+    #[test]
+    fn serde_round_trip() {
+        let a = ("A", BelowRangeSet::from(10, vec![(2, 2), (5, 6)]));
+        let b = ("B", BelowRangeSet::from_event_range(1, 3));
+        let c = ("C", BelowRangeSet::from_events(vec![4, 100]));
+        let clock = BRClock::from(vec![a, b, c]);
+
+        let bytes = bincode::serialize(&clock).unwrap();
+        let deserialized: BRClock<&str> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(deserialized, clock);
+
+        let json = serde_json::to_string(&clock).unwrap();
+        let deserialized: BRClock<&str> = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, clock);
+
+        // invalid exceptions are normalized
+        let json = r#"{"max":5,"exs":{"0":1,"3":3,"4":4,"6":7}}"#;
+        let eset: BelowRangeSet = serde_json::from_str(json).unwrap();
+        assert_eq!(eset.events(), (5, vec![1, 3, 4]));
+        assert_eq!(eset.ranges_len(), 2);
+    }
+}
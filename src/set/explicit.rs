@@ -0,0 +1,413 @@
+//! This module contains an implementation of an explicit set, i.e. a set that
+//! stores each event explicitly.
+//!
+//! This representation makes no assumption about how events are generated (in
+//! particular, it doesn't benefit from events being contiguous), and thus it's
+//! only suitable for small sets. It's also used as the reference model against
+//! which other `EventSet` implementations are tested.
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! let mut explicit_set = ExplicitSet::new();
+//! assert_eq!(explicit_set.next_event(), 1);
+//! assert!(explicit_set.is_event(1));
+//! assert!(!explicit_set.is_event(2));
+//!
+//! let other = ExplicitSet::from_event(3);
+//! assert!(!other.is_event(1));
+//! assert!(!other.is_event(2));
+//! assert!(other.is_event(3));
+//!
+//! explicit_set.join(&other);
+//! assert!(explicit_set.is_event(1));
+//! assert!(!explicit_set.is_event(2));
+//! assert!(explicit_set.is_event(3));
+//! ```
+
+use crate::EventSet;
+use serde::{Deserialize, Serialize};
+use std::cmp::{self, Ordering};
+use std::collections::btree_set::{self, BTreeSet};
+use std::fmt;
+
+#[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ExplicitSet {
+    // Set of events seen (sorted ASC)
+    events: BTreeSet<u64>,
+}
+
+impl EventSet for ExplicitSet {
+    type EventIter = EventIter;
+    const KIND: &'static str = "XClock";
+
+    /// Returns a new `ExplicitSet` instance.
+    fn new() -> Self {
+        ExplicitSet {
+            events: BTreeSet::new(),
+        }
+    }
+
+    /// Generates the next event, i.e. the event after the highest event seen.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::new();
+    /// assert_eq!(explicit_set.next_event(), 1);
+    /// assert_eq!(explicit_set.next_event(), 2);
+    /// ```
+    fn next_event(&mut self) -> u64 {
+        let next = self.events.iter().next_back().map_or(1, |max| max + 1);
+        self.events.insert(next);
+        next
+    }
+
+    /// Adds an event to the set.
+    /// Returns `true` if it's a new event.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::new();
+    ///
+    /// explicit_set.add_event(1);
+    /// assert!(explicit_set.is_event(1));
+    /// assert!(!explicit_set.is_event(2));
+    ///
+    /// explicit_set.add_event(3);
+    /// assert!(explicit_set.is_event(1));
+    /// assert!(!explicit_set.is_event(2));
+    /// assert!(explicit_set.is_event(3));
+    /// ```
+    fn add_event(&mut self, event: u64) -> bool {
+        // as in the other `EventSet` implementations, event 0 is always part
+        // of the set
+        event != 0 && self.events.insert(event)
+    }
+
+    /// Creates a new instance from several sorted `events`, building the
+    /// underlying `BTreeSet` in bulk.
+    fn from_sorted_events<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        // 0's are not events
+        let events = iter.into_iter().filter(|event| *event != 0);
+        ExplicitSet {
+            events: events.collect(),
+        }
+    }
+
+    /// Removes an event from the set.
+    /// Returns `true` if the event was part of the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::from_events(vec![1, 2, 3]);
+    /// assert!(explicit_set.remove_event(2));
+    /// assert_eq!(explicit_set.events(), (1, vec![3]));
+    /// assert!(!explicit_set.remove_event(2));
+    /// ```
+    fn remove_event(&mut self, event: u64) -> bool {
+        self.events.remove(&event)
+    }
+
+    /// Removes a range of events from the set.
+    /// Returns `true` if some event in the range was part of the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::from_events(vec![1, 2, 3, 5]);
+    /// assert!(explicit_set.remove_event_range(2, 4));
+    /// assert_eq!(explicit_set.events(), (1, vec![5]));
+    /// assert!(!explicit_set.remove_event_range(2, 4));
+    /// ```
+    fn remove_event_range(&mut self, start: u64, end: u64) -> bool {
+        let len = self.events.len();
+        self.events.retain(|event| *event < start || *event > end);
+        self.events.len() < len
+    }
+
+    /// Removes all events higher than `max_event` from the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::from_events(vec![1, 3, 6, 8]);
+    /// explicit_set.truncate(6);
+    /// assert_eq!(explicit_set.events(), (1, vec![3, 6]));
+    /// ```
+    fn truncate(&mut self, max_event: u64) {
+        if max_event < u64::MAX {
+            self.events.split_off(&(max_event + 1));
+        }
+    }
+
+    /// Checks if an event is part of the set.
+    /// As in the other `EventSet` implementations, event 0 is always part of
+    /// the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::new();
+    /// let event = explicit_set.next_event();
+    /// assert!(explicit_set.is_event(event));
+    ///
+    /// explicit_set.add_event(3);
+    /// assert!(!explicit_set.is_event(2));
+    /// assert!(explicit_set.is_event(3));
+    /// ```
+    fn is_event(&self, event: u64) -> bool {
+        event == 0 || self.events.contains(&event)
+    }
+
+    /// Returns all events seen as a tuple.
+    /// The first component is the highest contiguous event seen, while the
+    /// second is a (sorted) vector with the remaining events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let explicit_set = ExplicitSet::from_events(vec![1, 2, 3, 5, 6]);
+    /// assert_eq!(explicit_set.events(), (3, vec![5, 6]));
+    /// ```
+    fn events(&self) -> (u64, Vec<u64>) {
+        let frontier = self.frontier();
+        let rest = self.events.range(frontier + 1..).cloned().collect();
+        (frontier, rest)
+    }
+
+    /// Returns the frontier (the highest contiguous event seen).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::new();
+    /// assert_eq!(explicit_set.frontier(), 0);
+    ///
+    /// explicit_set.add_event(1);
+    /// assert_eq!(explicit_set.frontier(), 1);
+    ///
+    /// explicit_set.add_event(3);
+    /// assert_eq!(explicit_set.frontier(), 1);
+    ///
+    /// explicit_set.add_event(2);
+    /// assert_eq!(explicit_set.frontier(), 3);
+    /// ```
+    fn frontier(&self) -> u64 {
+        // since events are sorted, the frontier is the last event `event` such
+        // that it's the `event`-th event
+        let mut frontier = 0;
+        for &event in self.events.iter() {
+            if event == frontier + 1 {
+                frontier = event;
+            } else {
+                break;
+            }
+        }
+        frontier
+    }
+
+    /// Returns the highest event in the set.
+    fn max_event(&self) -> u64 {
+        self.events.iter().next_back().cloned().unwrap_or(0)
+    }
+
+    /// Merges `other` `ExplicitSet` into `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::from_events(vec![1, 3]);
+    /// explicit_set.join(&ExplicitSet::from_events(vec![2, 5]));
+    /// assert_eq!(explicit_set.events(), (3, vec![5]));
+    /// ```
+    fn join(&mut self, other: &Self) {
+        self.events.extend(other.events.iter().cloned());
+    }
+
+    /// Intersects `other` `ExplicitSet` with `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::from_events(vec![1, 2, 3, 5]);
+    /// explicit_set.meet(&ExplicitSet::from_events(vec![1, 2, 5]));
+    /// assert_eq!(explicit_set.events(), (2, vec![5]));
+    /// ```
+    fn meet(&mut self, other: &Self) {
+        self.events.retain(|event| other.events.contains(event));
+    }
+
+    /// Return a list of events that remain when `other` is subtracted from
+    /// `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let explicit_set = ExplicitSet::from_events(vec![1, 2, 3, 5]);
+    /// let other = ExplicitSet::from_events(vec![2, 5]);
+    /// assert_eq!(explicit_set.subtracted(&other), vec![1, 3]);
+    /// ```
+    fn subtracted(&self, other: &Self) -> Vec<u64> {
+        self.events.difference(&other.events).cloned().collect()
+    }
+
+    /// Returns the number of events that remain when `other` is subtracted
+    /// from `self`.
+    fn subtracted_count(&self, other: &Self) -> u64 {
+        self.events.difference(&other.events).count() as u64
+    }
+
+    /// Returns the number of events in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let explicit_set = ExplicitSet::from_events(vec![1, 3, 5]);
+    /// assert_eq!(explicit_set.event_count(), 3);
+    /// ```
+    fn event_count(&self) -> u64 {
+        self.events.len() as u64
+    }
+
+    /// Returns the number of events in the set that are within `[start, end]`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let explicit_set = ExplicitSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// assert_eq!(explicit_set.count_events_in_range(2, 6), 3);
+    /// ```
+    fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        if start > end {
+            return 0;
+        }
+        self.events.range(start..=end).count() as u64
+    }
+
+    /// Checks if all events within `[start, end]` are in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let explicit_set = ExplicitSet::from_events(vec![1, 2, 3, 5, 6, 8]);
+    /// assert!(explicit_set.contains_all_range(1, 3));
+    /// assert!(!explicit_set.contains_all_range(5, 8));
+    /// ```
+    fn contains_all_range(&self, start: u64, end: u64) -> bool {
+        // event 0 is always part of the set
+        let start = cmp::max(start, 1);
+        start > end
+            || self.events.range(start..=end).count() as u64 == end - start + 1
+    }
+
+    /// Returns a `ExplicitSet` event iterator with all events from lowest to
+    /// highest.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut explicit_set = ExplicitSet::new();
+    /// explicit_set.add_event(3);
+    /// explicit_set.add_event(5);
+    ///
+    /// let mut iter = explicit_set.event_iter();
+    /// assert_eq!(iter.next(), Some(3));
+    /// assert_eq!(iter.next(), Some(5));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    fn event_iter(self) -> Self::EventIter {
+        EventIter(self.events.into_iter())
+    }
+
+    /// Returns a `ExplicitSet` event iterator with all events from lowest to
+    /// highest, borrowing its events.
+    #[allow(refining_impl_trait)]
+    fn iter(&self) -> Iter<'_> {
+        Iter(self.events.iter())
+    }
+}
+
+pub struct EventIter(btree_set::IntoIter<u64>);
+
+impl Iterator for EventIter {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for EventIter {}
+
+impl DoubleEndedIterator for EventIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+pub struct Iter<'a>(btree_set::Iter<'a, u64>);
+
+impl Iterator for Iter<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl PartialOrd for ExplicitSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        crate::traits::partial_cmp(self, other)
+    }
+}
+
+impl fmt::Debug for ExplicitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let events: Vec<_> = self.events.iter().collect();
+        super::fmt_items(f, &events)
+    }
+}
+
+impl fmt::Display for ExplicitSet {
+    /// Formats the events as ranges of contiguous events, e.g. `1..3,5,7..8`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let explicit_set = ExplicitSet::from_events(vec![1, 2, 3, 5, 7, 8]);
+    /// assert_eq!(explicit_set.to_string(), "1..3,5,7..8");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        super::fmt_ranges(f, self.clone().range_iter())
+    }
+}
@@ -18,26 +18,57 @@
 //! assert!(maxset.is_event(3));
 //! ```
 
-use crate::EventSet;
+use crate::{EventSet, Seq};
 use serde::{Deserialize, Serialize};
+use std::cmp::{self, Ordering};
 use std::fmt;
 
+/// A `MaxSetOf` storing events as `u64`s.
+pub type MaxSet = MaxSetOf<u64>;
+
+/// A max set storing events as `S` (e.g. `u32`, to halve its size). Events
+/// are still `u64`s in the `EventSet` API; events that don't fit in `S` are
+/// not added by `add_event` and `add_event_range` (see `EventSet::MAX_SEQ`).
+///
+/// # Examples
+/// ```
+/// use threshold::*;
+///
+/// let mut maxset = MaxSetOf::<u32>::new();
+/// maxset.add_event(10);
+/// assert_eq!(maxset.frontier(), 10);
+/// assert_eq!(std::mem::size_of_val(&maxset), 4);
+///
+/// let mut clock = Clock::<_, MaxSetOf<u32>>::new();
+/// clock.add_range(&"A", 1, 10);
+/// assert!(clock.contains(&"A", 10));
+///
+/// // `u64::from(u32::MAX) + 1` doesn't fit in a `u32`
+/// assert!(!clock.add(&"A", 1 << 32));
+/// assert!(clock.try_add(&"A", 1 << 32).is_err());
+/// assert_eq!(clock.get(&"A").unwrap().frontier(), 10);
+/// ```
 #[derive(Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
-pub struct MaxSet {
+pub struct MaxSetOf<S: Seq> {
     // Highest event seen
-    max: u64,
+    max: S,
 }
 
-impl EventSet for MaxSet {
+impl<S: Seq> EventSet for MaxSetOf<S> {
     type EventIter = EventIter;
+    const KIND: &'static str = "VClock";
+    const MAX_SEQ: u64 = S::MAX;
 
     /// Returns a new `MaxSet` instance.
     fn new() -> Self {
-        MaxSet { max: 0 }
+        MaxSetOf { max: S::default() }
     }
 
     /// Generates the next event.
     ///
+    /// # Panics
+    /// Panics if the next event doesn't fit in `S`.
+    ///
     /// # Examples
     /// ```
     /// use threshold::*;
@@ -47,12 +78,14 @@ impl EventSet for MaxSet {
     /// assert_eq!(maxset.next_event(), 2);
     /// ```
     fn next_event(&mut self) -> u64 {
-        self.max += 1;
-        self.max
+        let max = self.max() + 1;
+        self.set_max(max);
+        max
     }
 
     /// Adds an event to the set.
-    /// Returns `true` if it's a new event.
+    /// Returns `true` if it's a new event. Events that don't fit in `S` are
+    /// not added, and so `false` is returned.
     ///
     /// # Examples
     /// ```
@@ -67,11 +100,12 @@ impl EventSet for MaxSet {
     /// assert!(maxset.is_event(10));
     /// ```
     fn add_event(&mut self, event: u64) -> bool {
-        if event <= self.max {
-            false
-        } else {
-            self.max = event;
-            true
+        match S::try_from_u64(event) {
+            Some(event) if event > self.max => {
+                self.max = event;
+                true
+            }
+            _ => false,
         }
     }
 
@@ -79,10 +113,95 @@ impl EventSet for MaxSet {
     /// Returns `true` if a new event was added.
     ///
     /// In the case of `MaxSet` we have that:
-    /// - `add_event_range(start, end) == add_event(end)`
+    /// - `add_event_range(start, end) == add_event(end)`, if `start <= end`
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut maxset = MaxSet::new();
+    /// assert!(!maxset.add_event_range(10, 5));
+    /// assert_eq!(maxset.frontier(), 0);
+    ///
+    /// assert!(maxset.add_event_range(5, 10));
+    /// assert_eq!(maxset.frontier(), 10);
+    /// ```
     fn add_event_range(&mut self, start: u64, end: u64) -> bool {
-        debug_assert!(start <= end);
-        self.add_event(end)
+        if start > end {
+            // empty range
+            false
+        } else {
+            self.add_event(end)
+        }
+    }
+
+    /// Removes an event from the set.
+    /// Returns `true` if the event was part of the set.
+    ///
+    /// Since a `MaxSet` can only represent contiguous events, all events
+    /// higher than the removed event are also removed.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut maxset = MaxSet::from(10);
+    /// assert!(maxset.remove_event(10));
+    /// assert_eq!(maxset.frontier(), 9);
+    ///
+    /// assert!(maxset.remove_event(5));
+    /// assert_eq!(maxset.frontier(), 4);
+    /// assert!(!maxset.remove_event(5));
+    /// ```
+    fn remove_event(&mut self, event: u64) -> bool {
+        if event == 0 || event > self.max() {
+            false
+        } else {
+            self.set_max(event - 1);
+            true
+        }
+    }
+
+    /// Removes a range of events from the set.
+    /// Returns `true` if some event in the range was part of the set.
+    ///
+    /// Since a `MaxSet` can only represent contiguous events, all events
+    /// higher than the start of the range are also removed.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut maxset = MaxSet::from(10);
+    /// assert!(maxset.remove_event_range(5, 7));
+    /// assert_eq!(maxset.frontier(), 4);
+    /// assert!(!maxset.remove_event_range(5, 7));
+    /// ```
+    fn remove_event_range(&mut self, start: u64, end: u64) -> bool {
+        let start = cmp::max(start, 1);
+        if start > end || start > self.max() {
+            false
+        } else {
+            self.set_max(start - 1);
+            true
+        }
+    }
+
+    /// Removes all events higher than `max_event` from the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut maxset = MaxSet::from_event(10);
+    /// maxset.truncate(5);
+    /// assert_eq!(maxset.frontier(), 5);
+    ///
+    /// maxset.truncate(8);
+    /// assert_eq!(maxset.frontier(), 5);
+    /// ```
+    fn truncate(&mut self, max_event: u64) {
+        self.set_max(cmp::min(self.max(), max_event));
     }
 
     /// Checks if an event is part of the set.
@@ -96,7 +215,7 @@ impl EventSet for MaxSet {
     /// assert!(maxset.is_event(event));
     /// ```
     fn is_event(&self, event: u64) -> bool {
-        event <= self.max
+        event <= self.max()
     }
 
     /// Returns all events seen.
@@ -111,7 +230,7 @@ impl EventSet for MaxSet {
     /// assert_eq!(maxset.events(), (4, vec![]));
     /// ```
     fn events(&self) -> (u64, Vec<u64>) {
-        (self.max, vec![])
+        (self.max(), vec![])
     }
 
     /// Returns the frontier (the highest contiguous event seen).
@@ -143,7 +262,12 @@ impl EventSet for MaxSet {
     /// assert_eq!(maxset.frontier(), 6);
     /// ```
     fn frontier(&self) -> u64 {
-        self.max
+        self.max()
+    }
+
+    /// Returns the highest event in the set.
+    fn max_event(&self) -> u64 {
+        self.max()
     }
 
     /// Merges `other` `MaxSet` into `self`.
@@ -162,6 +286,16 @@ impl EventSet for MaxSet {
         self.max = std::cmp::max(self.max, other.max);
     }
 
+    /// Merges `other` `MaxSet` into `self`, returning `true` if its highest
+    /// event is higher.
+    fn join_mut(&mut self, other: &Self) -> bool {
+        let new = other.max > self.max;
+        if new {
+            self.max = other.max;
+        }
+        new
+    }
+
     /// Intersects `other` `MaxSet` with `self`.
     ///
     /// # Examples
@@ -179,13 +313,86 @@ impl EventSet for MaxSet {
     }
 
     fn subtracted(&self, other: &Self) -> Vec<u64> {
-        if self.max > other.max {
-            ((other.max + 1)..=self.max).collect()
+        if self.max() > other.max() {
+            ((other.max() + 1)..=self.max()).collect()
         } else {
             Vec::new()
         }
     }
 
+    /// Returns the number of events that remain when `other` is subtracted
+    /// from `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let maxset = MaxSet::from_event(10);
+    /// assert_eq!(maxset.subtracted_count(&MaxSet::from_event(4)), 6);
+    /// assert_eq!(maxset.subtracted_count(&MaxSet::from_event(20)), 0);
+    /// ```
+    fn subtracted_count(&self, other: &Self) -> u64 {
+        self.max().saturating_sub(other.max())
+    }
+
+    /// Checks if all events in `self` are also in `other`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// assert!(MaxSet::from(3).leq(&MaxSet::from(5)));
+    /// assert!(!MaxSet::from(5).leq(&MaxSet::from(3)));
+    /// ```
+    fn leq(&self, other: &Self) -> bool {
+        self.max <= other.max
+    }
+
+    /// Returns the number of events in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// assert_eq!(MaxSet::from_event(10).event_count(), 10);
+    /// ```
+    fn event_count(&self) -> u64 {
+        self.max()
+    }
+
+    /// Returns the number of extras the set stores, which is always 0.
+    fn stored_extras(&self) -> usize {
+        0
+    }
+
+    /// Returns the number of events in the set that are within `[start, end]`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let maxset = MaxSet::from(10);
+    /// assert_eq!(maxset.count_events_in_range(5, 20), 6);
+    /// assert_eq!(maxset.count_events_in_range(11, 20), 0);
+    /// ```
+    fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        super::count_up_to(start, end, self.max())
+    }
+
+    /// Checks if all events within `[start, end]` are in the set.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let maxset = MaxSet::from_event(10);
+    /// assert!(maxset.contains_all_range(5, 10));
+    /// assert!(!maxset.contains_all_range(5, 11));
+    /// ```
+    fn contains_all_range(&self, start: u64, end: u64) -> bool {
+        start > end || end <= self.max()
+    }
+
     /// Returns a `MaxSet` event iterator with all events from lowest to
     /// highest.
     ///
@@ -205,15 +412,57 @@ impl EventSet for MaxSet {
     fn event_iter(self) -> Self::EventIter {
         EventIter {
             current: 0,
-            max: self.max,
+            max: self.max(),
         }
     }
+
+    /// Returns a `MaxSet` event iterator with all events from lowest to
+    /// highest. Since a `MaxSet` doesn't own any allocation, this is the same
+    /// as `MaxSet::event_iter`.
+    #[allow(refining_impl_trait)]
+    fn iter(&self) -> EventIter {
+        EventIter {
+            current: 0,
+            max: self.max(),
+        }
+    }
+
+    /// Returns the events in the `MaxSet` as a single range (or none, if the
+    /// set is empty).
+    fn range_iter(self) -> impl Iterator<Item = (u64, u64)> {
+        Some((1, self.max())).filter(|_| self.max() > 0).into_iter()
+    }
 }
 
-impl MaxSet {
+impl<S: Seq> MaxSetOf<S> {
     /// Creates a `MaxSet` from the highest event.
+    ///
+    /// # Panics
+    /// Panics if `max` doesn't fit in `S`.
     pub fn from(max: u64) -> Self {
-        Self { max }
+        Self {
+            max: S::from_u64(max),
+        }
+    }
+
+    /// Returns the highest event. This accessor doesn't allocate, and it's
+    /// stable for FFI wrapping.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let maxset = MaxSet::from_events(vec![3, 10]);
+    /// assert_eq!(maxset.max(), 10);
+    /// ```
+    #[inline]
+    pub fn max(&self) -> u64 {
+        self.max.into()
+    }
+
+    // Sets the highest event.
+    fn set_max(&mut self, max: u64) {
+        self.max = S::from_u64(max);
     }
 }
 
@@ -237,10 +486,53 @@ impl Iterator for EventIter {
             Some(self.current)
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.max - self.current) as usize;
+        (len, Some(len))
+    }
 }
 
-impl fmt::Debug for MaxSet {
+impl ExactSizeIterator for EventIter {}
+
+impl DoubleEndedIterator for EventIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current == self.max {
+            // we've reached the end of the iterator
+            None
+        } else {
+            // return the last value, and move it back
+            self.max -= 1;
+            Some(self.max + 1)
+        }
+    }
+}
+
+impl<S: Seq> PartialOrd for MaxSetOf<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        crate::traits::partial_cmp(self, other)
+    }
+}
+
+impl<S: Seq> fmt::Debug for MaxSetOf<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.max())
+    }
+}
+
+impl<S: Seq> fmt::Display for MaxSetOf<S> {
+    /// Formats the events as a range, e.g. `1..5`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// assert_eq!(MaxSet::from(5).to_string(), "1..5");
+    /// assert_eq!(MaxSet::from(1).to_string(), "1");
+    /// assert_eq!(MaxSet::new().to_string(), "");
+    /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.max)
+        let range = Some((1, self.max())).filter(|(_, max)| *max > 0);
+        super::fmt_ranges(f, range)
     }
 }
@@ -1,10 +1,213 @@
+use std::fmt;
+
 mod above_ex;
 mod above_range;
+mod auto;
 mod below_ex;
+mod below_range;
+mod explicit;
 mod max;
 
 // Re-exports.
 pub use above_ex::AboveExSet;
 pub use above_range::AboveRangeSet;
+pub use auto::{choose_representation, Representation};
 pub use below_ex::BelowExSet;
-pub use max::MaxSet;
+pub use below_range::BelowRangeSet;
+pub use explicit::ExplicitSet;
+pub use max::{MaxSet, MaxSetOf};
+
+/// Writes the (sorted) items of an event set as `{a, b, c}`.
+/// If the formatter has a precision (e.g. `{:.2?}`), at most that many items
+/// are written, followed by a `… (+N more)` suffix.
+fn fmt_items<T: fmt::Display>(
+    f: &mut fmt::Formatter<'_>,
+    items: &[T],
+) -> fmt::Result {
+    let limit = f.precision().unwrap_or(items.len());
+    write!(f, "{{")?;
+    for (i, item) in items.iter().take(limit).enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}", item)?;
+    }
+    if items.len() > limit {
+        if limit > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "… (+{} more)", items.len() - limit)?;
+    }
+    write!(f, "}}")
+}
+
+/// Writes sorted ranges of events (both ends included) as `1..3,5,7..8`: a
+/// range with a single event is written as that event.
+fn fmt_ranges<I: IntoIterator<Item = (u64, u64)>>(
+    f: &mut fmt::Formatter<'_>,
+    ranges: I,
+) -> fmt::Result {
+    for (i, (start, end)) in ranges.into_iter().enumerate() {
+        if i > 0 {
+            write!(f, ",")?;
+        }
+        if start == end {
+            write!(f, "{}", start)?;
+        } else {
+            write!(f, "{}..{}", start, end)?;
+        }
+    }
+    Ok(())
+}
+
+/// Merges sorted (by start) ranges that overlap or are adjacent.
+pub(crate) fn coalesce<I: IntoIterator<Item = (u64, u64)>>(
+    ranges: I,
+) -> Vec<(u64, u64)> {
+    let mut coalesced: Vec<(u64, u64)> = Vec::new();
+    for (start, end) in ranges {
+        match coalesced.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = std::cmp::max(*last_end, end);
+            }
+            _ => coalesced.push((start, end)),
+        }
+    }
+    coalesced
+}
+
+/// Returns the events in the `intervals` that are not in the `other` intervals,
+/// as a list of intervals. Both lists should be sorted and disjoint, with both
+/// ends of each interval included.
+pub(crate) fn subtract_intervals(
+    intervals: &[(u64, u64)],
+    other: &[(u64, u64)],
+) -> Vec<(u64, u64)> {
+    let mut subtracted = Vec::new();
+
+    // walk both (sorted) lists of intervals, clipping each interval in
+    // `intervals` against the intervals in `other`
+    let mut j = 0;
+    for &(start, end) in intervals {
+        let mut current = start;
+
+        // skip the intervals in `other` that end before `current`
+        while j < other.len() && other[j].1 < current {
+            j += 1;
+        }
+
+        while j < other.len() && other[j].0 <= end {
+            let (other_start, other_end) = other[j];
+            if other_start > current {
+                subtracted.push((current, other_start - 1));
+            }
+            current = other_end + 1;
+            if current > end {
+                // the next interval in `intervals` may still overlap with
+                // this interval in `other`, so don't skip it
+                break;
+            }
+            j += 1;
+        }
+
+        if current <= end {
+            subtracted.push((current, end));
+        }
+    }
+    subtracted
+}
+
+/// Returns the events that are both in the `intervals` and in the `other`
+/// intervals, as a list of intervals. Both lists should be sorted and disjoint,
+/// with both ends of each interval included.
+pub(crate) fn intersect_intervals(
+    intervals: &[(u64, u64)],
+    other: &[(u64, u64)],
+) -> Vec<(u64, u64)> {
+    let mut intersected = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < intervals.len() && j < other.len() {
+        let start = std::cmp::max(intervals[i].0, other[j].0);
+        let end = std::cmp::min(intervals[i].1, other[j].1);
+        if start <= end {
+            intersected.push((start, end));
+        }
+        // move past the interval that ends first
+        if intervals[i].1 < other[j].1 {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    intersected
+}
+
+/// Returns the number of events in `[start, end]` that are also in `[1, max]`.
+fn count_up_to(start: u64, end: u64, max: u64) -> u64 {
+    let start = std::cmp::max(start, 1);
+    let end = std::cmp::min(end, max);
+    if start <= end {
+        end - start + 1
+    } else {
+        0
+    }
+}
+
+/// Copies `len` items into `buf`, sorted ASC, and returns `len`.
+/// If `buf` is too small, nothing is copied (and `len`, the required length,
+/// is still returned).
+fn copy_sorted_into<T: Ord, I: Iterator<Item = T>>(
+    items: I,
+    len: usize,
+    buf: &mut [T],
+) -> usize {
+    if buf.len() >= len {
+        for (slot, item) in buf.iter_mut().zip(items) {
+            *slot = item;
+        }
+        buf[..len].sort_unstable();
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn ffi_accessors() {
+        let maxset = MaxSet::from(10);
+        assert_eq!(maxset.max(), 10);
+
+        let above_exset = AboveExSet::from(2, vec![7, 4, 5]);
+        assert_eq!(above_exset.max(), 2);
+        assert_eq!(above_exset.extras_len(), 3);
+        let mut small = [0; 2];
+        assert_eq!(above_exset.copy_extras_into(&mut small), 3);
+        assert_eq!(small, [0, 0]);
+        let mut buf = [0; 4];
+        assert_eq!(above_exset.copy_extras_into(&mut buf), 3);
+        assert_eq!(buf, [4, 5, 7, 0]);
+
+        let below_exset = BelowExSet::from(10, vec![8, 1, 3]);
+        assert_eq!(below_exset.max(), 10);
+        assert_eq!(below_exset.exceptions_len(), 3);
+        let mut small = [0; 1];
+        assert_eq!(below_exset.copy_exceptions_into(&mut small), 3);
+        assert_eq!(small, [0]);
+        let mut buf = [0; 3];
+        assert_eq!(below_exset.copy_exceptions_into(&mut buf), 3);
+        assert_eq!(buf, [1, 3, 8]);
+
+        let mut above_range_set = AboveRangeSet::from_event_range(1, 2);
+        above_range_set.add_event_range(8, 9);
+        above_range_set.add_event_range(4, 5);
+        assert_eq!(above_range_set.max(), 2);
+        assert_eq!(above_range_set.ranges_len(), 2);
+        let mut empty = [];
+        assert_eq!(above_range_set.copy_ranges_into(&mut empty), 2);
+        let mut buf = [(0, 0); 2];
+        assert_eq!(above_range_set.copy_ranges_into(&mut buf), 2);
+        assert_eq!(buf, [(4, 5), (8, 9)]);
+    }
+}
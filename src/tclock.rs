@@ -20,16 +20,39 @@
 //! assert_eq!(tclock.threshold_union(2), (vclock_t2, false));
 //! ```
 
+use crate::hash::HashMap;
 use crate::*;
-use std::collections::HashMap;
 use std::marker::PhantomData;
 
 type EventCount = (u64, u64);
 
+// Votes of a clock entry: a count per event.
+type Votes = Vec<(u64, EventCount)>;
+
+/// A report that can be added to a `TClock`.
+///
+/// Each report is decomposed into votes: a positive vote at the highest event
+/// (which, with prefix semantics, also counts as a positive vote for all the
+/// events below it), and a negative vote at each event below the highest
+/// event that is known to be missing.
 #[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Report<A: Actor, E: EventSet> {
+    /// A report with frontiers only: a positive vote at each frontier,
+    /// meaning that all events up to the frontier were seen.
+    Frontier(VClock<A>),
+    /// An exact report: votes are computed from `EventSet::events`, as in
+    /// `TClock::add`. For a `BEClock`, this is a positive vote at the highest
+    /// event and a negative vote at each exception.
+    Exact(Clock<A, E>),
+}
+
+#[derive(Debug, Clone)]
 pub struct TClock<A: Actor, E: EventSet> {
     /// A `MultiSet` per `Actor`
     occurrences: HashMap<A, MultiSet<u64, EventCount>>,
+    /// Votes of each clock entry added with a timestamp (so that they can
+    /// later expire), along with the timestamp and the actor
+    timestamped: Vec<(u64, A, Votes)>,
     phantom: PhantomData<E>,
 }
 
@@ -38,7 +61,8 @@ impl<A: Actor, E: EventSet> TClock<A, E> {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         TClock {
-            occurrences: HashMap::new(),
+            occurrences: HashMap::default(),
+            timestamped: Vec::new(),
             phantom: PhantomData,
         }
     }
@@ -46,7 +70,11 @@ impl<A: Actor, E: EventSet> TClock<A, E> {
     /// Returns a new `TClock` instance with a given capacity.
     pub fn with_capacitiy(capacity: usize) -> Self {
         TClock {
-            occurrences: HashMap::with_capacity(capacity),
+            occurrences: HashMap::with_capacity_and_hasher(
+                capacity,
+                Default::default(),
+            ),
+            timestamped: Vec::new(),
             phantom: PhantomData,
         }
     }
@@ -68,17 +96,180 @@ impl<A: Actor, E: EventSet> TClock<A, E> {
         }
     }
 
-    /// Adds a single clock entry to the `TClock`.
-    fn add_entry(&mut self, actor: A, eset: E) {
-        // compute event count
+    /// Add a `Clock` to the `TClock`, returning whether it contributed an
+    /// observation that could raise a threshold.
+    ///
+    /// This is the case iff some entry of the clock has a highest event other
+    /// than 0: its positive vote increases the count of that event, and so
+    /// `TClock::threshold_union` may now return a higher event for some
+    /// threshold. Note that this doesn't mean that the result changed for
+    /// any particular threshold: in particular, re-adding a clock that was
+    /// already added returns `true`, since counts still increase. Entries
+    /// with no events (and an empty clock) don't contribute, and so `false`
+    /// is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let mut tclock = TClock::new();
+    /// assert!(tclock.add_observe(clock::vclock_from_seqs(vec![10, 5])));
+    ///
+    /// // re-adding the same clock increases counts
+    /// assert!(tclock.add_observe(clock::vclock_from_seqs(vec![10, 5])));
+    ///
+    /// // a clock with no events doesn't
+    /// assert!(!tclock.add_observe(clock::vclock_from_seqs(vec![0, 0])));
+    /// assert!(!tclock.add_observe(VClock::new()));
+    /// ```
+    pub fn add_observe(&mut self, clock: Clock<A, E>) -> bool {
+        let mut observed = false;
+        for (actor, eset) in clock {
+            observed |= self.add_entry(actor, eset);
+        }
+        observed
+    }
+
+    /// Adds a single clock entry to the `TClock`, returning whether it has a
+    /// positive vote at an event other than 0.
+    fn add_entry(&mut self, actor: A, eset: E) -> bool {
+        // compute event count; the positive vote comes first
         let count = event_count(eset);
+        let observed = count.first().is_some_and(|&(seq, _)| seq > 0);
+        self.add_votes(actor, count);
+        observed
+    }
+
+    /// Adds the votes of a single clock entry to the `TClock`.
+    fn add_votes<I>(&mut self, actor: A, count: I)
+    where
+        I: IntoIterator<Item = (u64, EventCount)>,
+    {
         // get current multi set for this actor
         let mset = self.occurrences.entry(actor).or_insert_with(MultiSet::new);
         // add new events
         mset.add(count);
     }
+
+    /// Add a `Report` to the `TClock`. This allows reports with frontiers
+    /// only and exact reports to be combined in the same `TClock`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// // this peer only reports its frontier: it has seen events 1 to 6
+    /// let frontier = VClock::from(vec![("A", MaxSet::from(6))]);
+    ///
+    /// // this peer has seen events 1 to 7, except event 5
+    /// let exact = BEClock::from(vec![("A", BelowExSet::from(7, vec![5]))]);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add_report(Report::Frontier(frontier));
+    /// tclock.add_report(Report::Exact(exact));
+    ///
+    /// // events 1 to 4 and event 6 were seen by both peers
+    /// let expected = BEClock::from(vec![("A", BelowExSet::from(6, vec![5]))]);
+    /// assert_eq!(tclock.threshold_union(2), expected);
+    ///
+    /// // event 7 was seen by only one of them
+    /// let expected = BEClock::from(vec![("A", BelowExSet::from(7, vec![]))]);
+    /// assert_eq!(tclock.threshold_union(1), expected);
+    /// ```
+    pub fn add_report(&mut self, report: Report<A, E>) {
+        match report {
+            Report::Frontier(vclock) => {
+                for (actor, eset) in vclock {
+                    self.add_votes(actor, event_count(eset));
+                }
+            }
+            Report::Exact(clock) => self.add(clock),
+        }
+    }
+
+    /// Add a `Clock` to the `TClock`, recording the `timestamp` at which it was
+    /// reported. Timestamps are opaque to the `TClock`: they are only compared
+    /// against the cutoff passed to `TClock::expire_older_than`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add_at(clock::vclock_from_seqs(vec![10, 5]), 1);
+    /// tclock.add_at(clock::vclock_from_seqs(vec![8, 6]), 2);
+    ///
+    /// let expected = clock::vclock_from_seqs(vec![8, 5]);
+    /// assert_eq!(tclock.threshold_union(2), (expected, false));
+    ///
+    /// tclock.expire_older_than(1);
+    /// let expected = clock::vclock_from_seqs(vec![8, 6]);
+    /// assert_eq!(tclock.threshold_union(1), (expected, true));
+    /// ```
+    pub fn add_at(&mut self, clock: Clock<A, E>, timestamp: u64) {
+        for (actor, eset) in clock {
+            let votes = event_count(eset);
+            self.add_votes(actor.clone(), votes.iter().cloned());
+            self.timestamped.push((timestamp, actor, votes));
+        }
+    }
+
+    /// Retracts all `Clock`s added with `TClock::add_at` with a timestamp
+    /// lower or equal than `cutoff`. Clocks added with `TClock::add` never
+    /// expire.
+    pub fn expire_older_than(&mut self, cutoff: u64) {
+        let (expired, timestamped) = self
+            .timestamped
+            .drain(..)
+            .partition(|(timestamp, ..)| *timestamp <= cutoff);
+        self.timestamped = timestamped;
+
+        for (_, actor, votes) in expired {
+            self.remove_votes(actor, votes);
+        }
+    }
+
+    /// Computes `TClock::gc_watermark`: for each actor in both the `TClock`
+    /// and `own`, the minimum between the frontier of `own` and the frontier
+    /// of the threshold-union, computed by `frontier` in a single pass over
+    /// the actor's occurrences.
+    fn watermark<F>(&self, own: &Clock<A, E>, frontier: F) -> VClock<A>
+    where
+        F: Fn(&MultiSet<u64, EventCount>) -> u64,
+    {
+        let iter = self.occurrences.iter().filter_map(|(actor, tset)| {
+            let own_frontier = own.get(actor)?.frontier();
+            let watermark = frontier(tset).min(own_frontier);
+            Some((actor.clone(), MaxSet::from_event(watermark)))
+        });
+        VClock::from(iter)
+    }
+
+    /// Removes the votes of a single clock entry from the `TClock`.
+    fn remove_votes(&mut self, actor: A, votes: Votes) {
+        if let Some(mset) = self.occurrences.get_mut(&actor) {
+            // remove events
+            for (seq, count) in votes {
+                mset.remove_elem(seq, count);
+            }
+            // if there are no more events for this actor, forget it
+            if mset.is_empty() {
+                self.occurrences.remove(&actor);
+            }
+        }
+    }
+}
+
+/// `TClock`s are equal if they have the same votes. The timestamps of the
+/// clocks added with `TClock::add_at` are not compared.
+impl<A: Actor, E: EventSet> PartialEq for TClock<A, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.occurrences == other.occurrences
+    }
 }
 
+impl<A: Actor, E: EventSet> Eq for TClock<A, E> {}
+
 impl<A: Actor> TClock<A, MaxSet> {
     /// Computes the [threshold-union](https://vitorenes.org/post/2018/11/threshold-union/)
     /// of all `VClock` added to the `TClock`.
@@ -147,7 +338,7 @@ impl<A: Actor> TClock<A, MaxSet> {
                 .map_or(0, |(&seq, _)| seq);
 
             // get highest sequence for this actor
-            let highest = tset.iter().rev().next().map_or(0, |(&seq, _)| seq);
+            let highest = tset.iter().next_back().map_or(0, |(&seq, _)| seq);
             // check if equal to union for this process
             equal_to_union = equal_to_union && highest == seq;
 
@@ -158,6 +349,68 @@ impl<A: Actor> TClock<A, MaxSet> {
         (VClock::from(iter), equal_to_union)
     }
 
+    /// Returns, for each actor, the highest event below which all events are
+    /// safe to garbage-collect: the minimum between the event up to which all
+    /// events were seen at least `threshold` times (as in the
+    /// [threshold-union](https://vitorenes.org/post/2018/11/threshold-union/))
+    /// and the frontier of `own`.
+    ///
+    /// Actors that are not in both the `TClock` and `own` are skipped (i.e.
+    /// they are not part of the result). The watermark is computed in a single
+    /// pass over each actor's occurrences.
+    ///
+    /// The watermark is for garbage-collecting what is kept alongside `own`
+    /// (e.g. a message log), or the events themselves with
+    /// `Clock::truncate_to_gc`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(clock::vclock_from_seqs(vec![10, 5, 5]));
+    /// tclock.add(clock::vclock_from_seqs(vec![8, 10, 6]));
+    ///
+    /// let own = clock::vclock_from_seqs(vec![9, 9]);
+    /// let watermark = tclock.gc_watermark(&own, 2);
+    /// assert_eq!(watermark, clock::vclock_from_seqs(vec![8, 5]));
+    /// ```
+    pub fn gc_watermark(&self, own: &VClock<A>, threshold: u64) -> VClock<A> {
+        self.watermark(own, |tset| prefix_frontier(tset, threshold))
+    }
+
+    /// Checks if the [threshold-union](https://vitorenes.org/post/2018/11/threshold-union/)
+    /// has settled at `threshold`, i.e. if for every actor that has reached
+    /// `threshold`, the threshold-union at `threshold` is the same as the
+    /// threshold-union at `threshold + 1`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let vclock_0 = clock::vclock_from_seqs(vec![10, 5]);
+    /// let vclock_1 = clock::vclock_from_seqs(vec![8, 5]);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(vclock_0);
+    /// tclock.add(vclock_1.clone());
+    /// assert!(!tclock.is_threshold_stable(1));
+    /// assert!(!tclock.is_threshold_stable(2));
+    ///
+    /// tclock.add(vclock_1);
+    /// assert!(!tclock.is_threshold_stable(1));
+    /// assert!(tclock.is_threshold_stable(2));
+    /// ```
+    pub fn is_threshold_stable(&self, threshold: u64) -> bool {
+        let (clock, _) = self.threshold_union(threshold);
+        let (next_clock, _) = self.threshold_union(threshold + 1);
+        clock
+            .iter()
+            // only consider actors that have reached the threshold
+            .filter(|(_, eset)| eset.frontier() > 0)
+            .all(|(actor, eset)| next_clock.get(actor) == Some(eset))
+    }
+
     /// Computes the union of all `VClock` added to the `TClock`.
     /// A boolean is also returned indicating whether all `VClock` added are
     /// equal.
@@ -206,6 +459,99 @@ impl<A: Actor> TClock<A, MaxSet> {
 
         (VClock::from(iter), all_equal)
     }
+
+    /// Prepares the `TClock` for repeated threshold queries: the cumulative
+    /// number of observations of each sequence is computed once, so that
+    /// `PreparedTClock::threshold_union` doesn't have to go through all
+    /// sequences on each call.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(clock::vclock_from_seqs(vec![10, 5, 5]));
+    /// tclock.add(clock::vclock_from_seqs(vec![8, 10, 6]));
+    ///
+    /// let prepared = tclock.prepare();
+    /// for threshold in 0..4 {
+    ///     assert_eq!(
+    ///         prepared.threshold_union(threshold),
+    ///         tclock.threshold_union(threshold)
+    ///     );
+    /// }
+    /// ```
+    pub fn prepare(&self) -> PreparedTClock<A> {
+        let occurrences = self
+            .occurrences
+            .iter()
+            .map(|(actor, tset)| {
+                let mut total_positives = 0;
+                let cumulative = tset
+                    .iter()
+                    .rev()
+                    .map(|(&seq, &(positives, _))| {
+                        total_positives += positives;
+                        (seq, total_positives)
+                    })
+                    .collect();
+                (actor.clone(), cumulative)
+            })
+            .collect();
+        PreparedTClock { occurrences }
+    }
+}
+
+/// A `TClock` of `VClock` prepared for repeated threshold queries (see
+/// `TClock::prepare`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedTClock<A: Actor> {
+    /// Per `Actor`, the sequences observed from the highest to the lowest,
+    /// along with the (cumulative) number of observations of each sequence,
+    /// i.e. the observations of all sequences higher or equal to it
+    occurrences: HashMap<A, Vec<(u64, u64)>>,
+}
+
+impl<A: Actor> PreparedTClock<A> {
+    /// Computes the threshold-union of all `VClock` added to the `TClock`, as
+    /// in `TClock::threshold_union`. Each actor's sequence is found with a
+    /// binary search.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::{clock, *};
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(clock::vclock_from_seqs(vec![10, 5, 5]));
+    /// tclock.add(clock::vclock_from_seqs(vec![8, 10, 6]));
+    /// let prepared = tclock.prepare();
+    ///
+    /// let vclock_t1 = clock::vclock_from_seqs(vec![10, 10, 6]);
+    /// let vclock_t2 = clock::vclock_from_seqs(vec![8, 5, 5]);
+    /// assert_eq!(prepared.threshold_union(1), (vclock_t1, true));
+    /// assert_eq!(prepared.threshold_union(2), (vclock_t2, false));
+    /// ```
+    pub fn threshold_union(&self, threshold: u64) -> (VClock<A>, bool) {
+        let mut equal_to_union = true;
+
+        let iter = self.occurrences.iter().map(|(actor, cumulative)| {
+            // the cumulative number of observations is sorted ASC, so the
+            // highest sequence that passes the threshold is the first one
+            // whose observations reach it
+            let index = cumulative
+                .partition_point(|(_, observations)| *observations < threshold);
+            let seq = cumulative.get(index).map_or(0, |(seq, _)| *seq);
+
+            // check if equal to union for this process
+            let highest = cumulative.first().map_or(0, |(seq, _)| *seq);
+            equal_to_union = equal_to_union && highest == seq;
+
+            // compute vclock entry
+            (actor.clone(), MaxSet::from_event(seq))
+        });
+
+        (VClock::from(iter), equal_to_union)
+    }
 }
 
 impl<A: Actor> TClock<A, BelowExSet> {
@@ -236,114 +582,510 @@ impl<A: Actor> TClock<A, BelowExSet> {
     /// ```
     pub fn threshold_union(&self, threshold: u64) -> BEClock<A> {
         let iter = self.occurrences.iter().map(|(actor, tset)| {
-            let mut total_pos = 0;
+            let (below_exset, _) = below_threshold(tset, threshold);
+            (actor.clone(), below_exset)
+        });
 
-            // skip until some entry passes the threshold
-            let iter = tset
-                .iter()
-                .rev()
-                .skip_while(|(_, &(pos, _))| {
-                    // `total_pos` records the implicit number of observations:
-                    // since we are iterating from the highest event to the
-                    // lowest, and the observation of event X counts as an
-                    // observation of event Y when X > Y, we can simply
-                    // accumulate all observations in `total_pos` and stop the
-                    // `skip_while` once `total_pos` passes the threshold
-                    total_pos += pos;
-                    total_pos < threshold
-                })
-                // had to collect here so that the borrow of `total_pos` ends
-                // TODO can we avoid this?
-                .collect::<Vec<_>>();
-            let mut iter = iter.iter().peekable();
-
-            let highest = match iter.next() {
-                None => Ok(0),
-                Some((&seq, &(_, neg))) => {
-                    // check if the highest seq that passes the positive
-                    // threshold is valid, i.e. if it still passes the threshold
-                    // after subtracting the negative votes
-                    if total_pos - neg >= threshold {
-                        // if yes, this is the highest sequence
-                        Ok(seq)
-                    } else {
-                        // if not, the highest sequence may not have received
-                        // any of vote, i.e. it is not in the structure
-                        Err(seq)
-                    }
-                }
+        BEClock::from(iter)
+    }
+
+    /// Returns, for each actor, the highest event below which all events are
+    /// safe to garbage-collect (see `TClock::gc_watermark` for `VClock`s).
+    /// The watermark is computed in a single pass over each actor's
+    /// occurrences.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock_a = BEClock::new();
+    /// clock_a.add_range(&"A", 1, 6);
+    /// let mut clock_b = BEClock::new();
+    /// clock_b.add_range(&"A", 1, 3);
+    /// clock_b.add(&"A", 5);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(clock_a.clone());
+    /// tclock.add(clock_b);
+    ///
+    /// let watermark = tclock.gc_watermark(&clock_a, 2);
+    /// assert_eq!(watermark, VClock::from(vec![("A", MaxSet::from_event(3))]));
+    /// ```
+    pub fn gc_watermark(&self, own: &BEClock<A>, threshold: u64) -> VClock<A> {
+        self.watermark(own, |tset| prefix_frontier(tset, threshold))
+    }
+
+    /// Computes the threshold-union (see `TClock::threshold_union`), also
+    /// returning, per actor, the number of observations of the highest event
+    /// in the threshold-union (i.e. of it or of any higher event), and the
+    /// number of those that don't include it (i.e. that have it as an
+    /// exception).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let clock_a = BEClock::from(vec![("A", BelowExSet::from(6, vec![3]))]);
+    /// let clock_b = BEClock::from(vec![("A", BelowExSet::from(5, vec![]))]);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(clock_a);
+    /// tclock.add(clock_b);
+    ///
+    /// let (clock, support) = tclock.threshold_union_debug(2);
+    /// let expected = BEClock::from(vec![("A", BelowExSet::from(5, vec![3]))]);
+    /// assert_eq!(clock, expected);
+    /// assert_eq!(support[&"A"], (2, 0));
+    /// ```
+    pub fn threshold_union_debug(
+        &self,
+        threshold: u64,
+    ) -> (BEClock<A>, std::collections::HashMap<A, (u64, u64)>) {
+        let mut supports =
+            std::collections::HashMap::with_capacity(self.occurrences.len());
+        let iter = self.occurrences.iter().map(|(actor, tset)| {
+            let (below_exset, support) = below_threshold(tset, threshold);
+            supports.insert(actor.clone(), support);
+            (actor.clone(), below_exset)
+        });
+
+        let clock = BEClock::from(iter);
+        (clock, supports)
+    }
+
+    /// Computes the union of all `BEClock` added to the `TClock`, i.e., for
+    /// each actor, the events observed in at least one of the clocks added.
+    /// This is the same as the threshold-union with threshold 1.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let clock_a = BEClock::from(vec![("A", BelowExSet::from(5, vec![2, 3]))]);
+    /// let clock_b = BEClock::from(vec![("A", BelowExSet::from(4, vec![1, 2]))]);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(clock_a);
+    /// tclock.add(clock_b);
+    ///
+    /// let expected = BEClock::from(vec![("A", BelowExSet::from(5, vec![2]))]);
+    /// assert_eq!(tclock.union(), expected);
+    /// ```
+    pub fn union(&self) -> BEClock<A> {
+        self.threshold_union(1)
+    }
+}
+
+impl<A: Actor> TClock<A, AboveExSet> {
+    /// Computes the [threshold-union](https://vitorenes.org/post/2018/11/threshold-union/)
+    /// of all `AEClock` added to the `TClock`, i.e., for each actor, the
+    /// events observed in at least `threshold` of the clocks added.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 2, 4]))]);
+    /// let b = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 4, 5]))]);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(a);
+    /// tclock.add(b);
+    ///
+    /// let expected = AEClock::from(vec![("A", AboveExSet::from(1, vec![4]))]);
+    /// assert_eq!(tclock.threshold_union(2), expected);
+    ///
+    /// let expected = AEClock::from(vec![("A", AboveExSet::from(2, vec![4, 5]))]);
+    /// assert_eq!(tclock.threshold_union(1), expected);
+    /// ```
+    pub fn threshold_union(&self, threshold: u64) -> AEClock<A> {
+        let iter = self.occurrences.iter().map(|(actor, tset)| {
+            let (max, exs) = above_threshold(tset, threshold);
+
+            // extras right after the highest contiguous event are moved into
+            // it
+            let mut above_exset = AboveExSet::from(max, exs);
+            above_exset.compress();
+            (actor.clone(), above_exset)
+        });
+
+        AEClock::from(iter)
+    }
+
+    /// Returns, for each actor, the highest event below which all events are
+    /// safe to garbage-collect (see `TClock::gc_watermark` for `VClock`s).
+    /// The votes of extra events are not votes for the events below them, so
+    /// the watermark is bounded by the frontier of the threshold-union (which
+    /// is computed without computing the threshold-union itself).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 2, 4]))]);
+    /// let b = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 4, 5]))]);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(a.clone());
+    /// tclock.add(b);
+    ///
+    /// let watermark = tclock.gc_watermark(&a, 2);
+    /// assert_eq!(watermark, VClock::from(vec![("A", MaxSet::from_event(1))]));
+    /// ```
+    pub fn gc_watermark(&self, own: &AEClock<A>, threshold: u64) -> VClock<A> {
+        self.watermark(own, |tset| above_frontier(tset, threshold))
+    }
+}
+
+impl<A: Actor> TClock<A, AboveRangeSet> {
+    /// Computes the [threshold-union](https://vitorenes.org/post/2018/11/threshold-union/)
+    /// of all `ARClock` added to the `TClock`, i.e., for each actor, the
+    /// events observed in at least `threshold` of the clocks added, with
+    /// consecutive events coalesced into ranges.
+    ///
+    /// The votes of each `AboveRangeSet` added are counted per range (and not
+    /// per event), and so neither adding a wide range nor computing the
+    /// threshold-union enumerates its events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut a = AboveRangeSet::from_event_range(1, 10);
+    /// a.add_event_range(20, 30);
+    /// let mut b = AboveRangeSet::from_event_range(1, 5);
+    /// b.add_event_range(25, 40);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(ARClock::from(vec![("A", a)]));
+    /// tclock.add(ARClock::from(vec![("A", b)]));
+    ///
+    /// let mut expected = AboveRangeSet::from_event_range(1, 5);
+    /// expected.add_event_range(25, 30);
+    /// let expected = ARClock::from(vec![("A", expected)]);
+    /// assert_eq!(tclock.threshold_union(2), expected);
+    /// ```
+    pub fn threshold_union(&self, threshold: u64) -> ARClock<A> {
+        let iter = self.occurrences.iter().map(|(actor, tset)| {
+            let mut above_range_set = AboveRangeSet::new();
+            for (start, end) in range_threshold(tset, threshold) {
+                above_range_set.add_event_range(start, end);
+            }
+            (actor.clone(), above_range_set)
+        });
+
+        Clock::from(iter)
+    }
+
+    /// Returns, for each actor, the highest event below which all events are
+    /// safe to garbage-collect (see `TClock::gc_watermark` for `VClock`s).
+    /// As with `AEClock`s, the watermark is computed from the frontier of the
+    /// threshold-union.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut a = AboveRangeSet::from_event_range(1, 10);
+    /// a.add_event_range(20, 30);
+    /// let b = AboveRangeSet::from_event_range(1, 25);
+    /// let a = ARClock::from(vec![("A", a)]);
+    ///
+    /// let mut tclock = TClock::new();
+    /// tclock.add(a.clone());
+    /// tclock.add(ARClock::from(vec![("A", b)]));
+    ///
+    /// let watermark = tclock.gc_watermark(&a, 2);
+    /// assert_eq!(watermark, VClock::from(vec![("A", MaxSet::from_event(10))]));
+    /// ```
+    pub fn gc_watermark(&self, own: &ARClock<A>, threshold: u64) -> VClock<A> {
+        self.watermark(own, |tset| range_frontier(tset, threshold))
+    }
+}
+
+/// Computes the frontier of the threshold-union of a below-exception set (or
+/// of a `MaxSet`), i.e. the event up to which all events were observed at
+/// least `threshold` times.
+fn prefix_frontier(tset: &MultiSet<u64, EventCount>, threshold: u64) -> u64 {
+    // iterating from the highest event to the lowest, all events (from the
+    // event found to the next event in the multiset) were seen
+    // `total_positives` times, except the event itself, that is missing in
+    // `negatives` reports
+    let mut total_positives = 0;
+    let mut frontier = None;
+    for (&seq, &(positives, negatives)) in tset.iter().rev() {
+        total_positives += positives;
+        if total_positives < threshold {
+            continue;
+        }
+        let mut candidate = frontier.unwrap_or(seq);
+        // the `negatives > total_positives` is here just to prevent that
+        // `total_positives - negatives` overflows
+        if negatives > total_positives
+            || total_positives - negatives < threshold
+        {
+            candidate = seq - 1;
+        }
+        frontier = Some(candidate);
+    }
+    frontier.unwrap_or(0)
+}
+
+/// Computes the events of a below-exception set observed at least `threshold`
+/// times: returns them as a `BelowExSet`, along with the number of
+/// observations (and of negative votes) of its highest event.
+fn below_threshold(
+    tset: &MultiSet<u64, EventCount>,
+    threshold: u64,
+) -> (BelowExSet, (u64, u64)) {
+    let mut total_pos = 0;
+    // observations (and negative votes) of the highest event
+    let mut support = (0, 0);
+
+    // skip until some entry passes the threshold
+    let mut iter = tset.iter().rev().peekable();
+    while let Some((_, &(pos, _))) = iter.peek() {
+        // `total_pos` records the implicit number of observations:
+        // since we are iterating from the highest event to the
+        // lowest, and the observation of event X counts as an
+        // observation of event Y when X > Y, we can simply
+        // accumulate all observations in `total_pos` and stop
+        // skipping once `total_pos` passes the threshold
+        total_pos += pos;
+        if total_pos >= threshold {
+            break;
+        }
+        iter.next();
+    }
+
+    let highest = match iter.next() {
+        None => {
+            support = (total_pos, 0);
+            Ok(0)
+        }
+        Some((&seq, &(_, neg))) => {
+            // check if the highest seq that passes the positive
+            // threshold is valid, i.e. if it still passes the threshold
+            // after subtracting the negative votes
+            if total_pos - neg >= threshold {
+                // if yes, this is the highest sequence
+                support = (total_pos, neg);
+                Ok(seq)
+            } else {
+                // if not, the highest sequence may not have received
+                // any of vote, i.e. it is not in the structure
+                Err(seq)
             }
-            .unwrap_or_else(|seq| {
-                // if the highest `seq` that passed the positive threshold is
-                // not the highest sequence we are looking for, then any
-                // sequence smaller than `seq` could be the highest sequence
-                // (even if it's not part of our structure)
-                let mut candidate = seq - 1;
-                loop {
-                    match iter.peek() {
-                        None => {
-                            // if the structure is empty, then the current
-                            // candidate is the highest sequence
+        }
+    }
+    .unwrap_or_else(|seq| {
+        // if the highest `seq` that passed the positive threshold is
+        // not the highest sequence we are looking for, then any
+        // sequence smaller than `seq` could be the highest sequence
+        // (even if it's not part of our structure)
+        let mut candidate = seq - 1;
+        loop {
+            match iter.peek() {
+                None => {
+                    // if the structure is empty, then the current
+                    // candidate is the highest sequence
+                    support = (total_pos, 0);
+                    break candidate;
+                }
+                Some((&next_seq, &(pos, neg))) => {
+                    if next_seq == candidate {
+                        // if the `candidate` is in the structure
+                        // advance the iterator
+                        // - we can't always advance the iterator
+                        //   because the element we're peeking might be
+                        //   an exception, so we only advance when we're
+                        //   sure that it will never be an exception
+                        iter.next();
+
+                        // accumulate more positives
+                        total_pos += pos;
+
+                        if total_pos - neg >= threshold {
+                            // if `candidate` passes the threshold, then
+                            // we've found the highest sequence
+                            support = (total_pos, neg);
                             break candidate;
+                        } else {
+                            // otherwise, try a smaller sequence
+                            candidate -= 1;
                         }
-                        Some((&next_seq, &(pos, neg))) => {
-                            if next_seq == candidate {
-                                // if the `candidate` is in the structure
-                                // advance the iterator
-                                // - we can't always advance the iterator
-                                //   because the element we're peeking might be
-                                //   an exception, so we only advance when we're
-                                //   sure that it will never be an exception
-                                iter.next();
-
-                                // accumulate more positives
-                                total_pos += pos;
-
-                                if total_pos - neg >= threshold {
-                                    // if `candidate` passes the threshold, then
-                                    // we've found the highest sequence
-                                    break candidate;
-                                } else {
-                                    // otherwise, try a smaller sequence
-                                    candidate -= 1;
-                                }
-                            } else {
-                                // if the `candidate` is not in the structure,
-                                // then this `candidate` is the highest sequence
-                                break candidate;
-                            }
-                        }
+                    } else {
+                        // if the `candidate` is not in the structure,
+                        // then this `candidate` is the highest sequence
+                        support = (total_pos, 0);
+                        break candidate;
                     }
                 }
-            });
-
-            // compute exceptions:
-            // - if there are any exceptions, they are part of our structure
-            let exs = iter.filter_map(|(&seq, &(pos, neg))| {
-                // accumulate more positives
-                total_pos += pos;
-
-                // we have an exception when `total_pos - neg < threshold`
-                // - the `neg > total_pos` is here just to prevent that
-                // `total_pos - neg` overflows
-                if neg > total_pos || total_pos - neg < threshold {
-                    Some(seq)
-                } else {
-                    None
-                }
-            });
+            }
+        }
+    });
 
-            let below_exset = BelowExSet::from(highest, exs);
-            (actor.clone(), below_exset)
-        });
+    // compute exceptions:
+    // - if there are any exceptions, they are part of our structure
+    let exs = iter.filter_map(|(&seq, &(pos, neg))| {
+        // accumulate more positives
+        total_pos += pos;
 
-        BEClock::from(iter)
+        // we have an exception when `total_pos - neg < threshold`
+        // - the `neg > total_pos` is here just to prevent that
+        // `total_pos - neg` overflows
+        if neg > total_pos || total_pos - neg < threshold {
+            Some(seq)
+        } else {
+            None
+        }
+    });
+
+    let below_exset = BelowExSet::from(highest, exs);
+    (below_exset, support)
+}
+
+/// Computes the events of an above-exception (or above-range) set observed at
+/// least `threshold` times: returns the highest contiguous event and the
+/// (sorted) extra events.
+fn above_threshold(
+    tset: &MultiSet<u64, EventCount>,
+    threshold: u64,
+) -> (u64, Vec<u64>) {
+    // for above-exception sets, the votes at some event are:
+    // - positive, if it was observed as the highest contiguous event (and
+    //   thus all events below it were also observed)
+    // - "negative", if it was observed as an extra event (only this event was
+    //   observed)
+    let mut total_pos = 0;
+    let mut max = 0;
+    let mut exs = Vec::new();
+
+    // iterate from the highest event to the lowest, accumulating positives:
+    // once they reach the threshold, we've found the highest contiguous
+    // event; above it, only extras that reach the threshold (with their own
+    // observations) are events
+    for (&seq, &(pos, extra)) in tset.iter().rev() {
+        total_pos += pos;
+        if total_pos >= threshold {
+            max = seq;
+            break;
+        } else if total_pos + extra >= threshold {
+            exs.push(seq);
+        }
     }
+    exs.reverse();
+    (max, exs)
 }
 
-fn event_count<E: EventSet>(
-    eset: E,
-) -> impl Iterator<Item = (u64, EventCount)> {
+/// Computes the frontier of the threshold-union of an above-exception set,
+/// i.e. its highest contiguous event, moved up past the extras right above
+/// it.
+fn above_frontier(tset: &MultiSet<u64, EventCount>, threshold: u64) -> u64 {
+    // the votes are the same as in `above_threshold`; iterating from the
+    // highest event to the lowest, `extras` is the lowest run of contiguous
+    // extras found
+    let mut total_pos = 0;
+    let mut extras = None;
+    for (&seq, &(pos, extra)) in tset.iter().rev() {
+        total_pos += pos;
+        if total_pos >= threshold {
+            // this is the highest contiguous event
+            return match extras {
+                Some((start, end)) if start == seq + 1 => end,
+                _ => seq,
+            };
+        } else if total_pos + extra >= threshold {
+            extras = match extras {
+                Some((start, end)) if start == seq + 1 => Some((seq, end)),
+                _ => Some((seq, seq)),
+            };
+        }
+    }
+    // no event passed the threshold, and so the highest contiguous event is 0
+    match extras {
+        Some((1, end)) => end,
+        _ => 0,
+    }
+}
+
+/// Calls `f` with the ranges of events (both ends included) of an above-range
+/// set observed at least `threshold` times, from the highest to the lowest.
+/// Ranges are disjoint, but they may be adjacent.
+fn range_walk<F>(tset: &MultiSet<u64, EventCount>, threshold: u64, mut f: F)
+where
+    F: FnMut(u64, u64),
+{
+    // for above-range sets, the votes at some event are:
+    // - positive, if it was observed as the end of a range (and thus all
+    //   events below it, down to the start of the range, were also observed)
+    // - "start", if it was observed as the start of a range (and thus the
+    //   events below it were not observed as part of that range)
+    let mut total_pos = 0;
+
+    // iterate from the highest event to the lowest: an event was observed by
+    // the ranges that end at or above it, minus the ones that start above it
+    let mut iter = tset.iter().rev().peekable();
+    while let Some((&seq, &(pos, starts))) = iter.next() {
+        total_pos += pos;
+        if seq > 0 && total_pos >= threshold {
+            f(seq, seq);
+        }
+        // each start vote is paired with a positive vote at the end of its
+        // range, which was already accumulated
+        total_pos -= starts;
+
+        // the events between the next event in the multiset and this one
+        // were observed as many times as the highest event below this one
+        let next = iter.peek().map_or(0, |(&next, _)| next);
+        if next + 1 < seq && total_pos >= threshold {
+            f(next + 1, seq - 1);
+        }
+    }
+}
+
+/// Computes the events of an above-range set observed at least `threshold`
+/// times, as ranges (from lowest to highest, and not adjacent).
+fn range_threshold(
+    tset: &MultiSet<u64, EventCount>,
+    threshold: u64,
+) -> Vec<(u64, u64)> {
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    range_walk(tset, threshold, |start, end| match ranges.last_mut() {
+        // ranges are found from the highest to the lowest
+        Some(last) if last.0 == end + 1 => last.0 = start,
+        _ => ranges.push((start, end)),
+    });
+    ranges.reverse();
+    ranges
+}
+
+/// Computes the frontier of the threshold-union of an above-range set, i.e.
+/// the end of its lowest range, if that range starts at 1.
+fn range_frontier(tset: &MultiSet<u64, EventCount>, threshold: u64) -> u64 {
+    // ranges are found from the highest to the lowest, and so only the
+    // lowest run of adjacent ranges is kept
+    let mut lowest = None;
+    range_walk(tset, threshold, |start, end| {
+        lowest = match lowest {
+            Some((low, high)) if low == end + 1 => Some((start, high)),
+            _ => Some((start, end)),
+        };
+    });
+    match lowest {
+        Some((1, end)) => end,
+        _ => 0,
+    }
+}
+
+fn event_count<E: EventSet>(eset: E) -> Votes {
+    // the votes of an `AboveRangeSet` are counted per range, as expected by
+    // the threshold-union of `ARClock`s; event sets outside this crate have
+    // no threshold-union, and so their votes only have to be consistent
+    if E::KIND == AboveRangeSet::KIND {
+        return range_count(eset);
+    }
+
     // get events
     let (left, right) = eset.events();
 
@@ -354,11 +1096,32 @@ fn event_count<E: EventSet>(
     let right_count = right.into_iter().map(|x| (x, (0, 1)));
 
     // chain both
-    left_count.chain(right_count)
+    left_count.chain(right_count).collect()
 }
 
-#[cfg(test)]
+fn range_count<E: EventSet>(eset: E) -> Votes {
+    // the contiguous prefix (if any) is the range that starts at 1: its
+    // highest event gets a positive vote, as with `EventSet::events`
+    let mut ranges = eset.range_iter().peekable();
+    let frontier = ranges
+        .next_if(|(start, _)| *start == 1)
+        .map_or(0, |(_, end)| end);
+    let mut votes = vec![(frontier, (1, 0))];
 
+    // each other range gets a positive vote at its end and a start vote at
+    // its start
+    for (start, end) in ranges {
+        if start == end {
+            votes.push((end, (1, 1)));
+        } else {
+            votes.push((end, (1, 0)));
+            votes.push((start, (0, 1)));
+        }
+    }
+    votes
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -389,6 +1152,451 @@ mod tests {
         expected.add(&b, 5);
 
         assert_eq!(clock, expected);
+
+        // event 5 is not in the structure: both clocks observed it (through
+        // events 6 and 7), and none of them has it as an exception
+        let (debug_clock, support) = tclock.threshold_union_debug(2);
+        assert_eq!(debug_clock, expected);
+        assert_eq!(support[&b], (2, 0));
+
+        // event 7 is only observed by `clock_b`
+        let (debug_clock, support) = tclock.threshold_union_debug(1);
+        assert_eq!(debug_clock, tclock.threshold_union(1));
+        assert_eq!(support[&b], (1, 0));
+    }
+
+    #[test]
+    fn stress_test_beclock() {
+        use rand::prelude::*;
+        let mut rng = StdRng::seed_from_u64(42);
+        let actors = ["A", "B"];
+        let max_event = 2000;
+
+        // clocks with many (wide) exceptions
+        let clocks: Vec<BEClock<&str>> = (0..10)
+            .map(|_| {
+                let entries = actors.iter().map(|actor| {
+                    let events = (1..=max_event).filter(|_| rng.gen_bool(0.7));
+                    (*actor, BelowExSet::from_events(events))
+                });
+                BEClock::from(entries)
+            })
+            .collect();
+
+        let mut tclock = TClock::new();
+        for clock in clocks.iter() {
+            tclock.add(clock.clone());
+        }
+
+        for threshold in 1..=clocks.len() {
+            let clock = tclock.threshold_union(threshold as u64);
+            for actor in actors.iter() {
+                for event in 1..=max_event + 1 {
+                    // an event is in the threshold-union iff it's in at least
+                    // `threshold` clocks
+                    let count = clocks
+                        .iter()
+                        .filter(|clock| clock.contains(actor, event))
+                        .count();
+                    assert_eq!(
+                        clock.contains(actor, event),
+                        count >= threshold
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn regression_test_aeclock() {
+        let b = String::from("B");
+
+        // Clock { clock: {B: AboveExSet { max: 2, exs: {4, 6} }} }
+        let mut clock_a = AEClock::new();
+        clock_a.add_range(&b, 1, 2);
+        clock_a.add(&b, 4);
+        clock_a.add(&b, 6);
+
+        // Clock { clock: {B: AboveExSet { max: 1, exs: {3, 4, 7} }} }
+        let mut clock_b = AEClock::new();
+        clock_b.add(&b, 1);
+        clock_b.add_range(&b, 3, 4);
+        clock_b.add(&b, 7);
+
+        // Clock { clock: {B: AboveExSet { max: 0, exs: {4, 7} }} }
+        let mut clock_c = AEClock::new();
+        clock_c.add(&b, 4);
+        clock_c.add(&b, 7);
+
+        // add all clocks to the threshold clock
+        let mut tclock = TClock::new();
+        tclock.add(clock_a);
+        tclock.add(clock_b);
+        tclock.add(clock_c);
+
+        // compute the threshold unions and create the expected clocks
+        let expected = |events: Vec<u64>| {
+            AEClock::from(vec![(b.clone(), AboveExSet::from_events(events))])
+        };
+        assert_eq!(tclock.threshold_union(1), expected(vec![1, 2, 3, 4, 6, 7]));
+        assert_eq!(tclock.threshold_union(2), expected(vec![1, 4, 7]));
+        assert_eq!(tclock.threshold_union(3), expected(vec![4]));
+        assert_eq!(tclock.threshold_union(4), expected(vec![]));
+    }
+
+    #[test]
+    fn expire_older_than() {
+        let c1 = clock::vclock_from_seqs(vec![10, 5, 5]);
+        let c2 = clock::vclock_from_seqs(vec![8, 10, 6]);
+        let c3 = clock::vclock_from_seqs(vec![9, 8, 7]);
+
+        // add all clocks with increasing timestamps
+        let mut tclock = TClock::new();
+        tclock.add_at(c1, 1);
+        tclock.add_at(c2, 2);
+        tclock.add_at(c3.clone(), 3);
+
+        // expire the first two clocks
+        tclock.expire_older_than(2);
+
+        // create the expected threshold clock
+        let mut expected = TClock::new();
+        expected.add(c3);
+
+        for threshold in 1..=3 {
+            assert_eq!(
+                tclock.threshold_union(threshold),
+                expected.threshold_union(threshold)
+            );
+        }
+
+        // timestamps are not part of equality
+        assert_eq!(tclock, expected);
+
+        // expiring again changes nothing
+        tclock.expire_older_than(2);
+        for threshold in 1..=3 {
+            assert_eq!(
+                tclock.threshold_union(threshold),
+                expected.threshold_union(threshold)
+            );
+        }
+        assert_eq!(tclock.union(), expected.union());
+    }
+
+    #[test]
+    fn add_observe() {
+        let mut tclock = TClock::new();
+        let clock = clock::vclock_from_seqs(vec![10, 5, 0]);
+
+        // the first time, the clock is new information
+        assert!(tclock.add_observe(clock.clone()));
+        let (t2, _) = tclock.threshold_union(2);
+        assert_eq!(t2, clock::vclock_from_seqs(vec![0, 0, 0]));
+
+        // re-adding it still returns true, since counts increase (and indeed
+        // the threshold union at 2 is now higher)
+        assert!(tclock.add_observe(clock.clone()));
+        let (t2, _) = tclock.threshold_union(2);
+        assert_eq!(t2, clock);
+
+        // only the entry with no events doesn't contribute
+        assert!(!tclock.add_observe(clock::vclock_from_seqs(vec![0, 0, 0])));
+
+        // exceptions (negative votes) can't raise a threshold
+        let mut tclock = TClock::new();
+        let beclock = BEClock::from(vec![("A", BelowExSet::from(0, vec![]))]);
+        assert!(!tclock.add_observe(beclock));
+        let beclock = BEClock::from(vec![("A", BelowExSet::from(3, vec![1]))]);
+        assert!(tclock.add_observe(beclock));
+    }
+
+    #[test]
+    fn arclock_wide_ranges() {
+        // votes are counted per range, and so this is instant
+        let mut a = ARClock::new();
+        a.add_range(&"A", 1, 1 << 40);
+        a.add_range(&"A", 1 << 41, 1 << 42);
+        let mut b = ARClock::new();
+        b.add_range(&"A", 1 << 30, (1 << 41) + 5);
+
+        let mut tclock = TClock::new();
+        tclock.add(a);
+        tclock.add(b);
+        // votes at the frontiers (1 << 40 and 0), and at the ends and starts
+        // of the other ranges
+        assert_eq!(tclock.occurrences[&"A"].iter().count(), 6);
+
+        let mut expected = ARClock::new();
+        expected.add_range(&"A", 1, 1 << 42);
+        assert_eq!(tclock.threshold_union(1), expected);
+
+        let mut expected = ARClock::new();
+        expected.add_range(&"A", 1 << 30, 1 << 40);
+        expected.add_range(&"A", 1 << 41, (1 << 41) + 5);
+        assert_eq!(tclock.threshold_union(2), expected);
+    }
+
+    #[test]
+    fn custom_event_set() {
+        // an event set defined outside of the crate: a contiguous prefix
+        #[derive(Debug, Clone, Default, PartialEq)]
+        struct Prefix(u64);
+
+        impl EventSet for Prefix {
+            type EventIter = std::ops::RangeInclusive<u64>;
+
+            fn new() -> Self {
+                Prefix(0)
+            }
+
+            fn next_event(&mut self) -> u64 {
+                self.0 += 1;
+                self.0
+            }
+
+            fn add_event(&mut self, event: u64) -> bool {
+                let added = event == self.0 + 1;
+                if added {
+                    self.0 = event;
+                }
+                added
+            }
+
+            fn is_event(&self, event: u64) -> bool {
+                event >= 1 && event <= self.0
+            }
+
+            fn events(&self) -> (u64, Vec<u64>) {
+                (self.0, vec![])
+            }
+
+            fn frontier(&self) -> u64 {
+                self.0
+            }
+
+            fn join(&mut self, other: &Self) {
+                self.0 = std::cmp::max(self.0, other.0);
+            }
+
+            fn meet(&mut self, other: &Self) {
+                self.0 = std::cmp::min(self.0, other.0);
+            }
+
+            fn subtracted(&self, other: &Self) -> Vec<u64> {
+                (other.0 + 1..=self.0).collect()
+            }
+
+            fn event_iter(self) -> Self::EventIter {
+                1..=self.0
+            }
+        }
+
+        let clock = |seqs: Vec<u64>| -> Clock<usize, Prefix> {
+            Clock::from(seqs.into_iter().map(Prefix).enumerate())
+        };
+
+        let mut tclock = TClock::new();
+        tclock.add(clock(vec![10, 5]));
+        assert!(tclock.add_observe(clock(vec![8, 7])));
+        assert!(!tclock.add_observe(clock(vec![0, 0])));
+
+        // a clock added with a timestamp is forgotten once it expires
+        let before = tclock.clone();
+        tclock.add_at(clock(vec![3, 9]), 1);
+        assert_ne!(tclock, before);
+        tclock.expire_older_than(1);
+        assert_eq!(tclock, before);
+    }
+
+    #[test]
+    fn gc_loop() {
+        use std::collections::BTreeSet;
+
+        let replicas = 3;
+        let threshold = replicas as u64;
+        let frontier = |clock: &VClock<usize>, actor: &usize| {
+            clock.get(actor).map_or(0, |eset| eset.frontier())
+        };
+
+        // for each replica, the events delivered, and the log of messages
+        // received (kept, e.g. for retransmission, until garbage-collected)
+        let mut delivered = vec![VClock::new(); replicas];
+        let mut logs = vec![BTreeSet::new(); replicas];
+        // messages sent but not yet received
+        let mut pending = Vec::new();
+        // number of messages garbage-collected, and highest number of messages
+        // in any log after GC
+        let mut dropped = 0;
+        let mut max_log = 0;
+
+        for step in 0..200 {
+            // generate an event and deliver it locally
+            let from = step % replicas;
+            let seq = delivered[from].next(&from);
+            logs[from].insert((from, seq));
+
+            // send it to the other replicas, delaying some of the messages
+            for to in (0..replicas).filter(|to| *to != from) {
+                pending.push((step + (seq as usize * to) % 7, to, from, seq));
+            }
+            let (due, later) =
+                pending.into_iter().partition(|(at, ..)| *at <= step);
+            pending = later;
+            for (_, to, actor, seq) in due {
+                // messages are received out of order, but only delivered
+                // once all previous messages from the same actor were
+                logs[to].insert((actor, seq));
+                while logs[to]
+                    .contains(&(actor, frontier(&delivered[to], &actor) + 1))
+                {
+                    delivered[to].next(&actor);
+                }
+            }
+
+            // every few steps, run a GC cycle at each replica
+            if step % 5 != 0 {
+                continue;
+            }
+            let mut tclock = TClock::new();
+            for clock in delivered.iter() {
+                tclock.add(clock.clone());
+            }
+            for (own, log) in delivered.iter().zip(logs.iter_mut()) {
+                let watermark = tclock.gc_watermark(own, threshold);
+                let (stable, kept): (BTreeSet<_>, BTreeSet<_>) =
+                    log.iter().partition(|(actor, seq)| {
+                        *seq <= frontier(&watermark, actor)
+                    });
+
+                // the messages dropped were delivered at all replicas
+                assert!(stable.iter().all(|(actor, seq)| {
+                    delivered.iter().all(|clock| clock.contains(actor, *seq))
+                }));
+                // the messages received but not yet delivered are kept
+                assert!(log
+                    .iter()
+                    .filter(|(actor, seq)| !own.contains(actor, *seq))
+                    .all(|message| kept.contains(message)));
+
+                dropped += stable.len();
+                max_log = max_log.max(kept.len());
+                *log = kept;
+            }
+        }
+        // each message is dropped once, and the others are still in the logs
+        // (or in flight)
+        let kept: usize = logs.iter().map(|log| log.len()).sum();
+        assert_eq!(dropped + kept + pending.len(), 200 * replicas);
+        // each message is delayed by at most 6 steps, and so all messages sent
+        // at least 6 steps before a GC cycle are dropped by it: only the
+        // messages sent in the last 6 steps are kept
+        assert!(max_log <= 6);
+    }
+
+    #[test]
+    fn gc_loop_truncate() {
+        let replicas = 3;
+        let threshold = replicas as u64;
+
+        // for each replica, the events delivered, and the events received
+        // (possibly out of order), garbage-collected with `truncate_to_gc`
+        let mut delivered = vec![VClock::new(); replicas];
+        let mut received = vec![ARClock::new(); replicas];
+        // messages sent but not yet received
+        let mut pending = Vec::new();
+        // number of events garbage-collected
+        let mut dropped = 0;
+
+        for step in 0..200 {
+            // generate an event and deliver it locally
+            let from = step % replicas;
+            let seq = delivered[from].next(&from);
+            received[from].add(&from, seq);
+
+            // send it to the other replicas, delaying some of the messages
+            for to in (0..replicas).filter(|to| *to != from) {
+                pending.push((step + (seq as usize * to) % 7, to, from, seq));
+            }
+            let (due, later) =
+                pending.into_iter().partition(|(at, ..)| *at <= step);
+            pending = later;
+            for (_, to, actor, seq) in due {
+                // events are received out of order, but only delivered once
+                // all previous events from the same actor were
+                received[to].add(&actor, seq);
+                let mut next =
+                    delivered[to].get(&actor).map_or(0, |e| e.frontier()) + 1;
+                while received[to].contains(&actor, next) {
+                    delivered[to].add(&actor, next);
+                    next += 1;
+                }
+            }
+
+            // every few steps, run a GC cycle at each replica
+            if step % 5 != 0 {
+                continue;
+            }
+            let mut tclock = TClock::new();
+            for clock in delivered.iter() {
+                tclock.add(clock.clone());
+            }
+            for (own, clock) in delivered.iter().zip(received.iter_mut()) {
+                let watermark = tclock.gc_watermark(own, threshold);
+                let before = clock.clone();
+                clock.truncate_to_gc(&watermark);
+
+                for (actor, eset) in before.iter() {
+                    let max = watermark.get(actor).map_or(0, |e| e.frontier());
+                    let kept = clock.get(actor).unwrap();
+                    for (start, end) in eset.clone().range_iter() {
+                        for seq in start..=end {
+                            if seq <= max {
+                                // the events dropped were delivered at all
+                                // replicas
+                                assert!(!kept.is_event(seq));
+                                assert!(delivered
+                                    .iter()
+                                    .all(|clock| clock.contains(actor, seq)));
+                            } else {
+                                // no event above the watermark is dropped
+                                assert!(kept.is_event(seq));
+                            }
+                        }
+                    }
+                    dropped += eset.event_count() - kept.event_count();
+                }
+            }
+        }
+        assert!(dropped > 0);
+        // each event is dropped once, and the others are still in the clocks
+        // (or in flight)
+        let kept: u64 = received
+            .iter()
+            .flat_map(|clock| clock.iter().map(|(_, eset)| eset.event_count()))
+            .sum();
+        assert_eq!(
+            dropped + kept + pending.len() as u64,
+            200 * replicas as u64
+        );
+    }
+
+    #[test]
+    fn threshold_stable() {
+        let mut tclock = TClock::new();
+        tclock.add(clock::vclock_from_seqs(vec![10, 5, 7]));
+        tclock.add(clock::vclock_from_seqs(vec![10, 6, 7]));
+        tclock.add(clock::vclock_from_seqs(vec![10, 6, 2]));
+
+        // threshold 1 and 2 coincide
+        assert_eq!(tclock.threshold_union(1), tclock.threshold_union(2));
+        assert!(tclock.is_threshold_stable(1));
+
+        // threshold 2 and 3 differ
+        assert!(!tclock.is_threshold_stable(2));
+
+        // at threshold 4 no actor has reached the threshold
+        assert!(tclock.is_threshold_stable(4));
     }
 
     #[test]
@@ -410,12 +1618,12 @@ mod tests {
         // compute threshold = 1
         let (t1, equal_to_union) = tclock.threshold_union(1);
         assert_eq!(t1, c1);
-        assert_eq!(equal_to_union, true);
+        assert!(equal_to_union);
 
         // compute threshold = 2
         let (t2, equal_to_union) = tclock.threshold_union(2);
         assert_eq!(t2, bottom);
-        assert_eq!(equal_to_union, false);
+        assert!(!equal_to_union);
 
         // add second clock
         tclock.add(c2.clone());
@@ -423,12 +1631,12 @@ mod tests {
         // compute threshold = 1 (it changes)
         let (t1, equal_to_union) = tclock.threshold_union(1);
         assert_eq!(t1, both);
-        assert_eq!(equal_to_union, true);
+        assert!(equal_to_union);
 
         // compute threshold = 2 (doesn't change)
         let (t2, equal_to_union) = tclock.threshold_union(2);
         assert_eq!(t2, bottom);
-        assert_eq!(equal_to_union, false);
+        assert!(!equal_to_union);
 
         // add third clock (equal to the first)
         tclock.add(c1.clone());
@@ -436,12 +1644,12 @@ mod tests {
         // compute threshold = 1 (doesn't change)
         let (t1, equal_to_union) = tclock.threshold_union(1);
         assert_eq!(t1, both);
-        assert_eq!(equal_to_union, true);
+        assert!(equal_to_union);
 
         // compute threshold = 2 (it changes)
         let (t2, equal_to_union) = tclock.threshold_union(2);
         assert_eq!(t2, c1);
-        assert_eq!(equal_to_union, false);
+        assert!(!equal_to_union);
 
         // add fourth clock (equal to the second)
         tclock.add(c2.clone());
@@ -449,11 +1657,11 @@ mod tests {
         // compute threshold = 1 (doesn't change)
         let (t1, equal_to_union) = tclock.threshold_union(1);
         assert_eq!(t1, both);
-        assert_eq!(equal_to_union, true);
+        assert!(equal_to_union);
 
         // compute threshold = 2 (it changes)
         let (t2, equal_to_union) = tclock.threshold_union(2);
         assert_eq!(t2, both);
-        assert_eq!(equal_to_union, true);
+        assert!(equal_to_union);
     }
 }
@@ -0,0 +1,206 @@
+//! This module contains a (test-only) simulation harness for replicas that
+//! exchange clocks over an unreliable network.
+//!
+//! Replicas generate events with `Clock::next` and send each new event to
+//! every other replica. Messages can be lost and are delivered out of order.
+//! Replicas also gossip their full clock to a random peer, and once all events
+//! have been generated, they run anti-entropy rounds (sending their full clock
+//! to every other replica) until all clocks are equal.
+//!
+//! The simulation is deterministic given its seed, so that failures are
+//! reproducible.
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! let report = testing::simulate_gossip::<AboveExSet>(4, 100, 42, 0.1);
+//! assert_eq!(report.events, 100);
+//! assert!(report.messages_lost > 0);
+//! assert!(report.max_extras > 0);
+//! ```
+
+use crate::*;
+use rand::prelude::*;
+
+/// Probability of a replica gossiping its full clock after generating an
+/// event.
+const GOSSIP_RATE: f64 = 0.1;
+/// Maximum delay (in steps) of a message.
+const MAX_DELAY: usize = 10;
+/// Maximum number of anti-entropy rounds before giving up.
+const MAX_ROUNDS: usize = 1000;
+
+/// Statistics of a simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SimReport {
+    /// Number of events generated.
+    pub events: usize,
+    /// Number of messages sent (including the lost ones).
+    pub messages_sent: usize,
+    /// Number of messages lost.
+    pub messages_lost: usize,
+    /// Number of anti-entropy rounds until all clocks were equal.
+    pub rounds: usize,
+    /// Highest number of extras (or exceptions) observed in a single entry of
+    /// some clock (see `EventSet::events`).
+    pub max_extras: usize,
+}
+
+/// A message in transit.
+struct Message<E: EventSet> {
+    /// Step at which the message is delivered
+    deliver_at: usize,
+    to: usize,
+    clock: Clock<u64, E>,
+}
+
+struct Sim<E: EventSet> {
+    rng: StdRng,
+    loss_rate: f64,
+    clocks: Vec<Clock<u64, E>>,
+    in_transit: Vec<Message<E>>,
+    report: SimReport,
+}
+
+impl<E: EventSet> Sim<E> {
+    fn send(&mut self, now: usize, to: usize, clock: Clock<u64, E>) {
+        self.report.messages_sent += 1;
+        if self.rng.gen_bool(self.loss_rate) {
+            self.report.messages_lost += 1;
+        } else {
+            let deliver_at = now + self.rng.gen_range(0, MAX_DELAY + 1);
+            self.in_transit.push(Message {
+                deliver_at,
+                to,
+                clock,
+            });
+        }
+    }
+
+    /// Delivers all messages due at step `now` (in random order).
+    fn deliver(&mut self, now: usize) {
+        self.in_transit.shuffle(&mut self.rng);
+        let (due, in_transit) = self
+            .in_transit
+            .drain(..)
+            .partition(|message| message.deliver_at <= now);
+        self.in_transit = in_transit;
+
+        for message in due {
+            let Message { to, clock, .. } = message;
+            self.clocks[to].join(&clock);
+            let extras = self.clocks[to]
+                .iter()
+                .map(|(_, eset)| eset.events().1.len())
+                .max()
+                .unwrap_or(0);
+            self.report.max_extras =
+                std::cmp::max(self.report.max_extras, extras);
+        }
+    }
+
+    fn peer(&mut self, from: usize) -> usize {
+        let replicas = self.clocks.len();
+        (from + self.rng.gen_range(1, replicas)) % replicas
+    }
+}
+
+/// Simulates `replicas` replicas (with actor identifiers `0..replicas`) that
+/// generate `steps` events in total, with each message being lost with
+/// probability `loss_rate`. The simulation is deterministic given `rng_seed`.
+///
+/// # Panics
+/// Panics if there are fewer than two replicas, if `loss_rate` is not within
+/// `[0, 1)`, or if the clocks don't converge (either they don't become equal
+/// or some generated event is missing in some clock).
+pub fn simulate_gossip<E: EventSet>(
+    replicas: usize,
+    steps: usize,
+    rng_seed: u64,
+    loss_rate: f64,
+) -> SimReport {
+    assert!(replicas >= 2, "there should be at least two replicas");
+    assert!(
+        (0.0..1.0).contains(&loss_rate),
+        "the loss rate should be within [0, 1)"
+    );
+
+    let mut sim = Sim {
+        rng: StdRng::seed_from_u64(rng_seed),
+        loss_rate,
+        clocks: vec![Clock::<u64, E>::new(); replicas],
+        in_transit: Vec::new(),
+        report: SimReport::default(),
+    };
+    let mut dots = Vec::with_capacity(steps);
+
+    // generate events, sending each of them to every other replica
+    for now in 0..steps {
+        let from = sim.rng.gen_range(0, replicas);
+        let actor = from as u64;
+        let seq = sim.clocks[from].next(&actor);
+        dots.push((actor, seq));
+        sim.report.events += 1;
+
+        for to in (0..replicas).filter(|to| *to != from) {
+            let delta = Clock::from(vec![(actor, E::from_event(seq))]);
+            sim.send(now, to, delta);
+        }
+        if sim.rng.gen_bool(GOSSIP_RATE) {
+            let to = sim.peer(from);
+            let clock = sim.clocks[from].clone();
+            sim.send(now, to, clock);
+        }
+        sim.deliver(now);
+    }
+
+    // deliver the remaining messages
+    let mut now = steps;
+    while !sim.in_transit.is_empty() {
+        sim.deliver(now);
+        now += 1;
+    }
+
+    // run anti-entropy rounds until all clocks are equal (i.e. have the same
+    // events)
+    let converged = |clocks: &[Clock<u64, E>]| {
+        clocks
+            .iter()
+            .all(|clock| clock.leq(&clocks[0]) && clocks[0].leq(clock))
+    };
+    while !converged(&sim.clocks) {
+        assert!(
+            sim.report.rounds < MAX_ROUNDS,
+            "clocks didn't converge (seed: {})",
+            rng_seed
+        );
+        sim.report.rounds += 1;
+        for from in 0..replicas {
+            for to in (0..replicas).filter(|to| *to != from) {
+                let clock = sim.clocks[from].clone();
+                sim.send(now, to, clock);
+            }
+        }
+        // deliver all messages of this round
+        let round_end = now + MAX_DELAY;
+        while now <= round_end {
+            sim.deliver(now);
+            now += 1;
+        }
+    }
+
+    // check that all events are in all clocks
+    for (actor, seq) in dots {
+        for clock in sim.clocks.iter() {
+            assert!(
+                clock.contains(&actor, seq),
+                "event {:?} is missing (seed: {})",
+                (actor, seq),
+                rng_seed
+            );
+        }
+    }
+
+    sim.report
+}
@@ -1,7 +1,7 @@
 use crate::*;
 use quickcheck::{Arbitrary, Gen};
 
-const MAX_EVENTS: u64 = 20;
+pub const MAX_EVENTS: u64 = 20;
 
 /// This enum should allow tests to be more effective since they only work on a
 /// small number of actors.
@@ -39,60 +39,97 @@ impl<E: Ord + Arbitrary, C: Count + Arbitrary> Arbitrary for MultiSet<E, C> {
     }
 }
 
+/// Only valid (non-zero) events are generated.
+impl Arbitrary for Event {
+    fn arbitrary<G: Gen>(g: &mut G) -> Event {
+        let seq: u64 = Arbitrary::arbitrary(g);
+        Event(seq + 1)
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Event>> {
+        Box::new((self.0 - 1).shrink().map(|seq| Event(seq + 1)))
+    }
+}
+
+/// Generates a vector of valid (non-zero) events.
+fn events<G: Gen>(g: &mut G) -> Vec<u64> {
+    let events: Vec<Event> = Arbitrary::arbitrary(g);
+    events.into_iter().map(|Event(seq)| seq).collect()
+}
+
+/// Generates a vector of valid (non-zero) events, with at most `MAX_EVENTS`
+/// as the highest event.
+fn bounded_events<G: Gen>(g: &mut G) -> Vec<u64> {
+    // reduce the number of possible events
+    events(g).into_iter().filter(|&x| x <= MAX_EVENTS).collect()
+}
+
+/// Shrinks an event set, never generating event 0.
+fn shrink_eset<E: EventSet + 'static>(eset: &E) -> Box<dyn Iterator<Item = E>> {
+    let vec: Vec<Event> = eset.iter().map(Event).collect();
+    Box::new(
+        vec.shrink()
+            .map(|v| E::from_events(v.into_iter().map(|Event(seq)| seq))),
+    )
+}
+
 impl Arbitrary for MaxSet {
     fn arbitrary<G: Gen>(g: &mut G) -> MaxSet {
-        let events: Vec<u64> = Arbitrary::arbitrary(g);
-        MaxSet::from_events(events)
+        MaxSet::from_events(events(g))
     }
 
     fn shrink(&self) -> Box<dyn Iterator<Item = MaxSet>> {
-        let vec: Vec<u64> = self.clone().event_iter().collect();
-        Box::new(vec.shrink().map(|v| MaxSet::from_events(v)))
+        shrink_eset(self)
     }
 }
 
 impl Arbitrary for AboveExSet {
     fn arbitrary<G: Gen>(g: &mut G) -> AboveExSet {
-        let events: Vec<u64> = Arbitrary::arbitrary(g);
-        // reduce the number of possible events
-        let events: Vec<u64> =
-            events.into_iter().filter(|&x| x <= MAX_EVENTS).collect();
-        AboveExSet::from_events(events)
+        AboveExSet::from_events(bounded_events(g))
     }
 
     fn shrink(&self) -> Box<dyn Iterator<Item = AboveExSet>> {
-        let vec: Vec<u64> = self.clone().event_iter().collect();
-        Box::new(vec.shrink().map(|v| AboveExSet::from_events(v)))
+        shrink_eset(self)
     }
 }
 
 impl Arbitrary for AboveRangeSet {
     fn arbitrary<G: Gen>(g: &mut G) -> AboveRangeSet {
-        let events: Vec<u64> = Arbitrary::arbitrary(g);
-        // reduce the number of possible events
-        let events: Vec<u64> =
-            events.into_iter().filter(|&x| x <= MAX_EVENTS).collect();
-        AboveRangeSet::from_events(events)
+        AboveRangeSet::from_events(bounded_events(g))
     }
 
     fn shrink(&self) -> Box<dyn Iterator<Item = AboveRangeSet>> {
-        let vec: Vec<u64> = self.clone().event_iter().collect();
-        Box::new(vec.shrink().map(|v| AboveRangeSet::from_events(v)))
+        shrink_eset(self)
     }
 }
 
 impl Arbitrary for BelowExSet {
     fn arbitrary<G: Gen>(g: &mut G) -> BelowExSet {
-        let events: Vec<u64> = Arbitrary::arbitrary(g);
-        // reduce the number of possible events
-        let events: Vec<u64> =
-            events.into_iter().filter(|&x| x <= MAX_EVENTS).collect();
-        BelowExSet::from_events(events)
+        BelowExSet::from_events(bounded_events(g))
     }
 
     fn shrink(&self) -> Box<dyn Iterator<Item = BelowExSet>> {
-        let vec: Vec<u64> = self.clone().event_iter().collect();
-        Box::new(vec.shrink().map(|v| BelowExSet::from_events(v)))
+        shrink_eset(self)
+    }
+}
+
+impl Arbitrary for BelowRangeSet {
+    fn arbitrary<G: Gen>(g: &mut G) -> BelowRangeSet {
+        BelowRangeSet::from_events(bounded_events(g))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = BelowRangeSet>> {
+        shrink_eset(self)
+    }
+}
+
+impl Arbitrary for ExplicitSet {
+    fn arbitrary<G: Gen>(g: &mut G) -> ExplicitSet {
+        ExplicitSet::from_events(bounded_events(g))
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = ExplicitSet>> {
+        shrink_eset(self)
     }
 }
 
@@ -142,6 +179,8 @@ mod test {
         some_shrink_assert::<MaxSet>();
         some_shrink_assert::<AboveExSet>();
         some_shrink_assert::<BelowExSet>();
+        some_shrink_assert::<BelowRangeSet>();
+        some_shrink_assert::<ExplicitSet>();
     }
 
     fn arbitrary<T: Arbitrary>() -> T {
@@ -159,7 +198,7 @@ mod test {
     fn some_shrink_assert<T: Arbitrary + EventSet>() {
         for _ in 0..ITERATIONS {
             let a = arbitrary::<T>();
-            match a.clone().event_iter().count() {
+            match a.iter().count() {
                 0 => (),
                 _ => assert!(a.shrink().count() > 0),
             }
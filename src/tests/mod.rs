@@ -2,7 +2,15 @@ mod arbitrary;
 mod prop_aeclock;
 mod prop_arclock;
 mod prop_beclock;
+mod prop_builder;
+mod prop_defaults;
 mod prop_eventset;
+mod prop_gossip;
 mod prop_multiset;
+mod prop_ops;
+#[cfg(feature = "persistent")]
+mod prop_pclock;
 mod prop_tclock;
+mod prop_truncate;
 mod prop_vclock;
+mod prop_view;
@@ -1,6 +1,7 @@
-use crate::tests::arbitrary::Musk;
+use crate::tests::arbitrary::{Musk, MAX_EVENTS};
 use crate::*;
 use quickcheck_macros::quickcheck;
+use std::cmp::Ordering;
 use std::collections::BTreeSet;
 
 #[quickcheck]
@@ -22,6 +23,17 @@ fn join(mut aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
     })
 }
 
+#[quickcheck]
+fn join_delta(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
+    let new = !aeclock_b.leq(&aeclock_a);
+    let mut joined = aeclock_a.clone();
+    let grew = joined.join_delta(&aeclock_b);
+
+    // prop: the clock grows iff `b` has some event not in `a`, and joining
+    // an already dominated clock is a no-op
+    grew == new && !joined.join_delta(&aeclock_b)
+}
+
 #[quickcheck]
 fn meet(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
     let mut result = aeclock_a.clone();
@@ -31,7 +43,7 @@ fn meet(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
         let a = eset_a.event_iter().collect::<BTreeSet<_>>();
         let b = aeclock_b
             .get(&actor)
-            .map(|eset_b| eset_b.clone().event_iter().collect::<BTreeSet<_>>())
+            .map(|eset_b| eset_b.iter().collect::<BTreeSet<_>>())
             .unwrap_or_default();
         let expected = a.intersection(&b).cloned().collect::<BTreeSet<_>>();
         let result = result
@@ -52,7 +64,7 @@ fn subtracted(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
         let a = eset_a.event_iter().collect::<BTreeSet<_>>();
         let b = aeclock_b
             .get(&actor)
-            .map(|eset_b| eset_b.clone().event_iter().collect::<BTreeSet<_>>())
+            .map(|eset_b| eset_b.iter().collect::<BTreeSet<_>>())
             .unwrap_or_default();
         let expected = a.difference(&b).cloned().collect::<BTreeSet<_>>();
         let result = result
@@ -64,3 +76,281 @@ fn subtracted(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
         expected == result
     })
 }
+
+#[quickcheck]
+fn sub(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
+    let delta = &aeclock_a - &aeclock_b;
+
+    // prop: joining the difference into `b` is the same as joining `a`
+    let mut with_delta = aeclock_b.clone();
+    with_delta.join(&delta);
+    let mut joined = aeclock_b.clone();
+    joined.join(&aeclock_a);
+
+    // prop: the difference has no events in common with `b`
+    with_delta.dominates(&aeclock_a)
+        && with_delta.leq(&joined)
+        && joined.leq(&with_delta)
+        && delta == aeclock_a.subtracted_clock(&aeclock_b)
+        && delta.iter().all(|(actor, eset)| {
+            eset.iter().all(|event| !aeclock_b.contains(actor, event))
+        })
+}
+
+#[quickcheck]
+fn missing_count(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
+    let expected: usize = aeclock_a
+        .subtracted(&aeclock_b)
+        .values()
+        .map(|events| events.len())
+        .sum();
+
+    // prop: the number of missing events is the number of events subtracted
+    aeclock_a.missing_count(&aeclock_b) == expected as u64
+}
+
+#[quickcheck]
+fn count_events_in_range(aeclock: AEClock<Musk>, start: u8, end: u8) -> bool {
+    let (start, end) = (start as u64, end as u64);
+    let expected = aeclock
+        .iter()
+        .map(|(actor, _)| {
+            (start..=end)
+                .filter(|event| *event > 0 && aeclock.contains(actor, *event))
+                .count() as u64
+        })
+        .sum::<u64>();
+
+    // prop: counting events in a range is the same as checking each event
+    aeclock.count_events_in_range(start, end) == expected
+}
+
+#[quickcheck]
+fn join_capped(
+    aeclock_a: AEClock<Musk>,
+    aeclock_b: AEClock<Musk>,
+    max_extras: u8,
+) -> bool {
+    let max_extras = max_extras as usize % 10;
+    let mut capped = aeclock_a.clone();
+    capped.join_capped(&aeclock_b, max_extras);
+    let mut joined = aeclock_a.clone();
+    joined.join(&aeclock_b);
+
+    // prop: the number of extras of the actors in b stays within the cap, and
+    // all events in the capped join are in the join
+    aeclock_b.iter().all(|(actor, _)| {
+        let eset = capped.get(actor).expect("actor should be in the clock");
+        eset.events().1.len() <= max_extras
+    }) && capped.iter().all(|(actor, eset)| {
+        eset.leq(joined.get(actor).expect("actor should be in the clock"))
+    })
+}
+
+#[quickcheck]
+fn truncate_to_common_frontier(
+    aeclock_a: AEClock<Musk>,
+    aeclock_b: AEClock<Musk>,
+    aeclock_c: AEClock<Musk>,
+) -> bool {
+    let clocks = vec![aeclock_a, aeclock_b, aeclock_c];
+    let frontier = clock::common_frontier(&clocks);
+
+    // compute the meet of all clocks (restricted to the common actors)
+    let mut meet = clocks[0].clone();
+    clocks.iter().for_each(|clock| meet.meet(clock));
+
+    // prop: the common frontier is the frontier of the meet
+    let frontier_prop = meet.frontier() == frontier;
+
+    // prop: after truncation, every clock equals the truncated meet, and its
+    // frontier is the common frontier
+    meet.truncate_to(&frontier);
+    let truncate_prop = clocks.into_iter().all(|mut clock| {
+        clock.truncate_to(&frontier);
+        clock == meet && clock.frontier() == frontier
+    });
+
+    frontier_prop && truncate_prop
+}
+
+#[quickcheck]
+fn map_events(actor: Musk, aeclock: AEClock<Musk>, offset: u8) -> bool {
+    let offset = offset as u64;
+    let mapped = aeclock.clone().map_events(|a, seq| {
+        if *a == actor {
+            seq + offset
+        } else {
+            seq
+        }
+    });
+
+    // prop: the events of `actor` are shifted by `offset`, and the remaining
+    // events are unchanged
+    mapped.len() == aeclock.len()
+        && aeclock.iter().all(|(a, eset)| {
+            let shift = if *a == actor { offset } else { 0 };
+            (1..=MAX_EVENTS).all(|seq| {
+                eset.is_event(seq) == mapped.contains(a, seq + shift)
+            }) && (1..=shift).all(|seq| !mapped.contains(a, seq))
+        })
+}
+
+#[quickcheck]
+fn evict_absorb(aeclock: AEClock<Musk>, actors: Vec<Musk>) -> bool {
+    let mut clock = aeclock.clone();
+    let evicted = clock.evict(actors.clone());
+
+    // prop: the events of evicted actors are no longer in the clock, but are
+    // in the evicted clock
+    let evict_prop = aeclock.iter().all(|(actor, eset)| {
+        let is_evicted = actors.contains(actor);
+        eset.iter().all(|seq| {
+            clock.contains(actor, seq) != is_evicted
+                && evicted.contains(actor, seq) == is_evicted
+        })
+    });
+
+    // prop: absorbing the evicted clock gives back the initial clock
+    clock.absorb(evicted);
+    let absorb_prop = clock == aeclock
+        && aeclock.iter().all(|(actor, eset)| {
+            eset.iter().all(|seq| clock.contains(actor, seq))
+        });
+
+    evict_prop && absorb_prop
+}
+
+#[quickcheck]
+fn next(actor: Musk, aeclock: AEClock<Musk>) -> bool {
+    let mut aeclock = aeclock.clone();
+    let frontier = aeclock.get(&actor).map_or(0, |eset| eset.frontier());
+    let next = aeclock.next(&actor);
+
+    // prop: a newly created event is the one after the frontier (even if
+    // there are extras), and it's now part of the clock
+    next == frontier + 1 && aeclock.contains(&actor, next)
+}
+
+#[quickcheck]
+fn leq(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
+    // check if all events in `a` are in `b`
+    let expected = aeclock_a.iter().all(|(actor, eset)| {
+        eset.iter().all(|seq| aeclock_b.contains(actor, seq))
+    });
+
+    let mut joined = aeclock_a.clone();
+    joined.join(&aeclock_b);
+
+    // prop: `leq` is inclusion of events, and both clocks are included in
+    // their join
+    aeclock_a.leq(&aeclock_b) == expected
+        && aeclock_a <= joined
+        && aeclock_b <= joined
+}
+
+#[quickcheck]
+fn dominates_concurrent(
+    aeclock_a: AEClock<Musk>,
+    aeclock_b: AEClock<Musk>,
+) -> bool {
+    let mut joined = aeclock_a.clone();
+    joined.join(&aeclock_b);
+
+    // prop: the join dominates both clocks, and two clocks are concurrent iff
+    // they are incomparable
+    joined.dominates(&aeclock_a)
+        && joined.dominates(&aeclock_b)
+        && aeclock_a.concurrent(&aeclock_b)
+            == aeclock_a.partial_cmp(&aeclock_b).is_none()
+}
+
+#[quickcheck]
+fn same_events_ignores_empty_entries(
+    aeclock_a: AEClock<Musk>,
+    aeclock_b: AEClock<Musk>,
+) -> bool {
+    // pad `a` with bottom entries for the actors in `b`
+    let mut padded = AEClock::seeded_from(&aeclock_b);
+    padded.join(&aeclock_a);
+
+    // prop: bottom entries are ignored by `same_events` (and by the hash)
+    let same = padded.same_events(&aeclock_a)
+        && padded.canonical_hash() == aeclock_a.canonical_hash();
+
+    // prop: clocks compare `Equal` iff they're `==`
+    let equal = |a: &AEClock<Musk>, b: &AEClock<Musk>| {
+        (a == b) == (a.partial_cmp(b) == Some(Ordering::Equal))
+    };
+    same && equal(&padded, &aeclock_a) && equal(&aeclock_a, &aeclock_b)
+}
+
+#[quickcheck]
+fn missing_to_dominate(
+    aeclock_a: AEClock<Musk>,
+    aeclock_b: AEClock<Musk>,
+) -> bool {
+    let missing = aeclock_a.missing_to_dominate(&aeclock_b);
+
+    // prop: nothing is missing iff `a` dominates `b`, and the events missing
+    // are in `b` but not in `a`
+    missing.is_empty() == aeclock_a.dominates(&aeclock_b)
+        && missing.iter().all(|(actor, events)| {
+            events.iter().all(|event| {
+                aeclock_b.contains(actor, *event)
+                    && !aeclock_a.contains(actor, *event)
+            })
+        })
+}
+
+#[quickcheck]
+fn total_events(
+    mut aeclock_a: AEClock<Musk>,
+    aeclock_b: AEClock<Musk>,
+) -> bool {
+    aeclock_a.join(&aeclock_b);
+
+    // prop: the number of events is the number of events enumerated
+    aeclock_a.total_events() == aeclock_a.event_dots().count() as u64
+}
+
+#[quickcheck]
+fn canonical_hash(aeclock: AEClock<Musk>, actor: Musk, event: Event) -> bool {
+    // prop: the hash doesn't depend on the order of the entries
+    let mut entries: Vec<_> = aeclock.clone().into_iter().collect();
+    entries.reverse();
+    let same = AEClock::from(entries);
+
+    // prop: adding a new event changes the hash (with high probability)
+    let mut other = aeclock.clone();
+    let changed = other.add(&actor, event);
+
+    aeclock.canonical_hash() == same.canonical_hash()
+        && changed != (aeclock.canonical_hash() == other.canonical_hash())
+}
+
+#[quickcheck]
+fn from_vec_sized(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
+    let entries_a: Vec<_> = aeclock_a.clone().into_iter().collect();
+    let entries_b: Vec<_> = aeclock_b.clone().into_iter().collect();
+
+    // prop: without repeated actors, it's the same as `from`
+    let same = AEClock::from_vec_sized(entries_a.clone()) == aeclock_a;
+
+    // prop: the event sets of repeated actors are joined
+    let mut joined = aeclock_a;
+    joined.join(&aeclock_b);
+    let entries = entries_a.into_iter().chain(entries_b).collect();
+    same && AEClock::from_vec_sized(entries) == joined
+}
+
+#[quickcheck]
+fn is_prefix_of(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
+    // prop: `a` is a prefix of `b` iff `a` is contiguous and `b` has all its
+    // events
+    let contiguous = aeclock_a
+        .iter()
+        .all(|(_, eset)| eset.iter().eq(1..=eset.frontier()));
+    aeclock_a.is_prefix_of(&aeclock_b)
+        == (contiguous && aeclock_a.leq(&aeclock_b))
+}
@@ -1,6 +1,7 @@
 use crate::tests::arbitrary::Musk;
 use crate::*;
 use quickcheck_macros::quickcheck;
+use std::collections::BTreeSet;
 
 #[quickcheck]
 fn add_dot(actor: Musk, event: u64, arclock: ARClock<Musk>) -> bool {
@@ -20,3 +21,83 @@ fn join(mut arclock_a: ARClock<Musk>, aeclock_b: ARClock<Musk>) -> bool {
         eset.event_iter().all(|seq| arclock_a.contains(&actor, seq))
     })
 }
+
+#[quickcheck]
+fn join_ranges(
+    above_range_set_a: AboveRangeSet,
+    above_range_set_b: AboveRangeSet,
+) -> bool {
+    let mut joined = above_range_set_a.clone();
+    joined.join(&above_range_set_b);
+
+    // compute the join by adding each event (as before the merge-based join)
+    let events: BTreeSet<_> = above_range_set_a
+        .event_iter()
+        .chain(above_range_set_b.event_iter())
+        .collect();
+    let expected = AboveRangeSet::from_events(events.clone());
+
+    // prop: the merge-based join has the same events
+    joined.frontier() == expected.frontier()
+        && joined.event_iter().collect::<BTreeSet<_>>() == events
+}
+
+#[quickcheck]
+fn next(actor: Musk, arclock: ARClock<Musk>) -> bool {
+    let mut arclock = arclock.clone();
+    let frontier = arclock.get(&actor).map_or(0, |eset| eset.frontier());
+    let next = arclock.next(&actor);
+
+    // prop: a newly created event is the one after the frontier (even if
+    // there are extras), and it's now part of the clock
+    next == frontier + 1 && arclock.contains(&actor, next)
+}
+
+#[quickcheck]
+fn subtracted_clock(
+    arclock_a: ARClock<Musk>,
+    arclock_b: ARClock<Musk>,
+) -> bool {
+    let delta = arclock_a.subtracted_clock(&arclock_b);
+
+    // prop: the difference has exactly the events in `a` that are not in `b`,
+    // and only actors with such events
+    let subtracted = arclock_a.subtracted(&arclock_b);
+    let expected = subtracted.values().filter(|e| !e.is_empty()).count();
+    delta.len() == expected
+        && subtracted.into_iter().all(|(actor, events)| {
+            let result = delta
+                .get(&actor)
+                .map(|eset| eset.iter().collect::<BTreeSet<_>>())
+                .unwrap_or_default();
+            result == events.into_iter().collect::<BTreeSet<_>>()
+        })
+        && delta == &arclock_a - &arclock_b
+}
+
+#[quickcheck]
+fn total_events(
+    mut arclock_a: ARClock<Musk>,
+    arclock_b: ARClock<Musk>,
+) -> bool {
+    arclock_a.join(&arclock_b);
+
+    // prop: the number of events is the number of events enumerated
+    arclock_a.total_events() == arclock_a.event_dots().count() as u64
+}
+
+#[quickcheck]
+fn contains_range(
+    mut arclock_a: ARClock<Musk>,
+    arclock_b: ARClock<Musk>,
+    actor: Musk,
+    start: u8,
+    end: u8,
+) -> bool {
+    arclock_a.join(&arclock_b);
+
+    // prop: a range is contained iff each of its events is
+    let (start, end) = (start as u64 % 32, end as u64 % 32);
+    let expected = (start..=end).all(|seq| arclock_a.contains(&actor, seq));
+    arclock_a.contains_range(&actor, start, end) == expected
+}
@@ -1,6 +1,7 @@
 use crate::tests::arbitrary::Musk;
 use crate::*;
 use quickcheck_macros::quickcheck;
+use std::collections::BTreeSet;
 
 #[quickcheck]
 fn add_dot(actor: Musk, event: u64, beclock: BEClock<Musk>) -> bool {
@@ -20,3 +21,57 @@ fn join(mut beclock_a: BEClock<Musk>, beclock_b: BEClock<Musk>) -> bool {
         eset.event_iter().all(|seq| beclock_a.contains(&actor, seq))
     })
 }
+
+#[quickcheck]
+fn join_capped(
+    beclock_a: BEClock<Musk>,
+    beclock_b: BEClock<Musk>,
+    max_extras: u8,
+) -> bool {
+    let max_extras = max_extras as usize % 10;
+    let mut capped = beclock_a.clone();
+    capped.join_capped(&beclock_b, max_extras);
+    let mut joined = beclock_a.clone();
+    joined.join(&beclock_b);
+
+    // prop: the number of extras of the actors in b stays within the cap, and
+    // all events in the capped join are in the join
+    beclock_b.iter().all(|(actor, _)| {
+        let eset = capped.get(actor).expect("actor should be in the clock");
+        eset.events().1.len() <= max_extras
+    }) && capped.iter().all(|(actor, eset)| {
+        eset.leq(joined.get(actor).expect("actor should be in the clock"))
+    })
+}
+
+#[quickcheck]
+fn subtracted(beclock_a: BEClock<Musk>, beclock_b: BEClock<Musk>) -> bool {
+    let result = beclock_a.subtracted(&beclock_b);
+
+    beclock_a.into_iter().all(|(actor, eset_a)| {
+        let a = eset_a.event_iter().collect::<BTreeSet<_>>();
+        let b = beclock_b
+            .get(&actor)
+            .map(|eset_b| eset_b.iter().collect::<BTreeSet<_>>())
+            .unwrap_or_default();
+        let expected = a.difference(&b).cloned().collect::<BTreeSet<_>>();
+        let result = result
+            .get(&actor)
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        expected == result
+    })
+}
+
+#[quickcheck]
+fn total_events(
+    mut beclock_a: BEClock<Musk>,
+    beclock_b: BEClock<Musk>,
+) -> bool {
+    beclock_a.join(&beclock_b);
+
+    // prop: the number of events is the number of events enumerated
+    beclock_a.total_events() == beclock_a.event_dots().count() as u64
+}
@@ -0,0 +1,89 @@
+use crate::*;
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeSet;
+
+#[quickcheck]
+fn build_max_set(events: BTreeSet<u64>) -> bool {
+    // for `MaxSet`, only the highest event matters
+    check_build::<MaxSet>(events, |eset, expected| eset == expected)
+}
+
+#[quickcheck]
+fn build_above_exset(events: BTreeSet<u64>) -> bool {
+    check_build::<AboveExSet>(events, |eset, expected| eset == expected)
+}
+
+#[quickcheck]
+fn build_above_range_set(events: BTreeSet<u64>) -> bool {
+    // ranges may be split differently, so compare the events
+    check_build::<AboveRangeSet>(events, |eset, expected| {
+        eset.event_iter().eq(expected.event_iter())
+    })
+}
+
+#[quickcheck]
+fn build_below_exset(events: BTreeSet<u64>) -> bool {
+    check_build::<BelowExSet>(events, |eset, expected| eset == expected)
+}
+
+#[quickcheck]
+fn build_explicit_set(events: BTreeSet<u64>) -> bool {
+    check_build::<ExplicitSet>(events, |eset, expected| eset == expected)
+}
+
+#[quickcheck]
+fn build_with_zero(events: Vec<u64>) -> bool {
+    let result = EventSetBuilder::<AboveExSet>::new()
+        .events(events)
+        .event(0)
+        .build();
+
+    // prop: event 0 is never valid
+    result == Err(BuildError::ZeroEvent)
+}
+
+#[quickcheck]
+fn build_with_repeated(mut events: BTreeSet<u64>, event: u64) -> bool {
+    // 0's are not allowed as events
+    events.remove(&0);
+
+    let result = EventSetBuilder::<AboveExSet>::new()
+        .events(events)
+        .range(event + 1, event + 1)
+        .event(event + 1)
+        .build();
+
+    // prop: adding the same event twice is never valid
+    matches!(result, Err(BuildError::Overlap { .. }))
+}
+
+fn check_build<E: EventSet>(
+    mut events: BTreeSet<u64>,
+    eq: impl Fn(E, E) -> bool,
+) -> bool {
+    // 0's are not allowed as events
+    events.remove(&0);
+
+    // group events in ranges of contiguous events
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for &event in events.iter() {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == event => *end = event,
+            _ => ranges.push((event, event)),
+        }
+    }
+
+    // add single events as events and the remaining as ranges
+    let mut builder = EventSetBuilder::new();
+    for (start, end) in ranges.into_iter().rev() {
+        if start == end {
+            builder.event(start);
+        } else {
+            builder.range(start, end);
+        }
+    }
+
+    // prop: building is the same as adding events one by one
+    let expected = E::from_events(events);
+    builder.build().is_ok_and(|eset| eq(eset, expected))
+}
@@ -0,0 +1,142 @@
+use crate::*;
+use quickcheck_macros::quickcheck;
+use std::collections::BTreeSet;
+
+/// Event set that only implements the required methods of `EventSet`.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct NaiveSet(BTreeSet<u64>);
+
+impl EventSet for NaiveSet {
+    type EventIter = std::collections::btree_set::IntoIter<u64>;
+
+    fn new() -> Self {
+        NaiveSet(BTreeSet::new())
+    }
+
+    fn next_event(&mut self) -> u64 {
+        let next = self.0.iter().next_back().map_or(1, |max| max + 1);
+        self.0.insert(next);
+        next
+    }
+
+    fn add_event(&mut self, event: u64) -> bool {
+        event != 0 && self.0.insert(event)
+    }
+
+    fn is_event(&self, event: u64) -> bool {
+        event == 0 || self.0.contains(&event)
+    }
+
+    fn events(&self) -> (u64, Vec<u64>) {
+        let frontier = self.frontier();
+        let extras = self.0.range(frontier + 1..).cloned().collect();
+        (frontier, extras)
+    }
+
+    fn frontier(&self) -> u64 {
+        (1..)
+            .take_while(|event| self.0.contains(event))
+            .last()
+            .unwrap_or(0)
+    }
+
+    fn join(&mut self, other: &Self) {
+        self.0.extend(other.0.iter().cloned());
+    }
+
+    fn meet(&mut self, other: &Self) {
+        self.0.retain(|event| other.0.contains(event));
+    }
+
+    fn subtracted(&self, other: &Self) -> Vec<u64> {
+        self.0.difference(&other.0).cloned().collect()
+    }
+
+    fn event_iter(self) -> Self::EventIter {
+        self.0.into_iter()
+    }
+}
+
+/// Creates a `NaiveSet` (and the expected events) from arbitrary events.
+fn naive(events: Vec<u8>) -> (NaiveSet, BTreeSet<u64>) {
+    let events: BTreeSet<_> = events
+        .into_iter()
+        .map(|event| event as u64 % 32)
+        .filter(|event| *event != 0)
+        .collect();
+    (NaiveSet::from_events(events.clone()), events)
+}
+
+#[quickcheck]
+fn subtracted_count(events: Vec<u8>, other: Vec<u8>) -> bool {
+    let (eset, events) = naive(events);
+    let (other, other_events) = naive(other);
+
+    // prop: the count is the number of events in `self` and not in `other`
+    let expected = events.difference(&other_events).count() as u64;
+    eset.subtracted_count(&other) == expected
+}
+
+#[quickcheck]
+fn event_count(events: Vec<u8>) -> bool {
+    let (eset, events) = naive(events);
+
+    // prop: the count is the number of events
+    eset.event_count() == events.len() as u64
+        && eset.is_empty() == events.is_empty()
+}
+
+#[quickcheck]
+fn max_event(events: Vec<u8>) -> bool {
+    let (eset, events) = naive(events);
+
+    // prop: the highest event is the last one (or 0 if there's none)
+    eset.max_event() == events.iter().next_back().cloned().unwrap_or(0)
+}
+
+#[quickcheck]
+fn iter(events: Vec<u8>) -> bool {
+    let (eset, events) = naive(events);
+
+    // prop: all events are iterated, from lowest to highest
+    eset.iter().eq(events.iter().cloned())
+}
+
+#[quickcheck]
+fn remove_event(events: Vec<u8>, event: u8) -> bool {
+    let (mut eset, mut events) = naive(events);
+    let event = event as u64 % 32;
+
+    // prop: the event is removed (unless it's 0), and the others are kept
+    let removed = event != 0 && events.remove(&event);
+    eset.remove_event(event) == removed && eset.0 == events
+}
+
+#[quickcheck]
+fn remove_event_range(events: Vec<u8>, start: u8, end: u8) -> bool {
+    let (mut eset, mut events) = naive(events);
+    let (start, end) = (start as u64 % 32, end as u64 % 32);
+
+    // prop: the events in the range are removed, and the others are kept
+    let before = events.len();
+    events.retain(|event| *event < start || *event > end);
+    let removed = events.len() < before;
+    eset.remove_event_range(start, end) == removed && eset.0 == events
+}
+
+#[quickcheck]
+fn truncate(events: Vec<u8>, max_event: u8) -> bool {
+    let (mut eset, mut events) = naive(events);
+    let max_event = max_event as u64 % 32;
+    eset.truncate(max_event);
+
+    // prop: only the events up to `max_event` are kept
+    events.retain(|event| *event <= max_event);
+    eset.0 == events
+}
+
+#[test]
+fn kind() {
+    let clock = Clock::from(vec![("A", NaiveSet::from_events(vec![1, 3]))]);
+    assert!(format!("{:?}", clock).starts_with("Clock {"));
+}
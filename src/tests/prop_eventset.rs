@@ -1,187 +1,1203 @@
 use crate::*;
 use quickcheck::TestResult;
 use quickcheck_macros::quickcheck;
+use std::cmp;
 use std::collections::BTreeSet;
 use std::iter::FromIterator;
 
 #[quickcheck]
-fn add_event_above_exset(event: u64, events: BTreeSet<u64>) -> TestResult {
+fn add_event_above_exset(event: Event, events: BTreeSet<u64>) -> TestResult {
     check_add_event::<AboveExSet>(event, events)
 }
 
 #[quickcheck]
-fn add_event_above_range_set(event: u64, events: BTreeSet<u64>) -> TestResult {
+fn add_event_above_range_set(
+    event: Event,
+    events: BTreeSet<u64>,
+) -> TestResult {
     check_add_event::<AboveRangeSet>(event, events)
 }
 
 #[quickcheck]
-fn add_event_below_exset(event: u64, events: BTreeSet<u64>) -> TestResult {
-    check_add_event::<BelowExSet>(event, events)
+fn add_event_below_exset(event: Event, events: BTreeSet<u64>) -> TestResult {
+    check_add_event::<BelowExSet>(event, events)
+}
+
+#[quickcheck]
+fn add_event_below_range_set(
+    event: Event,
+    events: BTreeSet<u64>,
+) -> TestResult {
+    check_add_event::<BelowRangeSet>(event, events)
+}
+
+#[quickcheck]
+fn add_event_explicit_set(event: Event, events: BTreeSet<u64>) -> TestResult {
+    check_add_event::<ExplicitSet>(event, events)
+}
+
+#[quickcheck]
+fn add_event_range_above_exset(
+    start: Event,
+    end: Event,
+    events: BTreeSet<u64>,
+) -> TestResult {
+    check_add_event_range::<AboveExSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_event_range_above_range_set(
+    start: Event,
+    end: Event,
+    events: BTreeSet<u64>,
+) -> TestResult {
+    check_add_event_range::<AboveRangeSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_event_range_below_exset(
+    start: Event,
+    end: Event,
+    events: BTreeSet<u64>,
+) -> TestResult {
+    check_add_event_range::<BelowExSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_event_range_below_range_set(
+    start: Event,
+    end: Event,
+    events: BTreeSet<u64>,
+) -> TestResult {
+    check_add_event_range::<BelowRangeSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_event_range_explicit_set(
+    start: Event,
+    end: Event,
+    events: BTreeSet<u64>,
+) -> TestResult {
+    check_add_event_range::<ExplicitSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_empty_event_range_max_set(
+    start: u64,
+    end: u64,
+    events: Vec<u64>,
+) -> bool {
+    check_add_empty_event_range::<MaxSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_empty_event_range_above_exset(
+    start: u64,
+    end: u64,
+    events: Vec<u64>,
+) -> bool {
+    check_add_empty_event_range::<AboveExSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_empty_event_range_above_range_set(
+    start: u64,
+    end: u64,
+    events: Vec<u64>,
+) -> bool {
+    check_add_empty_event_range::<AboveRangeSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_empty_event_range_below_exset(
+    start: u64,
+    end: u64,
+    events: Vec<u64>,
+) -> bool {
+    check_add_empty_event_range::<BelowExSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_empty_event_range_below_range_set(
+    start: u64,
+    end: u64,
+    events: Vec<u64>,
+) -> bool {
+    check_add_empty_event_range::<BelowRangeSet>(start, end, events)
+}
+
+#[quickcheck]
+fn add_empty_event_range_explicit_set(
+    start: u64,
+    end: u64,
+    events: Vec<u64>,
+) -> bool {
+    check_add_empty_event_range::<ExplicitSet>(start, end, events)
+}
+
+#[quickcheck]
+fn is_empty_max_set(events: Vec<u64>) -> bool {
+    check_is_empty::<MaxSet>(events)
+}
+
+#[quickcheck]
+fn is_empty_above_exset(events: Vec<u64>) -> bool {
+    check_is_empty::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn is_empty_above_range_set(events: Vec<u64>) -> bool {
+    check_is_empty::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn is_empty_below_exset(events: Vec<u64>) -> bool {
+    check_is_empty::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn is_empty_below_range_set(events: Vec<u64>) -> bool {
+    check_is_empty::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn is_empty_explicit_set(events: Vec<u64>) -> bool {
+    check_is_empty::<ExplicitSet>(events)
+}
+
+#[quickcheck]
+fn events_sorted_max_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_events_sorted::<MaxSet>(events, remove)
+}
+
+#[quickcheck]
+fn events_sorted_above_exset(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_events_sorted::<AboveExSet>(events, remove)
+}
+
+#[quickcheck]
+fn events_sorted_above_range_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_events_sorted::<AboveRangeSet>(events, remove)
+}
+
+#[quickcheck]
+fn events_sorted_below_exset(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_events_sorted::<BelowExSet>(events, remove)
+}
+
+#[quickcheck]
+fn events_sorted_below_range_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_events_sorted::<BelowRangeSet>(events, remove)
+}
+
+#[quickcheck]
+fn events_sorted_explicit_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_events_sorted::<ExplicitSet>(events, remove)
+}
+
+#[quickcheck]
+fn add_event_delivered_max_set(events: Vec<u64>) -> bool {
+    check_add_event_delivered::<MaxSet>(events)
+}
+
+#[quickcheck]
+fn add_event_delivered_above_exset(events: Vec<u64>) -> bool {
+    check_add_event_delivered::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn add_event_delivered_above_range_set(events: Vec<u64>) -> bool {
+    check_add_event_delivered::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn add_event_delivered_below_exset(events: Vec<u64>) -> bool {
+    check_add_event_delivered::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn add_event_delivered_below_range_set(events: Vec<u64>) -> bool {
+    check_add_event_delivered::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn add_event_delivered_explicit_set(events: Vec<u64>) -> bool {
+    check_add_event_delivered::<ExplicitSet>(events)
+}
+
+#[quickcheck]
+fn range_iter_max_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_range_iter::<MaxSet>(events, remove)
+}
+
+#[quickcheck]
+fn range_iter_above_exset(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_range_iter::<AboveExSet>(events, remove)
+}
+
+#[quickcheck]
+fn range_iter_above_range_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_range_iter::<AboveRangeSet>(events, remove)
+}
+
+#[quickcheck]
+fn range_iter_below_exset(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_range_iter::<BelowExSet>(events, remove)
+}
+
+#[quickcheck]
+fn range_iter_below_range_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_range_iter::<BelowRangeSet>(events, remove)
+}
+
+#[quickcheck]
+fn range_iter_explicit_set(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    check_range_iter::<ExplicitSet>(events, remove)
+}
+
+#[quickcheck]
+fn join_mut_max_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join_mut::<MaxSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_mut_above_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join_mut::<AboveExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_mut_above_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join_mut::<AboveRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_mut_below_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join_mut::<BelowExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_mut_below_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join_mut::<BelowRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_mut_explicit_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join_mut::<ExplicitSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn is_event_max_set(events: Vec<u64>) -> bool {
+    check_is_event::<MaxSet>(events)
+}
+
+#[quickcheck]
+fn is_event_above_exset(events: Vec<u64>) -> bool {
+    check_is_event::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn is_event_above_range_set(events: Vec<u64>) -> bool {
+    check_is_event::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn is_event_below_exset(events: Vec<u64>) -> bool {
+    check_is_event::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn is_event_below_range_set(events: Vec<u64>) -> bool {
+    check_is_event::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn is_event_explicit_set(events: Vec<u64>) -> bool {
+    check_is_event::<ExplicitSet>(events)
+}
+
+#[quickcheck]
+fn join_max_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join::<MaxSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_above_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join::<AboveExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_above_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join::<AboveRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_below_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join::<BelowExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_below_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join::<BelowRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn meet_above_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_meet::<AboveExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn meet_below_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_meet::<BelowRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn join_explicit_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_join::<ExplicitSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn is_event_max_set_u32(events: Vec<u32>) -> bool {
+    check_is_event::<MaxSetOf<u32>>(widen(events))
+}
+
+#[quickcheck]
+fn join_max_set_u32(events_a: Vec<u32>, events_b: Vec<u32>) -> bool {
+    check_join::<MaxSetOf<u32>>(widen(events_a), widen(events_b))
+}
+
+#[quickcheck]
+fn max_set_u32(events_a: Vec<u32>, events_b: Vec<u32>) -> bool {
+    let (events_a, events_b) = (widen(events_a), widen(events_b));
+    let mut narrow = MaxSetOf::<u32>::from_events(events_a.clone());
+    let mut wide = MaxSet::from_events(events_a);
+    narrow.join(&MaxSetOf::from_events(events_b.clone()));
+    wide.join(&MaxSet::from_events(events_b));
+
+    // prop: storing events as `u32`s doesn't change the events in the set
+    narrow.events() == wide.events() && narrow.iter().eq(wide.iter())
+}
+
+#[quickcheck]
+fn frontier_maxset(events: BTreeSet<u64>) -> bool {
+    let eset = MaxSet::from_events(events.clone());
+    let frontier = events.into_iter().next_back().unwrap_or(0);
+    eset.frontier() == frontier
+}
+
+#[quickcheck]
+fn frontier_above_exset(events: BTreeSet<u64>) -> TestResult {
+    check_frontier::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn frontier_above_range_set(events: BTreeSet<u64>) -> TestResult {
+    check_frontier::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn frontier_below_exset(events: BTreeSet<u64>) -> TestResult {
+    check_frontier::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn frontier_below_range_set(events: BTreeSet<u64>) -> TestResult {
+    check_frontier::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn frontier_explicit_set(events: BTreeSet<u64>) -> TestResult {
+    check_frontier::<ExplicitSet>(events)
+}
+
+#[quickcheck]
+fn subtract_maxset(events: BTreeSet<u64>, subtract: BTreeSet<u64>) -> bool {
+    check_subtract_maxset(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_above_exset_from_above_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<AboveExSet, AboveExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_above_range_set_from_above_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<AboveRangeSet, AboveRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_above_exset_from_below_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<AboveExSet, BelowExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_above_exset_from_below_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<AboveExSet, BelowRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_above_range_set_from_below_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<AboveRangeSet, BelowExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_above_range_set_from_below_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<AboveRangeSet, BelowRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_exset_from_above_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowExSet, AboveExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_range_set_from_above_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowRangeSet, AboveExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_exset_from_above_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowExSet, AboveRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_range_set_from_above_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowRangeSet, AboveRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_exset_from_below_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowExSet, BelowExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_range_set_from_below_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowRangeSet, BelowRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_exset_from_below_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowExSet, BelowRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_below_range_set_from_below_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<BelowRangeSet, BelowExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtract_explicit_set_from_explicit_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtract::<ExplicitSet, ExplicitSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_max_set(events: BTreeSet<u64>, subtract: BTreeSet<u64>) -> bool {
+    check_subtracted::<MaxSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_above_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted::<AboveExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_above_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted::<AboveRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_ranges_above_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    // create event sets
+    let eset = AboveRangeSet::from_events(events);
+    let subtract = AboveRangeSet::from_events(subtract);
+
+    // compute expected by enumerating the events
+    let expected: Vec<_> =
+        crate::subtract_iter(eset.clone(), subtract.clone()).collect();
+
+    let ranges = eset.subtracted_ranges(&subtract);
+
+    // ranges should be sorted, disjoint and non-adjacent
+    let disjoint = ranges
+        .windows(2)
+        .all(|pair| pair[0].0 <= pair[0].1 && pair[0].1 + 1 < pair[1].0);
+    let events: Vec<_> = ranges
+        .into_iter()
+        .flat_map(|(start, end)| start..=end)
+        .collect();
+    disjoint && events == expected
+}
+
+#[quickcheck]
+fn subtracted_below_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted::<BelowExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_below_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted::<BelowRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_explicit_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted::<ExplicitSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_count_max_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted_count::<MaxSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_count_above_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted_count::<AboveExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_count_above_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted_count::<AboveRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_count_below_exset(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted_count::<BelowExSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_count_below_range_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted_count::<BelowRangeSet>(events, subtract)
+}
+
+#[quickcheck]
+fn subtracted_count_explicit_set(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    check_subtracted_count::<ExplicitSet>(events, subtract)
+}
+
+#[quickcheck]
+fn count_events_in_range_max_set(
+    events: BTreeSet<u64>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_count_events_in_range::<MaxSet>(events, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn count_events_in_range_above_exset(
+    events: BTreeSet<u64>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_count_events_in_range::<AboveExSet>(events, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn count_events_in_range_above_range_set(
+    events: BTreeSet<u64>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_count_events_in_range::<AboveRangeSet>(
+        events,
+        start as u64,
+        end as u64,
+    )
+}
+
+#[quickcheck]
+fn count_events_in_range_below_exset(
+    events: BTreeSet<u64>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_count_events_in_range::<BelowExSet>(events, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn count_events_in_range_below_range_set(
+    events: BTreeSet<u64>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_count_events_in_range::<BelowRangeSet>(
+        events,
+        start as u64,
+        end as u64,
+    )
+}
+
+#[quickcheck]
+fn count_events_in_range_explicit_set(
+    events: BTreeSet<u64>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_count_events_in_range::<ExplicitSet>(events, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn leq_max_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_leq::<MaxSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn leq_above_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_leq::<AboveExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn leq_above_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_leq::<AboveRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn leq_below_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_leq::<BelowExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn leq_below_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_leq::<BelowRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn leq_explicit_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_leq::<ExplicitSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn model_above_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_model::<AboveExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn model_above_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_model::<AboveRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn model_below_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_model::<BelowExSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn model_below_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    check_model::<BelowRangeSet>(events_a, events_b)
+}
+
+#[quickcheck]
+fn remove_event_max_set(events: BTreeSet<u64>, remove: Vec<u64>) -> bool {
+    // removing an event from a `MaxSet` also removes all events above it
+    check_remove_event::<MaxSet, _>(events, remove, |model, event| {
+        !model.split_off(&event).is_empty()
+    })
+}
+
+#[quickcheck]
+fn remove_event_above_exset(events: BTreeSet<u64>, remove: Vec<u64>) -> bool {
+    check_remove_event::<AboveExSet, _>(events, remove, |model, event| {
+        model.remove(&event)
+    })
+}
+
+#[quickcheck]
+fn remove_event_above_range_set(
+    events: BTreeSet<u64>,
+    remove: Vec<u64>,
+) -> bool {
+    check_remove_event::<AboveRangeSet, _>(events, remove, |model, event| {
+        model.remove(&event)
+    })
+}
+
+#[quickcheck]
+fn remove_event_below_exset(events: BTreeSet<u64>, remove: Vec<u64>) -> bool {
+    check_remove_event::<BelowExSet, _>(events, remove, |model, event| {
+        model.remove(&event)
+    })
+}
+
+#[quickcheck]
+fn remove_event_below_range_set(
+    events: BTreeSet<u64>,
+    remove: Vec<u64>,
+) -> bool {
+    check_remove_event::<BelowRangeSet, _>(events, remove, |model, event| {
+        model.remove(&event)
+    })
+}
+
+#[quickcheck]
+fn remove_event_explicit_set(events: BTreeSet<u64>, remove: Vec<u64>) -> bool {
+    check_remove_event::<ExplicitSet, _>(events, remove, |model, event| {
+        model.remove(&event)
+    })
+}
+
+#[quickcheck]
+fn remove_event_range_max_set(
+    events: BTreeSet<u64>,
+    ranges: Vec<(u64, u64)>,
+) -> bool {
+    // removing a range from a `MaxSet` also removes all events above it
+    check_remove_event_range::<MaxSet, _>(
+        events,
+        ranges,
+        |model, start, end| start <= end && !model.split_off(&start).is_empty(),
+    )
+}
+
+#[quickcheck]
+fn remove_event_range_above_exset(
+    events: BTreeSet<u64>,
+    ranges: Vec<(u64, u64)>,
+) -> bool {
+    check_remove_event_range::<AboveExSet, _>(events, ranges, remove_range)
+}
+
+#[quickcheck]
+fn remove_event_range_above_range_set(
+    events: BTreeSet<u64>,
+    ranges: Vec<(u64, u64)>,
+) -> bool {
+    check_remove_event_range::<AboveRangeSet, _>(events, ranges, remove_range)
+}
+
+#[quickcheck]
+fn remove_event_range_below_exset(
+    events: BTreeSet<u64>,
+    ranges: Vec<(u64, u64)>,
+) -> bool {
+    check_remove_event_range::<BelowExSet, _>(events, ranges, remove_range)
+}
+
+#[quickcheck]
+fn remove_event_range_below_range_set(
+    events: BTreeSet<u64>,
+    ranges: Vec<(u64, u64)>,
+) -> bool {
+    check_remove_event_range::<BelowRangeSet, _>(events, ranges, remove_range)
+}
+
+#[quickcheck]
+fn remove_event_range_explicit_set(
+    events: BTreeSet<u64>,
+    ranges: Vec<(u64, u64)>,
+) -> bool {
+    check_remove_event_range::<ExplicitSet, _>(events, ranges, remove_range)
+}
+
+#[quickcheck]
+fn event_count_max_set(events: Vec<u64>) -> bool {
+    check_event_count::<MaxSet>(events)
+}
+
+#[quickcheck]
+fn event_count_above_exset(events: Vec<u64>) -> bool {
+    check_event_count::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn event_count_above_range_set(events: Vec<u64>) -> bool {
+    check_event_count::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn event_count_below_exset(events: Vec<u64>) -> bool {
+    check_event_count::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn event_count_below_range_set(events: Vec<u64>) -> bool {
+    check_event_count::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn event_count_explicit_set(events: Vec<u64>) -> bool {
+    check_event_count::<ExplicitSet>(events)
+}
+
+#[quickcheck]
+fn canonical_hash_max_set(events: Vec<u64>) -> bool {
+    check_canonical_hash::<MaxSet>(events)
+}
+
+#[quickcheck]
+fn canonical_hash_above_exset(events: Vec<u64>) -> bool {
+    check_canonical_hash::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn canonical_hash_above_range_set(events: Vec<u64>) -> bool {
+    check_canonical_hash::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn canonical_hash_below_exset(events: Vec<u64>) -> bool {
+    check_canonical_hash::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn canonical_hash_below_range_set(events: Vec<u64>) -> bool {
+    check_canonical_hash::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn canonical_hash_explicit_set(events: Vec<u64>) -> bool {
+    check_canonical_hash::<ExplicitSet>(events)
+}
+
+#[quickcheck]
+fn contains_all_range_max_set(
+    ranges: Vec<(u8, u8)>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_contains_all_range::<MaxSet>(ranges, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn contains_all_range_above_exset(
+    ranges: Vec<(u8, u8)>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_contains_all_range::<AboveExSet>(ranges, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn contains_all_range_above_range_set(
+    ranges: Vec<(u8, u8)>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_contains_all_range::<AboveRangeSet>(ranges, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn contains_all_range_below_exset(
+    ranges: Vec<(u8, u8)>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_contains_all_range::<BelowExSet>(ranges, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn contains_all_range_below_range_set(
+    ranges: Vec<(u8, u8)>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_contains_all_range::<BelowRangeSet>(ranges, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn contains_all_range_explicit_set(
+    ranges: Vec<(u8, u8)>,
+    start: u8,
+    end: u8,
+) -> bool {
+    check_contains_all_range::<ExplicitSet>(ranges, start as u64, end as u64)
+}
+
+#[quickcheck]
+fn max_event_max_set(events: Vec<u64>) -> bool {
+    check_max_event::<MaxSet>(events)
+}
+
+#[quickcheck]
+fn max_event_above_exset(events: Vec<u64>) -> bool {
+    check_max_event::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn max_event_above_range_set(events: Vec<u64>) -> bool {
+    check_max_event::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn max_event_below_exset(events: Vec<u64>) -> bool {
+    check_max_event::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn max_event_below_range_set(events: Vec<u64>) -> bool {
+    check_max_event::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn max_event_explicit_set(events: Vec<u64>) -> bool {
+    check_max_event::<ExplicitSet>(events)
+}
+
+#[quickcheck]
+fn iter_max_set(events: Vec<u64>) -> bool {
+    check_iter::<MaxSet>(events)
+}
+
+#[quickcheck]
+fn iter_above_exset(events: Vec<u64>) -> bool {
+    check_iter::<AboveExSet>(events)
+}
+
+#[quickcheck]
+fn iter_above_range_set(events: Vec<u64>) -> bool {
+    check_iter::<AboveRangeSet>(events)
+}
+
+#[quickcheck]
+fn iter_below_exset(events: Vec<u64>) -> bool {
+    check_iter::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn iter_below_range_set(events: Vec<u64>) -> bool {
+    check_iter::<BelowRangeSet>(events)
 }
 
 #[quickcheck]
-fn add_event_range_above_exset(
-    start: u64,
-    end: u64,
-    events: BTreeSet<u64>,
-) -> TestResult {
-    check_add_event_range::<AboveExSet>(start, end, events)
+fn iter_explicit_set(events: Vec<u64>) -> bool {
+    check_iter::<ExplicitSet>(events)
 }
 
 #[quickcheck]
-fn add_event_range_above_range_set(
-    start: u64,
-    end: u64,
-    events: BTreeSet<u64>,
-) -> TestResult {
-    check_add_event_range::<AboveRangeSet>(start, end, events)
+fn rev_event_iter_max_set(events: Vec<u64>, directions: Vec<bool>) -> bool {
+    check_rev_event_iter::<MaxSet>(events, directions)
 }
 
 #[quickcheck]
-fn add_event_range_below_exset(
-    start: u64,
-    end: u64,
-    events: BTreeSet<u64>,
-) -> TestResult {
-    check_add_event_range::<BelowExSet>(start, end, events)
+fn rev_event_iter_above_exset(events: Vec<u64>, directions: Vec<bool>) -> bool {
+    check_rev_event_iter::<AboveExSet>(events, directions)
 }
 
 #[quickcheck]
-fn is_event_max_set(events: Vec<u64>) -> bool {
-    check_is_event::<MaxSet>(events)
+fn rev_event_iter_above_range_set(
+    events: Vec<u64>,
+    directions: Vec<bool>,
+) -> bool {
+    check_rev_event_iter::<AboveRangeSet>(events, directions)
 }
 
 #[quickcheck]
-fn is_event_above_exset(events: Vec<u64>) -> bool {
-    check_is_event::<AboveExSet>(events)
+fn rev_event_iter_below_exset(events: Vec<u64>, directions: Vec<bool>) -> bool {
+    check_rev_event_iter::<BelowExSet>(events, directions)
 }
 
 #[quickcheck]
-fn is_event_above_range_set(events: Vec<u64>) -> bool {
-    check_is_event::<AboveRangeSet>(events)
+fn rev_event_iter_below_range_set(
+    events: Vec<u64>,
+    directions: Vec<bool>,
+) -> bool {
+    check_rev_event_iter::<BelowRangeSet>(events, directions)
 }
 
 #[quickcheck]
-fn is_event_below_exset(events: Vec<u64>) -> bool {
-    check_is_event::<BelowExSet>(events)
+fn rev_event_iter_explicit_set(
+    events: Vec<u64>,
+    directions: Vec<bool>,
+) -> bool {
+    check_rev_event_iter::<ExplicitSet>(events, directions)
 }
 
 #[quickcheck]
-fn join_max_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
-    check_join::<MaxSet>(events_a, events_b)
+fn truncate_max_set(events: Vec<u64>, max_event: u64) -> bool {
+    check_truncate::<MaxSet>(events, max_event)
 }
 
 #[quickcheck]
-fn join_above_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
-    check_join::<AboveExSet>(events_a, events_b)
+fn truncate_above_exset(events: Vec<u64>, max_event: u64) -> bool {
+    check_truncate::<AboveExSet>(events, max_event)
 }
 
 #[quickcheck]
-fn join_above_range_set(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
-    check_join::<AboveRangeSet>(events_a, events_b)
+fn truncate_above_range_set(events: Vec<u64>, max_event: u64) -> bool {
+    check_truncate::<AboveRangeSet>(events, max_event)
 }
 
 #[quickcheck]
-fn join_below_exset(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
-    check_join::<BelowExSet>(events_a, events_b)
+fn truncate_below_exset(events: Vec<u64>, max_event: u64) -> bool {
+    check_truncate::<BelowExSet>(events, max_event)
 }
 
 #[quickcheck]
-fn frontier_maxset(events: BTreeSet<u64>) -> bool {
-    let eset = MaxSet::from_events(events.clone());
-    let frontier = events.into_iter().last().unwrap_or(0);
-    eset.frontier() == frontier
+fn truncate_below_range_set(events: Vec<u64>, max_event: u64) -> bool {
+    check_truncate::<BelowRangeSet>(events, max_event)
 }
 
 #[quickcheck]
-fn frontier_above_exset(events: BTreeSet<u64>) -> TestResult {
-    check_frontier::<AboveExSet>(events)
+fn truncate_explicit_set(events: Vec<u64>, max_event: u64) -> bool {
+    check_truncate::<ExplicitSet>(events, max_event)
 }
 
 #[quickcheck]
-fn frontier_above_range_set(events: BTreeSet<u64>) -> TestResult {
-    check_frontier::<AboveRangeSet>(events)
+fn size_hint_max_set(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let eset = MaxSet::from_events(events.into_iter().filter(|e| *e != 0));
+    let len = eset.event_count() as usize;
+    check_size_hint(eset.iter(), len) && check_size_hint(eset.event_iter(), len)
 }
 
 #[quickcheck]
-fn frontier_below_exset(events: BTreeSet<u64>) -> TestResult {
-    check_frontier::<BelowExSet>(events)
+fn size_hint_above_exset(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let eset = AboveExSet::from_events(events.into_iter().filter(|e| *e != 0));
+    let len = eset.event_count() as usize;
+    check_size_hint(eset.iter(), len) && check_size_hint(eset.event_iter(), len)
 }
 
 #[quickcheck]
-fn subtract_maxset(events: BTreeSet<u64>, subtract: BTreeSet<u64>) -> bool {
-    check_subtract_maxset(events, subtract)
+fn size_hint_above_range_set(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let eset =
+        AboveRangeSet::from_events(events.into_iter().filter(|e| *e != 0));
+    let len = eset.event_count() as usize;
+    check_size_hint(eset.iter(), len) && check_size_hint(eset.event_iter(), len)
 }
 
 #[quickcheck]
-fn subtract_above_exset_from_above_exset(
-    events: BTreeSet<u64>,
-    subtract: BTreeSet<u64>,
-) -> bool {
-    check_subtract::<AboveExSet, AboveExSet>(events, subtract)
+fn size_hint_below_exset(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let eset = BelowExSet::from_events(events.into_iter().filter(|e| *e != 0));
+    let len = eset.event_count() as usize;
+    check_size_hint(eset.iter(), len) && check_size_hint(eset.event_iter(), len)
 }
 
 #[quickcheck]
-fn subtract_above_range_set_from_above_range_set(
-    events: BTreeSet<u64>,
-    subtract: BTreeSet<u64>,
-) -> bool {
-    check_subtract::<AboveRangeSet, AboveRangeSet>(events, subtract)
+fn size_hint_below_range_set(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let eset =
+        BelowRangeSet::from_events(events.into_iter().filter(|e| *e != 0));
+    let len = eset.event_count() as usize;
+    check_size_hint(eset.iter(), len) && check_size_hint(eset.event_iter(), len)
 }
 
 #[quickcheck]
-fn subtract_above_exset_from_below_exset(
-    events: BTreeSet<u64>,
-    subtract: BTreeSet<u64>,
-) -> bool {
-    check_subtract::<AboveExSet, BelowExSet>(events, subtract)
+fn size_hint_explicit_set(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let eset = ExplicitSet::from_events(events.into_iter().filter(|e| *e != 0));
+    let len = eset.event_count() as usize;
+    check_size_hint(eset.iter(), len) && check_size_hint(eset.event_iter(), len)
 }
 
 #[quickcheck]
-fn subtract_above_range_set_from_below_exset(
-    events: BTreeSet<u64>,
-    subtract: BTreeSet<u64>,
-) -> bool {
-    check_subtract::<AboveRangeSet, BelowExSet>(events, subtract)
+fn from_sorted_events_max_set(events: Vec<u64>) -> bool {
+    check_from_sorted_events::<MaxSet>(events)
 }
 
 #[quickcheck]
-fn subtract_below_exset_from_above_exset(
-    events: BTreeSet<u64>,
-    subtract: BTreeSet<u64>,
-) -> bool {
-    check_subtract::<BelowExSet, AboveExSet>(events, subtract)
+fn from_sorted_events_above_exset(events: Vec<u64>) -> bool {
+    check_from_sorted_events::<AboveExSet>(events)
 }
 
 #[quickcheck]
-fn subtract_below_exset_from_above_range_set(
-    events: BTreeSet<u64>,
-    subtract: BTreeSet<u64>,
-) -> bool {
-    check_subtract::<BelowExSet, AboveRangeSet>(events, subtract)
+fn from_sorted_events_above_range_set(events: Vec<u64>) -> bool {
+    check_from_sorted_events::<AboveRangeSet>(events)
 }
 
 #[quickcheck]
-fn subtract_below_exset_from_below_exset(
-    events: BTreeSet<u64>,
-    subtract: BTreeSet<u64>,
-) -> bool {
-    check_subtract::<BelowExSet, BelowExSet>(events, subtract)
+fn from_sorted_events_below_exset(events: Vec<u64>) -> bool {
+    check_from_sorted_events::<BelowExSet>(events)
+}
+
+#[quickcheck]
+fn from_sorted_events_below_range_set(events: Vec<u64>) -> bool {
+    check_from_sorted_events::<BelowRangeSet>(events)
+}
+
+#[quickcheck]
+fn from_sorted_events_explicit_set(events: Vec<u64>) -> bool {
+    check_from_sorted_events::<ExplicitSet>(events)
 }
 
 // TODO this test currently will fail with `MaxSet` due to its special semantics
 // (events do not need to be added to be part of the set)
 fn check_add_event<E: EventSet>(
-    event: u64,
+    Event(event): Event,
     mut events: BTreeSet<u64>,
 ) -> TestResult {
-    // event 0 is not allowed
-    if event == 0 {
-        return TestResult::discard();
-    }
-
     // prune all events from `events` that are higher than `event`
-    events = events.into_iter().filter(|&e| e > event).collect();
+    events.retain(|&e| e > event);
 
     // create event set from events
     let mut eset = E::from_events(events.clone());
@@ -214,30 +1230,32 @@ fn check_add_event<E: EventSet>(
 }
 
 fn check_add_event_range<E: EventSet>(
-    start: u64,
-    end: u64,
+    Event(a): Event,
+    Event(b): Event,
     mut events: BTreeSet<u64>,
 ) -> TestResult {
-    // event 0 and invalid ranges are not allowed
-    if start == 0 || end == 0 || start > end {
-        return TestResult::discard();
-    }
+    // order both ends so that the range is valid
+    let (start, end) = (std::cmp::min(a, b), std::cmp::max(a, b));
 
-    // prune all events from `events` that are part of the range to be added
-    events = events
-        .into_iter()
-        .filter(|&e| e < start || e > end)
-        .collect();
+    // 0 is never an event
+    events.remove(&0);
 
-    // create event set from events
+    // create event set from events (which may overlap with the range)
     let mut eset = E::from_events(events.clone());
 
     // add event range to eset
-    eset.add_event_range(start, end);
+    let added = eset.add_event_range(start, end);
 
     // also add event range to `events`
+    let new = (start..=end).any(|event| !events.contains(&event));
     events.extend(BTreeSet::from_iter(start..=end));
 
+    // the result is `true` iff some event in the range is new, and each event
+    // is iterated exactly once
+    if added != new || !eset.iter().eq(events.iter().cloned()) {
+        return TestResult::failed();
+    }
+
     // check that only the initial events and the added event are events now
     let highest_event = events.iter().last().unwrap();
     let res = (1..highest_event + 10).all(|event| {
@@ -253,6 +1271,312 @@ fn check_add_event_range<E: EventSet>(
     TestResult::from_bool(res)
 }
 
+// Checks `remove_event` against a `BTreeSet` oracle, updated with
+// `model_remove`.
+fn check_remove_event<E, F>(
+    mut events: BTreeSet<u64>,
+    remove: Vec<u64>,
+    mut model_remove: F,
+) -> bool
+where
+    E: EventSet,
+    F: FnMut(&mut BTreeSet<u64>, u64) -> bool,
+{
+    // 0's are not allowed as events
+    events.remove(&0);
+
+    // create event set and its model
+    let mut eset = E::from_events(events.clone());
+    let mut model: BTreeSet<_> = eset.iter().collect();
+
+    remove.into_iter().all(|event| {
+        let removed = eset.remove_event(event);
+        let model_removed = event != 0 && model_remove(&mut model, event);
+
+        let mut frontier = 0;
+        while model.contains(&(frontier + 1)) {
+            frontier += 1;
+        }
+
+        removed == model_removed
+            && eset.frontier() == frontier
+            && eset.iter().collect::<BTreeSet<_>>() == model
+    })
+}
+
+// Removes the events within `[start, end]` from the `BTreeSet` oracle.
+fn remove_range(model: &mut BTreeSet<u64>, start: u64, end: u64) -> bool {
+    let len = model.len();
+    model.retain(|event| *event < start || *event > end);
+    model.len() < len
+}
+
+// Checks `remove_event_range` against a `BTreeSet` oracle, updated with
+// `model_remove`. The ranges removed often straddle the highest contiguous
+// event, since events (and ranges) are generated from the same small domain.
+fn check_remove_event_range<E, F>(
+    mut events: BTreeSet<u64>,
+    ranges: Vec<(u64, u64)>,
+    mut model_remove: F,
+) -> bool
+where
+    E: EventSet,
+    F: FnMut(&mut BTreeSet<u64>, u64, u64) -> bool,
+{
+    // 0's are not allowed as events
+    events.remove(&0);
+
+    // create event set and its model
+    let mut eset = E::from_events(events.clone());
+    let mut model: BTreeSet<_> = eset.iter().collect();
+
+    ranges.into_iter().all(|(start, end)| {
+        let removed = eset.remove_event_range(start, end);
+        let model_removed = model_remove(&mut model, start.max(1), end);
+
+        let mut frontier = 0;
+        while model.contains(&(frontier + 1)) {
+            frontier += 1;
+        }
+
+        removed == model_removed
+            && eset.frontier() == frontier
+            && eset.iter().collect::<BTreeSet<_>>() == model
+    })
+}
+
+fn check_event_count<E: EventSet>(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let events = events.into_iter().filter(|event| *event != 0);
+    let eset = E::from_events(events);
+    eset.event_count() == eset.iter().count() as u64
+        && eset.event_count() == eset.clone().event_iter().count() as u64
+        && eset.is_empty() == (eset.event_count() == 0)
+}
+
+fn check_canonical_hash<E: EventSet>(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let events: Vec<_> =
+        events.into_iter().filter(|event| *event != 0).collect();
+
+    // build the same set in different orders, and by joining its halves
+    let in_order = E::from_events(events.clone());
+    let reversed = E::from_events(events.iter().rev().cloned());
+    let (left, right) = events.split_at(events.len() / 2);
+    let mut joined = E::from_events(right.to_vec());
+    joined.join(&E::from_events(left.to_vec()));
+
+    let hash = in_order.canonical_hash();
+    hash == reversed.canonical_hash() && hash == joined.canonical_hash()
+}
+
+fn check_max_event<E: EventSet>(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let events = events.into_iter().filter(|event| *event != 0);
+    let eset = E::from_events(events);
+    eset.max_event() == eset.iter().max().unwrap_or(0)
+}
+
+fn check_iter<E: EventSet>(events: Vec<u64>) -> bool {
+    // 0's are not allowed as events
+    let events = events.into_iter().filter(|event| *event != 0);
+    let eset = E::from_events(events);
+
+    // prop: borrowing and consuming iterators return the same events
+    eset.iter().collect::<Vec<_>>()
+        == eset.clone().event_iter().collect::<Vec<_>>()
+}
+
+fn check_rev_event_iter<E: EventSet>(
+    events: Vec<u64>,
+    directions: Vec<bool>,
+) -> bool
+where
+    E::EventIter: DoubleEndedIterator + ExactSizeIterator,
+{
+    // 0's are not allowed as events
+    let events = events.into_iter().filter(|event| *event != 0);
+    let eset = E::from_events(events);
+    let forward: Vec<_> = eset.clone().event_iter().collect();
+
+    // prop: iterating backwards is the same as reversing the forward events
+    let mut reversed = forward.clone();
+    reversed.reverse();
+    let rev = eset.clone().event_iter().rev().collect::<Vec<_>>() == reversed;
+
+    // prop: interleaving both directions (forward if `true`) yields each
+    // event once, and the length is always the number of events left
+    let len = forward.len();
+    let mut iter = eset.event_iter();
+    let mut front = Vec::new();
+    let mut back = Vec::new();
+    for forward in directions.into_iter().chain(std::iter::repeat(true)) {
+        if iter.len() != len - front.len() - back.len() {
+            return false;
+        }
+        let next = if forward {
+            iter.next()
+        } else {
+            iter.next_back()
+        };
+        match next {
+            Some(event) if forward => front.push(event),
+            Some(event) => back.push(event),
+            None => break,
+        }
+    }
+    back.reverse();
+    front.extend(back);
+
+    rev && front == forward
+}
+
+fn check_truncate<E: EventSet>(events: Vec<u64>, max_event: u64) -> bool {
+    // 0's are not allowed as events
+    let events = events.into_iter().filter(|event| *event != 0);
+    let eset = E::from_events(events);
+    let mut truncated = eset.clone();
+    truncated.truncate(max_event);
+
+    // prop: events up to `max_event` are kept, and the ones above are not
+    let highest = cmp::max(eset.max_event(), max_event) + 1;
+    (0..=highest).all(|event| {
+        truncated.is_event(event)
+            == (event <= max_event && eset.is_event(event))
+    })
+}
+
+fn check_size_hint<I>(mut iter: I, mut len: usize) -> bool
+where
+    I: ExactSizeIterator<Item = u64>,
+{
+    // prop: the length is always the number of events left
+    loop {
+        if iter.len() != len || iter.size_hint() != (len, Some(len)) {
+            return false;
+        }
+        if iter.next().is_none() {
+            return len == 0;
+        }
+        len -= 1;
+    }
+}
+
+fn check_from_sorted_events<E: EventSet>(mut events: Vec<u64>) -> bool {
+    events.sort_unstable();
+    let eset = E::from_events(events.clone());
+    let sorted = E::from_sorted_events(events);
+
+    // prop: both have the same events (`AboveRangeSet`s may split them into
+    // different ranges, and so they're not compared with `==`)
+    sorted.frontier() == eset.frontier() && sorted.iter().eq(eset.iter())
+}
+
+fn check_add_empty_event_range<E: EventSet>(
+    a: u64,
+    b: u64,
+    events: Vec<u64>,
+) -> bool {
+    // order both ends so that the range is empty
+    let (start, end) = (cmp::max(a, b), cmp::min(a, b));
+    if start == end {
+        return true;
+    }
+
+    let mut eset = E::from_events(events.clone());
+    let expected = eset.events();
+
+    // prop: adding an empty range is a no-op, both to an existing set and to
+    // a new one
+    !eset.add_event_range(start, end)
+        && eset.events() == expected
+        && E::from_event_range(start, end).is_empty()
+}
+
+// Converts `u32` events into `u64` events.
+fn widen(events: Vec<u32>) -> Vec<u64> {
+    events.into_iter().map(u64::from).collect()
+}
+
+fn check_is_empty<E: EventSet>(events: Vec<u64>) -> bool {
+    // 0's are not events
+    let events: Vec<_> =
+        events.into_iter().filter(|event| *event != 0).collect();
+    let mut eset = E::from_events(events.clone());
+    let all: Vec<_> = eset.iter().collect();
+
+    // prop: a set is empty iff it has no events, including when its events
+    // are removed
+    let emptied = eset.is_empty() == events.is_empty()
+        && all.into_iter().all(|event| {
+            eset.remove_event(event);
+            eset.is_empty() == (eset.iter().next().is_none())
+        });
+    E::new().is_empty() && emptied && eset.is_empty()
+}
+
+fn check_events_sorted<E: EventSet>(
+    events: Vec<u64>,
+    remove: Vec<u64>,
+) -> bool {
+    let mut eset = E::from_events(events);
+    remove.into_iter().for_each(|event| {
+        eset.remove_event(event);
+    });
+
+    // prop: the extras (or exceptions) are in strictly ascending order
+    let (_, extras) = eset.events();
+    extras.windows(2).all(|w| w[0] < w[1])
+}
+
+fn check_add_event_delivered<E: EventSet>(events: Vec<u64>) -> bool {
+    let mut eset = E::new();
+    let mut delivered = Vec::new();
+    for event in events {
+        if let Some((start, end)) = eset.add_event_delivered(event) {
+            delivered.extend(start..=end);
+        }
+    }
+
+    // prop: each event up to the frontier is delivered exactly once, and in
+    // order
+    delivered.into_iter().eq(1..=eset.frontier())
+}
+
+fn check_range_iter<E: EventSet>(events: Vec<u64>, remove: Vec<u64>) -> bool {
+    let mut eset = E::from_events(events);
+    remove.into_iter().for_each(|event| {
+        eset.remove_event(event);
+    });
+    let ranges: Vec<_> = eset.clone().range_iter().collect();
+
+    // rebuild the set from its ranges
+    let mut rebuilt = E::new();
+    for &(start, end) in ranges.iter() {
+        rebuilt.add_event_range(start, end);
+    }
+
+    // prop: ranges are sorted, valid, not adjacent, and have all events
+    ranges
+        .iter()
+        .all(|(start, end)| 1 <= *start && start <= end)
+        && ranges.windows(2).all(|w| w[0].1 + 1 < w[1].0)
+        && rebuilt.iter().eq(eset.iter())
+}
+
+fn check_join_mut<E: EventSet>(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    let eset_a = E::from_events(events_a);
+    let eset_b = E::from_events(events_b);
+    let mut joined = eset_a.clone();
+    let grew = joined.join_mut(&eset_b);
+    let mut expected = eset_a.clone();
+    expected.join(&eset_b);
+
+    // prop: `join_mut` is `join`, and it returns whether the set grew
+    joined.iter().eq(expected.iter())
+        && grew == (joined.event_count() > eset_a.event_count())
+}
+
 fn check_is_event<E: EventSet>(events: Vec<u64>) -> bool {
     let eset = E::from_events(events.clone());
     events.into_iter().all(|event| eset.is_event(event))
@@ -264,10 +1588,22 @@ fn check_join<E: EventSet>(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
     eset_a.join(&eset_b);
     events_a
         .into_iter()
-        .chain(events_b.into_iter())
+        .chain(events_b)
         .all(|event| eset_a.is_event(event))
 }
 
+fn check_meet<E: EventSet>(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    let mut eset = E::from_events(events_a.clone());
+    eset.meet(&E::from_events(events_b.clone()));
+
+    // the model keeps only the events in common (0 is not a valid event)
+    let mut model = ExplicitSet::from_events(events_a);
+    model.meet(&ExplicitSet::from_events(events_b));
+    model.remove_event(0);
+
+    eset.event_iter().eq(model.event_iter())
+}
+
 fn check_frontier<E: EventSet>(mut events: BTreeSet<u64>) -> TestResult {
     // 0's are not allowed as events
     events.remove(&0);
@@ -306,8 +1642,7 @@ fn check_subtract_maxset(
     let subtracted: Vec<_> = crate::subtract_iter(eset, subtract).collect();
 
     // create expected
-    let expected: Vec<_> =
-        ((max_subtract + 1)..=max_event).into_iter().collect();
+    let expected: Vec<_> = ((max_subtract + 1)..=max_event).collect();
 
     subtracted == expected
 }
@@ -335,3 +1670,137 @@ fn check_subtract<E: EventSet, S: EventSet>(
 
     subtracted == expected
 }
+
+fn check_subtracted<E: EventSet>(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    // create event sets
+    let eset = E::from_events(events);
+    let subtract = E::from_events(subtract);
+
+    // compute expected by enumerating the events
+    let expected: Vec<_> =
+        crate::subtract_iter(eset.clone(), subtract.clone()).collect();
+
+    // `subtracted` returns the events in no specific order
+    let mut subtracted = eset.subtracted(&subtract);
+    subtracted.sort_unstable();
+    subtracted == expected
+}
+
+fn check_subtracted_count<E: EventSet>(
+    events: BTreeSet<u64>,
+    subtract: BTreeSet<u64>,
+) -> bool {
+    // create event sets
+    let eset = E::from_events(events);
+    let subtract = E::from_events(subtract);
+
+    // compute expected by enumerating the events
+    let expected =
+        crate::subtract_iter(eset.clone(), subtract.clone()).count() as u64;
+
+    eset.subtracted_count(&subtract) == expected
+}
+
+fn check_contains_all_range<E: EventSet>(
+    ranges: Vec<(u8, u8)>,
+    start: u64,
+    end: u64,
+) -> bool {
+    // add (possibly overlapping) ranges of small events, so that the queried
+    // range is often contained; 0's are not allowed as events
+    let mut eset = E::new();
+    for (a, b) in ranges {
+        let lo = cmp::max(cmp::min(a, b), 1) as u64;
+        let hi = cmp::max(a, b) as u64;
+        if lo <= hi {
+            eset.add_event_range(lo, hi);
+        }
+    }
+
+    // compute expected by checking each event in the range
+    let expected = (start..=end).all(|event| eset.is_event(event));
+    eset.contains_all_range(start, end) == expected
+}
+
+fn check_count_events_in_range<E: EventSet>(
+    events: BTreeSet<u64>,
+    start: u64,
+    end: u64,
+) -> bool {
+    let eset = E::from_events(events);
+
+    // compute expected by checking each event in the range
+    let expected = (start..=end)
+        .filter(|event| *event > 0 && eset.is_event(*event))
+        .count() as u64;
+
+    eset.count_events_in_range(start, end) == expected
+}
+
+fn check_leq<E: EventSet + PartialOrd>(
+    events_a: Vec<u64>,
+    events_b: Vec<u64>,
+) -> bool {
+    // create event sets
+    let eset_a = E::from_events(events_a);
+    let eset_b = E::from_events(events_b);
+
+    // compute join
+    let mut join = eset_a.clone();
+    join.join(&eset_b);
+
+    // `a <= b` iff no event remains when `b` is subtracted from `a`
+    let subset =
+        crate::subtract_iter(eset_a.clone(), eset_b.clone()).count() == 0;
+
+    // the same order on `BTreeSet`s
+    let set_a: BTreeSet<_> = eset_a.iter().collect();
+    let set_b: BTreeSet<_> = eset_b.iter().collect();
+    // (sets with the same events are only `Equal` if they're `==`)
+    let expected = match (set_a.is_subset(&set_b), set_b.is_subset(&set_a)) {
+        (true, true) if eset_a == eset_b => Some(cmp::Ordering::Equal),
+        (true, true) => None,
+        (true, false) => Some(cmp::Ordering::Less),
+        (false, true) => Some(cmp::Ordering::Greater),
+        (false, false) => None,
+    };
+
+    eset_a.leq(&eset_a)
+        && eset_a.leq(&join)
+        && eset_b.leq(&join)
+        && eset_a <= join
+        && eset_a.leq(&eset_b) == subset
+        && (eset_a <= eset_b) == subset
+        && set_a.is_subset(&set_b) == subset
+        && eset_a.partial_cmp(&eset_b) == expected
+}
+
+// Checks that `E` behaves as the reference model `ExplicitSet`.
+fn check_model<E: EventSet>(events_a: Vec<u64>, events_b: Vec<u64>) -> bool {
+    // create event sets and their models
+    let eset_a = E::from_events(events_a.clone());
+    let eset_b = E::from_events(events_b.clone());
+    let model_a = ExplicitSet::from_events(events_a);
+    let model_b = ExplicitSet::from_events(events_b);
+
+    // compute joins
+    let mut join = eset_a.clone();
+    join.join(&eset_b);
+    let mut model_join = model_a.clone();
+    model_join.join(&model_b);
+
+    let same_events = |eset: E, model: ExplicitSet| {
+        eset.event_iter().collect::<Vec<_>>()
+            == model.event_iter().collect::<Vec<_>>()
+    };
+
+    eset_a.frontier() == model_a.frontier()
+        && eset_a.subtracted_count(&eset_b)
+            == model_a.subtracted_count(&model_b)
+        && join.frontier() == model_join.frontier()
+        && same_events(eset_a, model_a)
+        && same_events(join, model_join)
+}
@@ -0,0 +1,36 @@
+use crate::*;
+use quickcheck_macros::quickcheck;
+
+// `simulate_gossip` panics if the clocks don't converge
+const REPLICAS: usize = 4;
+const STEPS: usize = 50;
+
+#[quickcheck]
+fn vclock_gossip(seed: u64, loss_rate: u8) -> bool {
+    check_gossip::<MaxSet>(seed, loss_rate)
+}
+
+#[quickcheck]
+fn aeclock_gossip(seed: u64, loss_rate: u8) -> bool {
+    check_gossip::<AboveExSet>(seed, loss_rate)
+}
+
+#[quickcheck]
+fn arclock_gossip(seed: u64, loss_rate: u8) -> bool {
+    check_gossip::<AboveRangeSet>(seed, loss_rate)
+}
+
+#[quickcheck]
+fn beclock_gossip(seed: u64, loss_rate: u8) -> bool {
+    check_gossip::<BelowExSet>(seed, loss_rate)
+}
+
+fn check_gossip<E: EventSet>(seed: u64, loss_rate: u8) -> bool {
+    // loss rate within [0, 0.5)
+    let loss_rate = (loss_rate % 50) as f64 / 100.0;
+    let report =
+        testing::simulate_gossip::<E>(REPLICAS, STEPS, seed, loss_rate);
+
+    // prop: all events are generated, and no message is lost without loss
+    report.events == STEPS && (loss_rate > 0.0 || report.messages_lost == 0)
+}
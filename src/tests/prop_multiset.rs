@@ -1,6 +1,7 @@
 use crate::MultiSet;
 use quickcheck::TestResult;
 use quickcheck_macros::quickcheck;
+use std::collections::{BTreeMap, HashMap};
 
 #[quickcheck]
 fn singleton(x: String, y: String) -> TestResult {
@@ -24,7 +25,51 @@ fn add_and_count(l: Vec<(u64, u64)>, mset: MultiSet<u64, u64>) -> bool {
     // prop: count of the element increased after add by the number of
     // occurrences of that element in `l`
     l.iter()
-        .all(|(x, _)| new_mset.count(&x) == mset.count(&x) + count(&x, &l))
+        .all(|(x, _)| new_mset.count(x) == mset.count(x) + count(x, &l))
+}
+
+#[quickcheck]
+fn add_and_remove(l: Vec<(u64, u64)>, mset: MultiSet<u64, u64>) -> bool {
+    let mut new_mset = mset.clone();
+    new_mset.add(l.clone());
+    new_mset.remove(l);
+
+    // prop: removing what was added returns the multiset to its prior counts
+    // and threshold results (the generated multiset may have elements with
+    // a zero count, that are dropped by `remove`)
+    mset.iter().all(|(x, &count)| new_mset.count(x) == count)
+        && new_mset.iter().all(|(x, &count)| mset.count(x) == count)
+        && (1..5).all(|threshold| {
+            new_mset.threshold(threshold) == mset.threshold(threshold)
+        })
+}
+
+#[quickcheck]
+fn remove_non_existent(
+    x: u64,
+    by: u64,
+    mset: MultiSet<u64, u64>,
+) -> TestResult {
+    // discard invalid inputs
+    if mset.iter().any(|(elem, _)| *elem == x) {
+        return TestResult::discard();
+    }
+
+    let mut new_mset = mset.clone();
+    new_mset.remove(vec![(x, by)]);
+
+    // prop: removing an element that is not in the multiset is a no-op
+    TestResult::from_bool(new_mset == mset)
+}
+
+#[quickcheck]
+fn collect_round_trip(threshold: u64, mset: MultiSet<u64, u64>) -> bool {
+    // move to a hash-based aggregator and back
+    let counts: HashMap<_, _> = mset.clone().into_iter().collect();
+    let new_mset: MultiSet<_, _> = counts.into_iter().collect();
+
+    // prop: thresholds agree after the conversion
+    new_mset.threshold(threshold) == mset.threshold(threshold)
 }
 
 #[quickcheck]
@@ -32,11 +77,57 @@ fn threshold(threshold: u64, mset: MultiSet<u64, u64>) -> bool {
     // prop: all the elements have a count higher than the threshold
     mset.threshold(threshold)
         .iter()
-        .all(|x| mset.count(&x) >= threshold)
+        .all(|x| mset.count(x) >= threshold)
+}
+
+#[quickcheck]
+fn into_threshold_iter(threshold: u64, mset: MultiSet<u64, u64>) -> bool {
+    let expected: Vec<u64> =
+        mset.threshold(threshold).into_iter().cloned().collect();
+
+    // prop: the owned elements are exactly the ones that qualify for the
+    // threshold
+    mset.into_threshold_iter(threshold).collect::<Vec<_>>() == expected
+}
+
+#[quickcheck]
+fn threshold_owned(threshold: u64, mset: MultiSet<u64, u64>) -> bool {
+    let expected: Vec<u64> =
+        mset.threshold(threshold).into_iter().cloned().collect();
+
+    // prop: the owned results are equal to the borrowed ones
+    mset.threshold_owned(threshold) == expected
+        && mset.into_threshold(threshold) == expected
+}
+
+#[quickcheck]
+fn add_capped(elems: BTreeMap<u64, u64>, cap: u8) -> bool {
+    let cap = cap as usize % 10;
+    let mut mset = MultiSet::new();
+    for (&elem, &by) in elems.iter() {
+        mset.add_capped(elem, by, cap);
+    }
+
+    // compute the highest counts
+    let mut counts: Vec<_> = elems.values().cloned().collect();
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    counts.truncate(cap);
+
+    // compute the counts of the elements that survived
+    let mut survivors: Vec<_> = mset.iter().map(|(_, &count)| count).collect();
+    survivors.sort_unstable_by(|a, b| b.cmp(a));
+
+    // prop: the cap is respected and the survivors are the elements with the
+    // highest counts
+    mset.elem_count() <= cap
+        && survivors == counts
+        && mset
+            .iter()
+            .all(|(elem, count)| elems.get(elem) == Some(count))
 }
 
 /// Count the number of occurrences of `x` in the vector of vectors.
-fn count(x: &u64, ls: &Vec<(u64, u64)>) -> u64 {
+fn count(x: &u64, ls: &[(u64, u64)]) -> u64 {
     ls.iter()
         .fold(0, |acc, (y, count)| if y == x { acc + count } else { acc })
 }
@@ -0,0 +1,89 @@
+use crate::clock::Op;
+use crate::tests::arbitrary::Musk;
+use crate::*;
+use quickcheck_macros::quickcheck;
+
+type RawOp = (Musk, u8, u8, Vec<u8>);
+
+#[quickcheck]
+fn apply_ops_vclock(clock: VClock<Musk>, ops: Vec<RawOp>) -> bool {
+    check_apply_ops(clock, ops)
+}
+
+#[quickcheck]
+fn apply_ops_aeclock(clock: AEClock<Musk>, ops: Vec<RawOp>) -> bool {
+    check_apply_ops(clock, ops)
+}
+
+#[quickcheck]
+fn apply_ops_arclock(clock: ARClock<Musk>, ops: Vec<RawOp>) -> bool {
+    check_apply_ops(clock, ops)
+}
+
+#[quickcheck]
+fn apply_ops_beclock(clock: BEClock<Musk>, ops: Vec<RawOp>) -> bool {
+    check_apply_ops(clock, ops)
+}
+
+fn check_apply_ops<E: EventSet>(
+    clock: Clock<Musk, E>,
+    ops: Vec<RawOp>,
+) -> bool {
+    // create operations from the raw operations
+    let ops: Vec<_> = ops
+        .into_iter()
+        .map(|(actor, a, b, events)| {
+            let (a, b) = (a as u64 % 30, b as u64 % 30);
+            match events.len() % 3 {
+                0 => Op::AddDot(actor, a),
+                1 => Op::AddRange(actor, a.min(b), a.max(b)),
+                _ => {
+                    let events = events.into_iter().map(|event| event as u64);
+                    Op::JoinEntry(actor, E::from_events(events))
+                }
+            }
+        })
+        .collect();
+
+    // apply operations in batch
+    let mut batched = clock.clone();
+    let summary = batched.apply_ops(ops.clone());
+
+    // apply operations individually
+    let mut individually = clock.clone();
+    for op in ops {
+        match op {
+            Op::AddDot(actor, seq) => {
+                individually.add(&actor, seq);
+            }
+            Op::AddRange(actor, start, end) => {
+                individually.add_range(&actor, start, end);
+            }
+            Op::JoinEntry(actor, eset) => {
+                individually.join(&Clock::from(vec![(actor, eset)]));
+            }
+        }
+    }
+
+    // prop: both clocks have the same events, the summary is correct, and the
+    // clock only changed (including its version) if some event was added
+    let frontiers_advanced = batched
+        .iter()
+        .filter(|(actor, eset)| {
+            eset.frontier() > clock.get(actor).map_or(0, |eset| eset.frontier())
+        })
+        .count();
+    let actors_touched = batched
+        .iter()
+        .filter(|(actor, eset)| {
+            !clock.get(actor).is_some_and(|before| eset.leq(before))
+        })
+        .count();
+    let changed = summary.events_added > 0;
+    batched.same_events(&individually)
+        && summary.events_added == batched.missing_count(&clock)
+        && summary.frontiers_advanced == frontiers_advanced
+        && summary.actors_touched == actors_touched
+        && (batched.version() != clock.version()) == changed
+        && (batched.len() == clock.len() || changed)
+}
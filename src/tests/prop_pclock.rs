@@ -0,0 +1,79 @@
+use crate::tests::arbitrary::Musk;
+use crate::*;
+use quickcheck_macros::quickcheck;
+
+#[quickcheck]
+fn conversions(aeclock: AEClock<Musk>) -> bool {
+    let pclock = PClock::from(aeclock.clone());
+
+    // prop: converting to a `PClock` and back gives the same clock
+    pclock.to_clock() == aeclock && Clock::from(pclock) == aeclock
+}
+
+#[quickcheck]
+fn add(actor: Musk, event: u64, aeclock: AEClock<Musk>) -> bool {
+    let mut pclock = PClock::from(aeclock.clone());
+    let mut aeclock = aeclock;
+
+    // prop: adding an event has the same effect in both clocks
+    pclock.add(&actor, event) == aeclock.add(&actor, event)
+        && pclock.to_clock() == aeclock
+}
+
+#[quickcheck]
+fn next(actor: Musk, vclock: VClock<Musk>) -> bool {
+    let mut pclock = PClock::from(vclock.clone());
+    let mut vclock = vclock;
+
+    // prop: generating the next event has the same effect in both clocks
+    pclock.next(&actor) == vclock.next(&actor) && pclock.to_clock() == vclock
+}
+
+#[quickcheck]
+fn join(aeclock_a: AEClock<Musk>, aeclock_b: AEClock<Musk>) -> bool {
+    let mut pclock_a = PClock::from(aeclock_a.clone());
+    let pclock_b = PClock::from(aeclock_b.clone());
+    let mut aeclock_a = aeclock_a;
+
+    pclock_a.join(&pclock_b);
+    aeclock_a.join(&aeclock_b);
+
+    // prop: joining has the same effect in both clocks
+    pclock_a.to_clock() == aeclock_a
+}
+
+#[quickcheck]
+fn meet(vclock_a: VClock<Musk>, vclock_b: VClock<Musk>) -> bool {
+    let mut pclock_a = PClock::from(vclock_a.clone());
+    let pclock_b = PClock::from(vclock_b.clone());
+    let mut vclock_a = vclock_a;
+
+    pclock_a.meet(&pclock_b);
+    vclock_a.meet(&vclock_b);
+
+    // prop: meeting has the same effect in both clocks
+    pclock_a.to_clock() == vclock_a
+}
+
+#[quickcheck]
+fn frontier_and_subtracted(
+    aeclock_a: AEClock<Musk>,
+    aeclock_b: AEClock<Musk>,
+) -> bool {
+    let pclock_a = PClock::from(aeclock_a.clone());
+    let pclock_b = PClock::from(aeclock_b.clone());
+
+    // prop: frontiers and subtracted events are the same in both clocks
+    pclock_a.frontier().to_clock() == aeclock_a.frontier()
+        && pclock_a.subtracted(&pclock_b) == aeclock_a.subtracted(&aeclock_b)
+}
+
+#[quickcheck]
+fn versions(actor: Musk, event: u64, aeclock: AEClock<Musk>) -> bool {
+    let mut pclock = PClock::from(aeclock.clone());
+    let version = pclock.clone();
+    pclock.add(&actor, event);
+
+    // prop: changing a clock doesn't change its previous versions
+    version.to_clock() == aeclock
+}
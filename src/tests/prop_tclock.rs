@@ -6,16 +6,11 @@ use quickcheck_macros::quickcheck;
 #[quickcheck]
 fn vclock_threshold_union(
     actor: Musk,
-    event: u64,
+    Event(event): Event,
     clock_a: VClock<Musk>,
     clock_b: VClock<Musk>,
     clock_c: VClock<Musk>,
 ) -> TestResult {
-    // event 0 is not allowed
-    if event == 0 {
-        return TestResult::discard();
-    }
-
     // create a vec with all clocks
     let clocks = vec![clock_a, clock_b, clock_c];
 
@@ -56,6 +51,25 @@ fn vclock_threshold_union(
     TestResult::from_bool(result)
 }
 
+#[quickcheck]
+fn vclock_prepared_threshold_union(
+    clock_a: VClock<Musk>,
+    clock_b: VClock<Musk>,
+    clock_c: VClock<Musk>,
+) -> bool {
+    let mut tclock = TClock::new();
+    tclock.add(clock_a);
+    tclock.add(clock_b);
+    tclock.add(clock_c);
+
+    // prop: the prepared threshold union is the same for all thresholds
+    // (including the ones no event reaches)
+    let prepared = tclock.prepare();
+    (0..=5).all(|threshold| {
+        prepared.threshold_union(threshold) == tclock.threshold_union(threshold)
+    })
+}
+
 #[quickcheck]
 fn vclock_union(clock_a: VClock<Musk>, clock_b: VClock<Musk>) -> TestResult {
     // add all clocks to the threshold clock
@@ -76,18 +90,74 @@ fn vclock_union(clock_a: VClock<Musk>, clock_b: VClock<Musk>) -> TestResult {
     TestResult::from_bool(result)
 }
 
+#[quickcheck]
+fn beclock_union(
+    clock_a: BEClock<Musk>,
+    clock_b: BEClock<Musk>,
+    clock_c: BEClock<Musk>,
+) -> bool {
+    // add all clocks to the threshold clock, and join them
+    let mut tclock = TClock::new();
+    let mut joined = BEClock::new();
+    for clock in [clock_a, clock_b, clock_c] {
+        joined.join(&clock);
+        tclock.add(clock);
+    }
+
+    // prop: the union is the join of all clocks
+    tclock.union() == joined
+}
+
 #[quickcheck]
 fn beclock_threshold_union(
     actor: Musk,
-    event: u64,
+    Event(event): Event,
     clock_a: BEClock<Musk>,
     clock_b: BEClock<Musk>,
     clock_c: BEClock<Musk>,
 ) -> TestResult {
-    // event 0 is not allowed
-    if event == 0 {
-        return TestResult::discard();
+    // create a vec with all clocks
+    let clocks = vec![clock_a, clock_b, clock_c];
+
+    // add all clocks to the threshold clock
+    let mut tclock = TClock::new();
+    for clock in clocks.clone() {
+        tclock.add(clock);
     }
+
+    // create a vec with possible threshold values
+    let thresholds = vec![1, 2, 3, 4];
+
+    let result = thresholds.into_iter().all(|threshold| {
+        // compute the threshold union
+        let clock = tclock.threshold_union(threshold as u64);
+
+        // compute the number of occurrences of `dot` in `clocks`
+        let occurrences = clocks
+            .iter()
+            .filter(|clock| clock.contains(&actor, event))
+            .count();
+
+        // prop: if the `dot` is in the resulting `clock`, then its number of
+        // occurrences is >= `threshold`
+        if clock.contains(&actor, event) {
+            occurrences >= threshold
+        } else {
+            occurrences < threshold
+        }
+    });
+
+    TestResult::from_bool(result)
+}
+
+#[quickcheck]
+fn aeclock_threshold_union(
+    actor: Musk,
+    Event(event): Event,
+    clock_a: AEClock<Musk>,
+    clock_b: AEClock<Musk>,
+    clock_c: AEClock<Musk>,
+) -> TestResult {
     // create a vec with all clocks
     let clocks = vec![clock_a, clock_b, clock_c];
 
@@ -121,3 +191,250 @@ fn beclock_threshold_union(
 
     TestResult::from_bool(result)
 }
+
+#[quickcheck]
+fn arclock_threshold_union(
+    clock_a: ARClock<Musk>,
+    clock_b: ARClock<Musk>,
+    clock_c: ARClock<Musk>,
+) -> bool {
+    // create a vec with all clocks, and the same clocks as `AEClock`s
+    let clocks = vec![clock_a, clock_b, clock_c];
+    let aeclocks: Vec<AEClock<Musk>> = clocks
+        .iter()
+        .map(|clock| {
+            Clock::from(clock.iter().map(|(actor, eset)| {
+                let events = eset.iter();
+                (actor.clone(), AboveExSet::from_events(events))
+            }))
+        })
+        .collect();
+
+    // add all clocks to the threshold clocks
+    let mut tclock = TClock::new();
+    for clock in clocks.clone() {
+        tclock.add(clock);
+    }
+    let mut aetclock = TClock::new();
+    for clock in aeclocks {
+        aetclock.add(clock);
+    }
+
+    // compute the highest event reported
+    let highest = clocks
+        .iter()
+        .flat_map(|clock| clock.iter().map(|(_, eset)| eset.events()))
+        .flat_map(|(max, exs)| exs.into_iter().chain(Some(max)))
+        .max()
+        .unwrap_or(0);
+
+    (1..=4).all(|threshold| {
+        // compute the threshold unions
+        let clock = tclock.threshold_union(threshold as u64);
+        let aeclock = aetclock.threshold_union(threshold as u64);
+
+        vec![Musk::A, Musk::B, Musk::C].into_iter().all(|actor| {
+            (1..=highest + 1).all(|event| {
+                // count the clocks with this event
+                let occurrences = clocks
+                    .iter()
+                    .filter(|clock| clock.contains(&actor, event))
+                    .count();
+
+                // prop: the event is in the threshold union iff it was
+                // observed at least `threshold` times, as in the `AEClock`
+                // threshold union
+                clock.contains(&actor, event) == (occurrences >= threshold)
+                    && aeclock.contains(&actor, event)
+                        == clock.contains(&actor, event)
+            })
+        })
+    })
+}
+
+#[quickcheck]
+fn mixed_threshold_union(
+    frontier_a: VClock<Musk>,
+    frontier_b: VClock<Musk>,
+    exact_a: BEClock<Musk>,
+    exact_b: BEClock<Musk>,
+) -> bool {
+    let frontiers = vec![frontier_a, frontier_b];
+    let exacts = vec![exact_a, exact_b];
+
+    // add all reports to the threshold clock
+    let mut tclock = TClock::new();
+    for frontier in frontiers.clone() {
+        tclock.add_report(Report::Frontier(frontier));
+    }
+    for exact in exacts.clone() {
+        tclock.add_report(Report::Exact(exact));
+    }
+
+    // compute the highest event reported
+    let highest =
+        frontiers
+            .iter()
+            .flat_map(|clock| clock.iter().map(|(_, eset)| eset.frontier()))
+            .chain(exacts.iter().flat_map(|clock| {
+                clock.iter().map(|(_, eset)| eset.events().0)
+            }))
+            .max()
+            .unwrap_or(0);
+
+    (1..=5).all(|threshold| {
+        // compute the threshold union
+        let clock = tclock.threshold_union(threshold as u64);
+
+        vec![Musk::A, Musk::B, Musk::C].into_iter().all(|actor| {
+            (1..=highest + 1).all(|event| {
+                // compute the number of reports with this event
+                let occurrences = frontiers
+                    .iter()
+                    .filter(|clock| clock.contains(&actor, event))
+                    .count()
+                    + exacts
+                        .iter()
+                        .filter(|clock| clock.contains(&actor, event))
+                        .count();
+
+                // prop: the event is in the threshold union iff it was
+                // reported at least `threshold` times
+                clock.contains(&actor, event) == (occurrences >= threshold)
+            })
+        })
+    })
+}
+
+#[quickcheck]
+fn vclock_gc_watermark(
+    own: VClock<Musk>,
+    clock_a: VClock<Musk>,
+    clock_b: VClock<Musk>,
+    clock_c: VClock<Musk>,
+) -> bool {
+    // add all clocks to the threshold clock
+    let mut tclock = TClock::new();
+    tclock.add(clock_a);
+    tclock.add(clock_b);
+    tclock.add(clock_c);
+
+    (1..=4).all(|threshold| {
+        let watermark = tclock.gc_watermark(&own, threshold);
+        let (clock, _) = tclock.threshold_union(threshold);
+        check_gc_watermark(&watermark, &own, &clock.frontier())
+    })
+}
+
+#[quickcheck]
+fn beclock_gc_watermark(
+    own: BEClock<Musk>,
+    clock_a: BEClock<Musk>,
+    clock_b: BEClock<Musk>,
+    clock_c: BEClock<Musk>,
+) -> bool {
+    // add all clocks to the threshold clock
+    let mut tclock = TClock::new();
+    tclock.add(clock_a);
+    tclock.add(clock_b);
+    tclock.add(clock_c);
+
+    (1..=4).all(|threshold| {
+        let watermark = tclock.gc_watermark(&own, threshold);
+        let clock = tclock.threshold_union(threshold);
+        check_gc_watermark(&watermark, &own, &clock.frontier())
+    })
+}
+
+#[quickcheck]
+fn aeclock_gc_watermark(
+    own: AEClock<Musk>,
+    clock_a: AEClock<Musk>,
+    clock_b: AEClock<Musk>,
+    clock_c: AEClock<Musk>,
+) -> bool {
+    // add all clocks to the threshold clock
+    let clocks = vec![clock_a, clock_b, clock_c];
+    let mut tclock = TClock::new();
+    for clock in clocks.clone() {
+        tclock.add(clock);
+    }
+
+    (1..=4).all(|threshold| {
+        let watermark = tclock.gc_watermark(&own, threshold);
+        let clock = tclock.threshold_union(threshold);
+        check_gc_watermark(&watermark, &own, &clock.frontier())
+            && check_gc_watermark_seen(&watermark, &clocks, threshold)
+    })
+}
+
+#[quickcheck]
+fn arclock_gc_watermark(
+    own: ARClock<Musk>,
+    clock_a: ARClock<Musk>,
+    clock_b: ARClock<Musk>,
+    clock_c: ARClock<Musk>,
+) -> bool {
+    // add all clocks to the threshold clock
+    let clocks = vec![clock_a, clock_b, clock_c];
+    let mut tclock = TClock::new();
+    for clock in clocks.clone() {
+        tclock.add(clock);
+    }
+
+    (1..=4).all(|threshold| {
+        let watermark = tclock.gc_watermark(&own, threshold);
+        let clock = tclock.threshold_union(threshold);
+        check_gc_watermark(&watermark, &own, &clock.frontier())
+            && check_gc_watermark_seen(&watermark, &clocks, threshold)
+    })
+}
+
+// Checks that, for each actor in both `own` and the threshold-union, the
+// watermark is the minimum between both frontiers (and so it's not higher than
+// either), that it skips the other actors, and that `Clock::truncate_to_gc`
+// doesn't drop any event above it.
+fn check_gc_watermark<E: EventSet + PartialEq>(
+    watermark: &VClock<Musk>,
+    own: &Clock<Musk, E>,
+    threshold_frontier: &VClock<Musk>,
+) -> bool {
+    let expected: Vec<_> = threshold_frontier
+        .iter()
+        .filter_map(|(actor, max)| {
+            let own_frontier = own.get(actor)?.frontier();
+            Some((actor, max.frontier().min(own_frontier)))
+        })
+        .collect();
+    let mut truncated = own.clone();
+    truncated.truncate_to_gc(watermark);
+    let kept_above = own.iter().all(|(actor, eset)| {
+        let max = watermark.get(actor).map_or(0, MaxSet::frontier);
+        let kept = truncated.get(actor).expect("entries are never removed");
+        kept.count_events_in_range(max + 1, u64::MAX)
+            == eset.count_events_in_range(max + 1, u64::MAX)
+    });
+    kept_above
+        && watermark.len() == expected.len()
+        && expected.into_iter().all(|(actor, max)| {
+            watermark.get(actor).map(|eset| eset.frontier()) == Some(max)
+        })
+}
+
+// Checks that all events at or below the watermark were seen in at least
+// `threshold` of the clocks.
+fn check_gc_watermark_seen<E: EventSet>(
+    watermark: &VClock<Musk>,
+    clocks: &[Clock<Musk, E>],
+    threshold: u64,
+) -> bool {
+    watermark.iter().all(|(actor, max)| {
+        (1..=max.frontier()).all(|event| {
+            let occurrences = clocks
+                .iter()
+                .filter(|clock| clock.contains(actor, event))
+                .count();
+            occurrences as u64 >= threshold
+        })
+    })
+}
@@ -0,0 +1,46 @@
+use crate::tests::arbitrary::Musk;
+use crate::*;
+use quickcheck_macros::quickcheck;
+
+#[quickcheck]
+fn truncate_to_aeclock(clock: AEClock<Musk>, frontier: VClock<Musk>) -> bool {
+    check_truncate_to(clock, frontier)
+}
+
+#[quickcheck]
+fn truncate_to_arclock(clock: ARClock<Musk>, frontier: VClock<Musk>) -> bool {
+    check_truncate_to(clock, frontier)
+}
+
+#[quickcheck]
+fn truncate_to_beclock(clock: BEClock<Musk>, frontier: VClock<Musk>) -> bool {
+    check_truncate_to(clock, frontier)
+}
+
+#[quickcheck]
+fn truncate_to_brclock(clock: BRClock<Musk>, frontier: VClock<Musk>) -> bool {
+    check_truncate_to(clock, frontier)
+}
+
+fn check_truncate_to<E: EventSet>(
+    mut clock: Clock<Musk, E>,
+    frontier: VClock<Musk>,
+) -> bool {
+    // compute the expected events of each actor left in the clock
+    let expected: Vec<_> = clock
+        .iter()
+        .filter_map(|(actor, eset)| {
+            let max = frontier.get(actor)?.frontier();
+            let events: Vec<_> = eset.iter().filter(|e| *e <= max).collect();
+            Some((actor.clone(), events))
+        })
+        .collect();
+
+    // prop: only the actors in the frontier are kept, with their events up
+    // to the frontier
+    clock.truncate_to(&frontier);
+    clock.len() == expected.len()
+        && expected.into_iter().all(|(actor, events)| {
+            clock.get(&actor).is_some_and(|eset| eset.iter().eq(events))
+        })
+}
@@ -1,7 +1,7 @@
 use crate::tests::arbitrary::Musk;
 use crate::*;
 use quickcheck_macros::quickcheck;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashSet};
 
 #[quickcheck]
 fn next(actor: Musk, vclock: VClock<Musk>) -> bool {
@@ -60,7 +60,7 @@ fn subtracted(vclock_a: VClock<Musk>, vclock_b: VClock<Musk>) -> bool {
         let a = eset_a.event_iter().collect::<BTreeSet<_>>();
         let b = vclock_b
             .get(&actor)
-            .map(|eset_b| eset_b.clone().event_iter().collect::<BTreeSet<_>>())
+            .map(|eset_b| eset_b.iter().collect::<BTreeSet<_>>())
             .unwrap_or_default();
         let expected = a.difference(&b).cloned().collect::<BTreeSet<_>>();
         let result = result
@@ -72,3 +72,64 @@ fn subtracted(vclock_a: VClock<Musk>, vclock_b: VClock<Musk>) -> bool {
         expected == result
     })
 }
+
+#[quickcheck]
+fn subtracted_clock(vclock_a: VClock<Musk>, vclock_b: VClock<Musk>) -> bool {
+    let delta = vclock_a.subtracted_clock(&vclock_b);
+
+    // prop: an actor is in the difference iff it has events in `a` that are
+    // not in `b`, and then it has all events in `a` (as `MaxSet`s can't
+    // represent gaps)
+    vclock_a.iter().all(|(actor, eset_a)| {
+        let frontier_b = vclock_b.get(actor).map_or(0, |eset| eset.frontier());
+        let missing = eset_a.frontier() > frontier_b;
+        match delta.get(actor) {
+            Some(eset) => missing && eset == eset_a,
+            None => !missing,
+        }
+    }) && delta.iter().all(|(actor, _)| vclock_a.get(actor).is_some())
+        && delta == &vclock_a - &vclock_b
+}
+
+#[quickcheck]
+fn missing_count(vclock_a: VClock<Musk>, vclock_b: VClock<Musk>) -> bool {
+    let expected: usize = vclock_a
+        .subtracted(&vclock_b)
+        .values()
+        .map(|events| events.len())
+        .sum();
+
+    // prop: the number of missing events is the number of events subtracted
+    vclock_a.missing_count(&vclock_b) == expected as u64
+}
+
+#[quickcheck]
+fn count_events_in_range(vclock: VClock<Musk>, start: u8, end: u8) -> bool {
+    let (start, end) = (start as u64, end as u64);
+    let expected = vclock
+        .iter()
+        .map(|(actor, _)| {
+            (start..=end)
+                .filter(|event| *event > 0 && vclock.contains(actor, *event))
+                .count() as u64
+        })
+        .sum::<u64>();
+
+    // prop: counting events in a range is the same as checking each event
+    vclock.count_events_in_range(start, end) == expected
+}
+
+#[quickcheck]
+fn frontier_threshold_excluding(
+    vclock: VClock<Musk>,
+    exclude: HashSet<Musk>,
+    threshold: u8,
+) -> bool {
+    let threshold = threshold as usize % 5 + 1;
+    let mut projected = vclock.clone();
+    projected.retain_actors(|actor| !exclude.contains(actor));
+
+    // prop: excluding actors is the same as removing them from the clock
+    vclock.frontier_threshold_excluding(&exclude, threshold)
+        == projected.frontier_threshold(threshold)
+}
@@ -0,0 +1,53 @@
+use crate::tests::arbitrary::{Musk, MAX_EVENTS};
+use crate::*;
+use quickcheck_macros::quickcheck;
+
+#[quickcheck]
+fn view_agrees_with_clock(
+    clock: AEClock<Musk>,
+    other: AEClock<Musk>,
+    threshold: usize,
+    min_actors: usize,
+) -> bool {
+    let view = clock.view();
+    let other_view = other.view();
+    let threshold = threshold % 4 + 1;
+    let min_actors = min_actors % 5;
+
+    let actors = [Musk::A, Musk::B, Musk::C];
+    let entries = actors.iter().all(|actor| {
+        view.get(actor) == clock.get(actor)
+            && view.frontier_of(actor)
+                == clock.get(actor).map_or(0, |eset| eset.frontier())
+            && (0..=MAX_EVENTS + 1).all(|seq| {
+                view.contains(actor, seq) == clock.contains(actor, seq)
+            })
+    });
+
+    // sort iterators so that they can be compared
+    let mut iter: Vec<_> = view.iter().collect();
+    let mut clock_iter: Vec<_> = clock.iter().collect();
+    iter.sort_by_key(|(actor, _)| *actor);
+    clock_iter.sort_by_key(|(actor, _)| *actor);
+
+    // other can be passed both as `&Clock` and as `ClockView`
+    entries
+        && iter == clock_iter
+        && view.len() == clock.len()
+        && view.is_empty() == clock.is_empty()
+        && view.frontier() == clock.frontier()
+        && view.frontier_threshold(threshold)
+            == clock.frontier_threshold(threshold)
+        && view.frontier_threshold_checked(threshold, min_actors)
+            == clock.frontier_threshold_checked(threshold, min_actors)
+        && view.leq(&other) == clock.leq(&other)
+        && view.leq(other_view) == clock.leq(&other)
+        && view.dominates(&other) == clock.dominates(&other)
+        && view.concurrent(other_view) == clock.concurrent(&other)
+        && view.subtracted(&other) == clock.subtracted(&other)
+        && view.missing_count(other_view) == clock.missing_count(&other)
+        && view.count_events_in_range(2, MAX_EVENTS / 2)
+            == clock.count_events_in_range(2, MAX_EVENTS / 2)
+        && view.snapshot() == clock.snapshot()
+        && view.to_clock() == clock
+}
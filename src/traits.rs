@@ -1,5 +1,8 @@
+use std::cmp::{self, Ordering};
+use std::collections::hash_map::DefaultHasher;
+use std::convert::TryFrom;
 use std::fmt::Debug;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 
 /// Count trait to be used in `MultiSet`.
 pub trait Count: Copy {
@@ -35,14 +38,80 @@ impl Count for (u64, u64) {
     }
 }
 
+/// CountSub trait for counts that can also be subtracted, to remove elements
+/// from a `MultiSet`.
+pub trait CountSub: Count + PartialEq {
+    /// Subtract from the count (saturating at zero).
+    fn sub(&mut self, other: Self);
+}
+
+impl CountSub for u64 {
+    /// Subtract from the count (saturating at zero).
+    fn sub(&mut self, other: Self) {
+        *self = self.saturating_sub(other);
+    }
+}
+
+impl CountSub for (u64, u64) {
+    /// Subtract from the count (saturating at zero).
+    fn sub(&mut self, other: Self) {
+        self.0 = self.0.saturating_sub(other.0);
+        self.1 = self.1.saturating_sub(other.1);
+    }
+}
+
+/// Seq trait for the integer types used to store events in event sets. Only
+/// `MaxSetOf` is generic over it; the other event sets store `u64`s.
+pub trait Seq:
+    Copy + Debug + Default + Hash + Ord + Into<u64> + TryFrom<u64> + 'static
+{
+    /// The highest event that fits in the `Seq`.
+    const MAX: u64;
+
+    /// Converts an event into a `Seq`, returning `None` if the event doesn't
+    /// fit in the `Seq`.
+    fn try_from_u64(event: u64) -> Option<Self> {
+        Self::try_from(event).ok()
+    }
+
+    /// Converts an event into a `Seq`.
+    ///
+    /// # Panics
+    /// Panics if the event doesn't fit in the `Seq`.
+    fn from_u64(event: u64) -> Self {
+        Self::try_from_u64(event)
+            .expect("event should fit in the sequence type")
+    }
+}
+
+impl Seq for u32 {
+    const MAX: u64 = u32::MAX as u64;
+}
+
+impl Seq for u64 {
+    const MAX: u64 = u64::MAX;
+}
+
 /// Actor trait to be used in `Clock`'s or `TClock`'s.
 pub trait Actor: Debug + Clone + Hash + Eq + Ord {}
 impl<A: Debug + Clone + Hash + Eq + Ord> Actor for A {}
 
-/// EventSet trait to be implemented by `MaxSet`, `BelowExSet` and `AboveExSet`.
+/// EventSet trait to be implemented by `MaxSet`, `BelowExSet`,
+/// `BelowRangeSet`, `AboveExSet`, `AboveRangeSet` and `ExplicitSet`.
 pub trait EventSet: Clone + Debug + Default {
     type EventIter: Iterator<Item = u64>;
 
+    /// Short name of the `Clock` type alias that uses this event set (e.g.
+    /// `"VClock"` for `MaxSet`). Used to tag `Clock`'s `Debug` output, and
+    /// `"Clock"` by default.
+    const KIND: &'static str = "Clock";
+
+    /// The highest event the set can store (e.g. `u32::MAX` for
+    /// `MaxSetOf<u32>`). Higher events are never added to the set: adding
+    /// them returns `false`, and `Clock::try_add` rejects them. `u64::MAX` by
+    /// default.
+    const MAX_SEQ: u64 = u64::MAX;
+
     /// Returns a new instance.
     fn new() -> Self;
 
@@ -69,13 +138,54 @@ pub trait EventSet: Clone + Debug + Default {
         eset
     }
 
+    /// Creates a new instance from several `events`, sorted from lowest to
+    /// highest (repetitions are allowed). This is the same as
+    /// `EventSet::from_events`, but runs of contiguous events are added at
+    /// once (with `EventSet::add_event_range`).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let events = vec![1, 2, 3, 5, 6, 6, 9];
+    /// let above_exset = AboveExSet::from_sorted_events(events.clone());
+    /// assert_eq!(above_exset, AboveExSet::from_events(events));
+    /// assert_eq!(above_exset.events(), (3, vec![5, 6, 9]));
+    /// ```
+    fn from_sorted_events<I: IntoIterator<Item = u64>>(iter: I) -> Self {
+        let mut eset = Self::new();
+        // current run of contiguous events (both ends included)
+        let mut run: Option<(u64, u64)> = None;
+        // 0's are not events
+        for event in iter.into_iter().filter(|event| *event != 0) {
+            match run {
+                Some((start, end)) if event <= end + 1 => {
+                    debug_assert!(event >= end, "events should be sorted");
+                    run = Some((start, event.max(end)));
+                }
+                _ => {
+                    if let Some((start, end)) = run {
+                        eset.add_event_range(start, end);
+                    }
+                    run = Some((event, event));
+                }
+            }
+        }
+        if let Some((start, end)) = run {
+            eset.add_event_range(start, end);
+        }
+        eset
+    }
+
     /// Generates the next event.
     fn next_event(&mut self) -> u64;
 
     /// Adds an event to the set.
     fn add_event(&mut self, event: u64) -> bool;
 
-    /// Adds a range of events to the set.
+    /// Adds a range of events to the set (both ends included).
+    /// Returns `true` if a new event was added. An empty range (i.e.
+    /// `start > end`) adds no events, and so it returns `false`.
     fn add_event_range(&mut self, start: u64, end: u64) -> bool {
         let mut res = false;
         (start..=end).for_each(|event| {
@@ -85,10 +195,102 @@ pub trait EventSet: Clone + Debug + Default {
         res
     }
 
+    /// Adds an event to the set, returning the range of events (both ends
+    /// included) that became contiguous with it, i.e. `(old_frontier + 1,
+    /// new_frontier)`, if the frontier advanced. Otherwise `None` is
+    /// returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_exset = AboveExSet::from_events(vec![1, 3]);
+    /// assert_eq!(above_exset.add_event_delivered(5), None);
+    /// assert_eq!(above_exset.add_event_delivered(2), Some((2, 3)));
+    /// assert_eq!(above_exset.add_event_delivered(2), None);
+    /// assert_eq!(above_exset.add_event_delivered(4), Some((4, 5)));
+    /// ```
+    fn add_event_delivered(&mut self, event: u64) -> Option<(u64, u64)> {
+        let old_frontier = self.frontier();
+        self.add_event(event);
+        let new_frontier = self.frontier();
+        if new_frontier > old_frontier {
+            Some((old_frontier + 1, new_frontier))
+        } else {
+            None
+        }
+    }
+
+    /// Removes an event from the set.
+    /// Returns `true` if the event was part of the set. Event 0 is always part
+    /// of the set, and so it can't be removed.
+    ///
+    /// Removing an event below the frontier lowers the frontier to the event
+    /// before it (and the events above it that were contiguous are no
+    /// longer).
+    ///
+    /// The default implementation rebuilds the set from its ranges (see
+    /// `EventSet::range_iter`) without the event.
+    fn remove_event(&mut self, event: u64) -> bool {
+        if event == 0 || !self.is_event(event) {
+            return false;
+        }
+        let ranges: Vec<_> = self.clone().range_iter().collect();
+        let mut eset = Self::new();
+        for (start, end) in
+            crate::set::subtract_intervals(&ranges, &[(event, event)])
+        {
+            eset.add_event_range(start, end);
+        }
+        *self = eset;
+        true
+    }
+
+    /// Removes a range of events from the set.
+    /// Returns `true` if some event in the range was part of the set.
+    ///
+    /// As with `EventSet::remove_event`, removing a range that starts below
+    /// the frontier lowers the frontier to the event before the range.
+    ///
+    /// The default implementation removes each event in the range, from the
+    /// highest to the lowest.
+    fn remove_event_range(&mut self, start: u64, end: u64) -> bool {
+        // remove from the highest event to the lowest, so that each event
+        // removed below the frontier is the highest contiguous one
+        let mut res = false;
+        (start..=end).rev().for_each(|event| {
+            let removed = self.remove_event(event);
+            res = res || removed;
+        });
+        res
+    }
+
+    /// Removes all events higher than `max_event` from the set, keeping only
+    /// the events up to (and including) `max_event`. This is what
+    /// `Clock::truncate_to` does to each entry.
+    ///
+    /// The default implementation rebuilds the set from its ranges (see
+    /// `EventSet::range_iter`) up to `max_event`.
+    fn truncate(&mut self, max_event: u64) {
+        if self.max_event() <= max_event {
+            return;
+        }
+        let ranges: Vec<_> = self.clone().range_iter().collect();
+        let mut eset = Self::new();
+        for (start, end) in ranges
+            .into_iter()
+            .take_while(|(start, _)| *start <= max_event)
+        {
+            eset.add_event_range(start, cmp::min(end, max_event));
+        }
+        *self = eset;
+    }
+
     /// Checks if an event is part of the set.
     fn is_event(&self, event: u64) -> bool;
 
-    /// Returns all events seen as a pair.
+    /// Returns all events seen as a pair. The second component is sorted in
+    /// strictly ascending order.
     ///
     /// For `MaxSet`:
     /// - the first component is the highest event
@@ -111,9 +313,48 @@ pub trait EventSet: Clone + Debug + Default {
     /// Returns the frontier (the highest contiguous event seen).
     fn frontier(&self) -> u64;
 
+    /// Returns the highest event in the set (or 0 if the set is empty),
+    /// without enumerating the events.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 3, 5, 6]);
+    /// assert_eq!(above_exset.frontier(), 3);
+    /// assert_eq!(above_exset.max_event(), 6);
+    /// ```
+    ///
+    /// The default implementation returns the end of the last range of the
+    /// set (see `EventSet::range_iter`).
+    fn max_event(&self) -> u64 {
+        self.clone().range_iter().last().map_or(0, |(_, end)| end)
+    }
+
     /// Merges `other` `EventSet` into `self`.
     fn join(&mut self, other: &Self);
 
+    /// Merges `other` `EventSet` into `self`, returning `true` if some event
+    /// in `other` was not in `self`.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_exset = AboveExSet::from_events(vec![1, 2, 4]);
+    /// assert!(!above_exset.join_mut(&AboveExSet::from_events(vec![1, 4])));
+    /// assert!(above_exset.join_mut(&AboveExSet::from_events(vec![3])));
+    /// assert_eq!(above_exset.frontier(), 4);
+    /// ```
+    fn join_mut(&mut self, other: &Self) -> bool {
+        // only join if there's something new in `other`
+        let new = !other.leq(self);
+        if new {
+            self.join(other);
+        }
+        new
+    }
+
     /// Intersects `other` `EventSet` with `self`.
     fn meet(&mut self, other: &Self);
 
@@ -121,9 +362,254 @@ pub trait EventSet: Clone + Debug + Default {
     /// `self`.
     fn subtracted(&self, other: &Self) -> Vec<u64>;
 
+    /// Returns the number of events that remain when `other` is subtracted
+    /// from `self`, without enumerating them.
+    ///
+    /// The default implementation subtracts the ranges of both sets (see
+    /// `EventSet::range_iter`), and so it only enumerates events if
+    /// `EventSet::range_iter` does.
+    fn subtracted_count(&self, other: &Self) -> u64 {
+        let ranges: Vec<_> = self.clone().range_iter().collect();
+        let other: Vec<_> = other.clone().range_iter().collect();
+        crate::set::subtract_intervals(&ranges, &other)
+            .into_iter()
+            .map(|(start, end)| end - start + 1)
+            .sum()
+    }
+
+    /// Returns the number of events in the set, without enumerating them.
+    ///
+    /// The default implementation adds up the sizes of the ranges of the set
+    /// (see `EventSet::range_iter`), and so it only enumerates events if
+    /// `EventSet::range_iter` does.
+    fn event_count(&self) -> u64 {
+        self.clone()
+            .range_iter()
+            .map(|(start, end)| end - start + 1)
+            .sum()
+    }
+
+    /// Returns the number of extras (or exceptions) the set stores besides its
+    /// contiguous prefix (or its highest event): the extras of an
+    /// `AboveExSet`, the exceptions of a `BelowExSet`, and the ranges of them
+    /// of an `AboveRangeSet` and a `BelowRangeSet`. This is what
+    /// `Clock::join_capped` bounds.
+    ///
+    /// The default implementation counts the ranges above the frontier (see
+    /// `EventSet::range_iter`), and so it only enumerates events if
+    /// `EventSet::range_iter` does.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 4, 5, 7]);
+    /// assert_eq!(above_exset.stored_extras(), 3);
+    ///
+    /// let above_range_set = AboveRangeSet::from_events(vec![1, 2, 4, 5, 7]);
+    /// assert_eq!(above_range_set.stored_extras(), 2);
+    /// ```
+    fn stored_extras(&self) -> usize {
+        self.clone()
+            .range_iter()
+            .filter(|(start, _)| *start > 1)
+            .count()
+    }
+
+    /// Checks if the set has no events.
+    fn is_empty(&self) -> bool {
+        self.event_count() == 0
+    }
+
+    /// Returns a hash of the set that only depends on its events (and not on
+    /// how the set was built), computed over the (compressed) output of
+    /// `EventSet::events` with sorted extras. The hash is stable across runs
+    /// (`DefaultHasher::new` is not randomly seeded), but not across
+    /// event set types nor Rust versions.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let a = BelowExSet::from_events(vec![1, 3, 5, 6]);
+    /// let b = BelowExSet::from_events(vec![6, 5, 3, 1]);
+    /// assert_eq!(a.canonical_hash(), b.canonical_hash());
+    /// ```
+    fn canonical_hash(&self) -> u64 {
+        let mut set = self.clone();
+        set.compress();
+        let (max, mut extras) = set.events();
+        extras.sort_unstable();
+        extras.dedup();
+
+        let mut hasher = DefaultHasher::new();
+        max.hash(&mut hasher);
+        extras.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the number of events in the set that are within `[start, end]`
+    /// (both ends included).
+    ///
+    /// The default implementation adds up the sizes of the ranges of the set
+    /// (see `EventSet::range_iter`) clamped to `[start, end]`, and so it only
+    /// enumerates events if `EventSet::range_iter` does.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_range_set = AboveRangeSet::from_events(vec![1, 2, 3, 5, 8]);
+    /// assert_eq!(above_range_set.count_events_in_range(2, 6), 3);
+    /// ```
+    fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        self.clone()
+            .range_iter()
+            .take_while(|(lo, _)| *lo <= end)
+            .map(|(lo, hi)| {
+                let lo = cmp::max(lo, start);
+                let hi = cmp::min(hi, end);
+                if lo <= hi {
+                    hi - lo + 1
+                } else {
+                    0
+                }
+            })
+            .sum()
+    }
+
+    /// Checks if all events within `[start, end]` (both ends included) are
+    /// in the set. An empty range (i.e. `start > end`) is always contained.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let above_exset = AboveExSet::from_events(vec![1, 2, 3, 5, 6]);
+    /// assert!(above_exset.contains_all_range(2, 3));
+    /// assert!(above_exset.contains_all_range(5, 6));
+    /// assert!(!above_exset.contains_all_range(3, 5));
+    /// ```
+    fn contains_all_range(&self, start: u64, end: u64) -> bool {
+        (start..=end).all(|event| self.is_event(event))
+    }
+
+    /// Checks if all events in `self` are also in `other`.
+    ///
+    /// This is the order used by the `PartialOrd` implementation of all event
+    /// sets: `a <= b` iff `a.leq(&b)`, and sets where neither is included in
+    /// the other are incomparable. So that it agrees with `==`, sets with the
+    /// same events are only `Ordering::Equal` if they're `==`, and
+    /// incomparable otherwise (e.g. if one was built without being
+    /// compressed).
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    /// use std::cmp::Ordering;
+    ///
+    /// assert!(MaxSet::from(3).leq(&MaxSet::from(5)));
+    /// assert!(MaxSet::from(3) < MaxSet::from(5));
+    ///
+    /// let a = AboveExSet::from_events(vec![1, 2, 4]);
+    /// let b = AboveExSet::from_events(vec![1, 2, 3]);
+    /// assert!(!a.leq(&b));
+    /// assert!(!b.leq(&a));
+    /// assert_eq!(a.partial_cmp(&b), None);
+    ///
+    /// let c = AboveExSet::from_events(vec![1, 2, 3, 4]);
+    /// assert!(a < c);
+    /// assert_eq!(c.partial_cmp(&c.clone()), Some(Ordering::Equal));
+    ///
+    /// // same events, but not `==`
+    /// let d = AboveExSet::from(0, vec![1]);
+    /// let e = AboveExSet::from_events(vec![1]);
+    /// assert!(d.leq(&e) && e.leq(&d));
+    /// assert_ne!(d, e);
+    /// assert_eq!(d.partial_cmp(&e), None);
+    /// ```
+    fn leq(&self, other: &Self) -> bool {
+        self.subtracted_count(other) == 0
+    }
+
+    /// Compresses the set: extras (or exceptions) that are redundant, or that
+    /// can be represented by the contiguous prefix, are dropped. The events
+    /// in the set don't change. Returns `true` if the representation of the set
+    /// changed.
+    ///
+    /// Event sets are kept compressed as events are added, and so this is
+    /// only needed for sets created with a raw constructor (e.g.
+    /// `AboveExSet::from`) or deserialized. By default, it does nothing.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut above_exset = AboveExSet::from(1, vec![1, 2, 3, 5]);
+    /// assert_eq!(above_exset.frontier(), 1);
+    ///
+    /// assert!(above_exset.compress());
+    /// assert_eq!(above_exset.events(), (3, vec![5]));
+    /// assert!(!above_exset.compress());
+    /// ```
+    fn compress(&mut self) -> bool {
+        false
+    }
+
     /// Returns an iterator containing all elements represented by this event
     /// set.
     fn event_iter(self) -> Self::EventIter;
+
+    /// Returns an iterator containing all elements represented by this event
+    /// set (from lowest to highest), borrowing the set instead of consuming
+    /// it.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from_events(vec![1, 2, 4, 6]);
+    /// let events: Vec<_> = below_exset.iter().collect();
+    /// assert_eq!(events, vec![1, 2, 4, 6]);
+    /// ```
+    ///
+    /// The default implementation clones the set and returns its
+    /// `EventSet::event_iter`. The event sets in this crate return their own
+    /// (allocation-free) iterator instead.
+    fn iter(&self) -> impl Iterator<Item = u64> {
+        self.clone().event_iter()
+    }
+
+    /// Returns an iterator with all events as ranges (both ends included),
+    /// from lowest to highest. Ranges are disjoint and not adjacent.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let below_exset = BelowExSet::from(10, vec![4, 5, 8]);
+    /// let ranges: Vec<_> = below_exset.range_iter().collect();
+    /// assert_eq!(ranges, vec![(1, 3), (6, 7), (9, 10)]);
+    /// ```
+    fn range_iter(self) -> impl Iterator<Item = (u64, u64)> {
+        crate::set::coalesce(self.event_iter().map(|event| (event, event)))
+            .into_iter()
+    }
+}
+
+/// Compares two event sets using the `EventSet::leq` order. Sets with the same
+/// events that are not `==` are incomparable.
+pub(crate) fn partial_cmp<E: EventSet + PartialEq>(
+    a: &E,
+    b: &E,
+) -> Option<Ordering> {
+    match (a.leq(b), b.leq(a)) {
+        (true, true) if a == b => Some(Ordering::Equal),
+        (true, true) => None,
+        (true, false) => Some(Ordering::Less),
+        (false, true) => Some(Ordering::Greater),
+        (false, false) => None,
+    }
 }
 
 pub fn subtract_iter<E, S>(from: E, subtract: S) -> SubtractIter<E, S>
@@ -0,0 +1,241 @@
+//! This module contains `ClockView`, a read-only view of a `Clock`.
+//!
+//! A `ClockView` is a (`Copy`) borrow of a clock that provides its read API
+//! without exposing mutation. Functions that only read a clock can take
+//! `impl Into<ClockView<'_, A, E>>`, accepting both `&Clock` and `ClockView`,
+//! and so their signatures make clear that the clock is not cloned nor
+//! changed.
+//!
+//! # Examples
+//! ```
+//! use threshold::*;
+//!
+//! fn seen<'a>(clock: impl Into<ClockView<'a, &'a str, MaxSet>>) -> u64 {
+//!     let view = clock.into();
+//!     view.iter().map(|(_, eset)| eset.frontier()).sum()
+//! }
+//!
+//! let mut clock = VClock::new();
+//! clock.add_range(&"A", 1, 10);
+//! clock.add_range(&"B", 1, 5);
+//!
+//! assert_eq!(seen(&clock), 15);
+//! assert_eq!(seen(clock.view()), 15);
+//! ```
+
+use crate::clock::Iter;
+use crate::*;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::BuildHasher;
+
+/// A read-only view of a `Clock`.
+pub struct ClockView<'a, A: Actor, E: EventSet> {
+    clock: &'a Clock<A, E>,
+}
+
+impl<'a, A: Actor, E: EventSet> ClockView<'a, A, E> {
+    /// Returns the number of actors in the clock.
+    pub fn len(&self) -> usize {
+        self.clock.len()
+    }
+
+    /// Checks that the clock is empty.
+    pub fn is_empty(&self) -> bool {
+        self.clock.is_empty()
+    }
+
+    /// Retrieves the event set associated with some `actor`.
+    pub fn get(&self, actor: &A) -> Option<&'a E> {
+        self.clock.get(actor)
+    }
+
+    /// Checks if an event is part of the clock.
+    pub fn contains(&self, actor: &A, seq: impl IntoSeq) -> bool {
+        self.clock.contains(actor, seq)
+    }
+
+    /// See `Clock::contains_range`.
+    pub fn contains_range(
+        &self,
+        actor: &A,
+        start: impl IntoSeq,
+        end: impl IntoSeq,
+    ) -> bool {
+        self.clock.contains_range(actor, start, end)
+    }
+
+    /// Returns the frontier of the `actor`, or 0 if the actor is not in the
+    /// clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let clock = AEClock::from(vec![("A", AboveExSet::from_events(vec![1, 3]))]);
+    /// assert_eq!(clock.view().frontier_of(&"A"), 1);
+    /// assert_eq!(clock.view().frontier_of(&"B"), 0);
+    /// ```
+    pub fn frontier_of(&self, actor: &A) -> u64 {
+        self.clock.get(actor).map_or(0, |eset| eset.frontier())
+    }
+
+    /// See `Clock::max_event`.
+    pub fn max_event(&self, actor: &A) -> u64 {
+        self.clock.max_event(actor)
+    }
+
+    /// Returns the clock frontier. See `Clock::frontier`.
+    pub fn frontier(&self) -> VClock<A> {
+        self.clock.frontier()
+    }
+
+    /// See `Clock::frontier_threshold`.
+    pub fn frontier_threshold(&self, threshold: usize) -> Option<u64> {
+        self.clock.frontier_threshold(threshold)
+    }
+
+    /// See `Clock::frontier_threshold_checked`.
+    pub fn frontier_threshold_checked(
+        &self,
+        threshold: usize,
+        min_actors: usize,
+    ) -> Option<u64> {
+        self.clock.frontier_threshold_checked(threshold, min_actors)
+    }
+
+    /// See `Clock::frontier_threshold_excluding`.
+    pub fn frontier_threshold_excluding<S: BuildHasher>(
+        &self,
+        exclude: &HashSet<A, S>,
+        threshold: usize,
+    ) -> Option<u64> {
+        self.clock.frontier_threshold_excluding(exclude, threshold)
+    }
+
+    /// Checks that every event in the clock is an event in `other`. See
+    /// `Clock::leq`.
+    pub fn leq<'b>(&self, other: impl Into<ClockView<'b, A, E>>) -> bool
+    where
+        A: 'b,
+        E: 'b,
+    {
+        self.clock.leq(other.into().clock)
+    }
+
+    /// See `Clock::dominates`.
+    pub fn dominates<'b>(&self, other: impl Into<ClockView<'b, A, E>>) -> bool
+    where
+        A: 'b,
+        E: 'b,
+    {
+        self.clock.dominates(other.into().clock)
+    }
+
+    /// See `Clock::concurrent`.
+    pub fn concurrent<'b>(&self, other: impl Into<ClockView<'b, A, E>>) -> bool
+    where
+        A: 'b,
+        E: 'b,
+    {
+        self.clock.concurrent(other.into().clock)
+    }
+
+    /// Returns a `Clock` iterator.
+    pub fn iter(&self) -> Iter<'a, A, E> {
+        self.clock.iter()
+    }
+
+    /// Returns the events in the clock that are not in `other`. See
+    /// `Clock::subtracted`.
+    pub fn subtracted<'b>(
+        &self,
+        other: impl Into<ClockView<'b, A, E>>,
+    ) -> HashMap<A, Vec<u64>>
+    where
+        A: 'b,
+        E: 'b,
+    {
+        self.clock.subtracted(other.into().clock)
+    }
+
+    /// Returns the number of events in the clock that are not in `other`. See
+    /// `Clock::missing_count`.
+    pub fn missing_count<'b>(
+        &self,
+        other: impl Into<ClockView<'b, A, E>>,
+    ) -> u64
+    where
+        A: 'b,
+        E: 'b,
+    {
+        self.clock.missing_count(other.into().clock)
+    }
+
+    /// See `Clock::count_events_in_range`.
+    pub fn count_events_in_range(&self, start: u64, end: u64) -> u64 {
+        self.clock.count_events_in_range(start, end)
+    }
+
+    /// See `Clock::snapshot`.
+    pub fn snapshot(&self) -> Vec<(A, u64, Vec<u64>)> {
+        self.clock.snapshot()
+    }
+
+    /// Returns an (owned) copy of the clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::new();
+    /// clock.next(&"A");
+    ///
+    /// let copy = clock.view().to_clock();
+    /// assert_eq!(copy, clock);
+    /// ```
+    pub fn to_clock(&self) -> Clock<A, E> {
+        self.clock.clone()
+    }
+}
+
+// Implemented by hand (instead of derived) so that `A` and `E` don't need to
+// be `Clone`/`Copy`.
+impl<A: Actor, E: EventSet> Clone for ClockView<'_, A, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Actor, E: EventSet> Copy for ClockView<'_, A, E> {}
+
+impl<'a, A: Actor, E: EventSet> From<&'a Clock<A, E>> for ClockView<'a, A, E> {
+    fn from(clock: &'a Clock<A, E>) -> Self {
+        ClockView { clock }
+    }
+}
+
+impl<A: Actor, E: EventSet> fmt::Debug for ClockView<'_, A, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.clock.fmt(f)
+    }
+}
+
+impl<A: Actor, E: EventSet> Clock<A, E> {
+    /// Returns a read-only view of the clock.
+    ///
+    /// # Examples
+    /// ```
+    /// use threshold::*;
+    ///
+    /// let mut clock = VClock::new();
+    /// clock.next(&"A");
+    ///
+    /// let view = clock.view();
+    /// assert!(view.contains(&"A", 1));
+    /// assert_eq!(view.frontier_of(&"A"), 1);
+    /// ```
+    pub fn view(&self) -> ClockView<'_, A, E> {
+        ClockView::from(self)
+    }
+}